@@ -0,0 +1,262 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Benchmarks for writing and reading encrypted Parquet files.
+//!
+//! Each benchmark group writes or reads the same data without encryption, with uniform
+//! encryption (all columns encrypted with the footer key) and with a separate key per column,
+//! so the overhead of encryption can be seen by comparing results within a group.
+//! Writes are also benchmarked with each column encoded and encrypted on its own thread.
+//! An estimate of the overhead as a percentage is also printed before the benchmarks run.
+
+use arrow::array::{ArrayRef, RecordBatch};
+use arrow::datatypes::{DataType, Field, Int64Type, Schema};
+use arrow::util::bench_util::{create_primitive_array, create_string_array};
+use bytes::Bytes;
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use parquet::arrow::arrow_reader::{ArrowReaderOptions, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::arrow_writer::{ArrowRowGroupWriterFactory, compute_leaves};
+use parquet::arrow::{ArrowSchemaConverter, ArrowWriter};
+use parquet::encryption::decrypt::FileDecryptionProperties;
+use parquet::encryption::encrypt::FileEncryptionProperties;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const FOOTER_KEY: &[u8] = b"0123456789012345";
+
+/// The sizes of files to benchmark, as (number of rows, number of columns)
+const FILE_SIZES: &[(usize, usize)] = &[(100_000, 4), (100_000, 16)];
+
+#[derive(Debug, Clone, Copy)]
+enum EncryptionMode {
+    None,
+    Uniform,
+    ColumnKeys,
+}
+
+impl EncryptionMode {
+    const ALL: [EncryptionMode; 3] = [Self::None, Self::Uniform, Self::ColumnKeys];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::None => "no_encryption",
+            Self::Uniform => "uniform_encryption",
+            Self::ColumnKeys => "column_keys",
+        }
+    }
+}
+
+fn column_name(i: usize) -> String {
+    format!("col_{i}")
+}
+
+/// A 16 byte key for each column
+fn column_key(i: usize) -> Vec<u8> {
+    format!("{i:016}").into_bytes()
+}
+
+fn create_batch(num_rows: usize, num_columns: usize) -> RecordBatch {
+    let fields: Vec<_> = (0..num_columns)
+        .map(|i| {
+            let data_type = match i % 2 {
+                0 => DataType::Int64,
+                _ => DataType::Utf8,
+            };
+            Field::new(column_name(i), data_type, true)
+        })
+        .collect();
+    let columns: Vec<ArrayRef> = (0..num_columns)
+        .map(|i| match i % 2 {
+            0 => Arc::new(create_primitive_array::<Int64Type>(num_rows, 0.1)) as _,
+            _ => Arc::new(create_string_array::<i32>(num_rows, 0.1)) as _,
+        })
+        .collect();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap()
+}
+
+fn writer_properties(mode: EncryptionMode, num_columns: usize) -> WriterProperties {
+    let builder = WriterProperties::builder();
+    let encryption_properties = match mode {
+        EncryptionMode::None => return builder.build(),
+        EncryptionMode::Uniform => {
+            FileEncryptionProperties::builder(FOOTER_KEY.to_vec()).with_uniform_encryption()
+        }
+        EncryptionMode::ColumnKeys => (0..num_columns).fold(
+            FileEncryptionProperties::builder(FOOTER_KEY.to_vec()),
+            |builder, i| builder.with_column_key(&column_name(i), column_key(i)),
+        ),
+    };
+    builder
+        .with_file_encryption_properties(encryption_properties.build().unwrap())
+        .build()
+}
+
+fn reader_options(mode: EncryptionMode, num_columns: usize) -> ArrowReaderOptions {
+    let builder = FileDecryptionProperties::builder(FOOTER_KEY.to_vec());
+    let decryption_properties = match mode {
+        EncryptionMode::None => return ArrowReaderOptions::new(),
+        EncryptionMode::Uniform => builder,
+        EncryptionMode::ColumnKeys => (0..num_columns).fold(builder, |builder, i| {
+            builder.with_column_key(&column_name(i), column_key(i))
+        }),
+    };
+    ArrowReaderOptions::new()
+        .with_file_decryption_properties(decryption_properties.build().unwrap())
+}
+
+fn write_file(batch: &RecordBatch, props: WriterProperties) -> Bytes {
+    let mut buffer = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(props)).unwrap();
+    writer.write(batch).unwrap();
+    writer.close().unwrap();
+    Bytes::from(buffer)
+}
+
+/// Write a single row group, encoding and encrypting each column on its own thread
+fn write_file_parallel(batch: &RecordBatch, props: WriterProperties) -> Bytes {
+    let schema = batch.schema();
+    let parquet_schema = ArrowSchemaConverter::new().convert(&schema).unwrap();
+    let mut buffer = Vec::new();
+    let mut writer = SerializedFileWriter::new(
+        &mut buffer,
+        parquet_schema.root_schema_ptr(),
+        Arc::new(props),
+    )
+    .unwrap();
+    let factory = ArrowRowGroupWriterFactory::new(&writer, Arc::clone(&schema));
+    let column_writers = factory.create_column_writers(0).unwrap();
+    let chunks: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = column_writers
+            .into_iter()
+            .zip(batch.columns().iter().zip(schema.fields()))
+            .map(|(mut column_writer, (array, field))| {
+                scope.spawn(move || {
+                    for leaves in compute_leaves(field, array).unwrap() {
+                        column_writer.write(&leaves).unwrap();
+                    }
+                    column_writer.close().unwrap()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    let mut row_group_writer = writer.next_row_group().unwrap();
+    for chunk in chunks {
+        chunk.append_to_row_group(&mut row_group_writer).unwrap();
+    }
+    row_group_writer.close().unwrap();
+    writer.close().unwrap();
+    Bytes::from(buffer)
+}
+
+fn read_file(file: Bytes, options: ArrowReaderOptions) -> usize {
+    let reader = ParquetRecordBatchReaderBuilder::try_new_with_options(file, options)
+        .unwrap()
+        .build()
+        .unwrap();
+    reader.map(|batch| batch.unwrap().num_rows()).sum()
+}
+
+/// Time a function over a few iterations, to estimate the overhead of encryption
+fn mean_duration(mut f: impl FnMut()) -> Duration {
+    const ITERATIONS: u32 = 5;
+    f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn report_overhead(batch: &RecordBatch, files: &[(EncryptionMode, Bytes)]) {
+    let num_columns = batch.num_columns();
+    let mut baseline = None;
+    for (mode, file) in files {
+        let write = mean_duration(|| {
+            black_box(write_file(batch, writer_properties(*mode, num_columns)));
+        });
+        let read = mean_duration(|| {
+            black_box(read_file(file.clone(), reader_options(*mode, num_columns)));
+        });
+        let (base_write, base_read) = *baseline.get_or_insert((write, read));
+        let overhead = |time: Duration, base: Duration| {
+            100.0 * (time.as_secs_f64() / base.as_secs_f64() - 1.0)
+        };
+        println!(
+            "{} rows x {} columns, {}: write overhead {:+.1}%, read overhead {:+.1}%",
+            batch.num_rows(),
+            num_columns,
+            mode.name(),
+            overhead(write, base_write),
+            overhead(read, base_read),
+        );
+    }
+}
+
+fn encryption_benchmark(c: &mut Criterion) {
+    for &(num_rows, num_columns) in FILE_SIZES {
+        let batch = create_batch(num_rows, num_columns);
+        let files: Vec<_> = EncryptionMode::ALL
+            .iter()
+            .map(|mode| {
+                (
+                    *mode,
+                    write_file(&batch, writer_properties(*mode, num_columns)),
+                )
+            })
+            .collect();
+        report_overhead(&batch, &files);
+
+        let mut group = c.benchmark_group(format!("write {num_rows} rows x {num_columns} columns"));
+        group.throughput(Throughput::Bytes(batch.get_array_memory_size() as u64));
+        for (mode, _) in &files {
+            group.bench_function(mode.name(), |b| {
+                b.iter(|| write_file(&batch, writer_properties(*mode, num_columns)))
+            });
+        }
+        group.finish();
+
+        let mut group = c.benchmark_group(format!(
+            "parallel write {num_rows} rows x {num_columns} columns"
+        ));
+        group.throughput(Throughput::Bytes(batch.get_array_memory_size() as u64));
+        for (mode, _) in &files {
+            group.bench_function(mode.name(), |b| {
+                b.iter(|| write_file_parallel(&batch, writer_properties(*mode, num_columns)))
+            });
+        }
+        group.finish();
+
+        let mut group = c.benchmark_group(format!("read {num_rows} rows x {num_columns} columns"));
+        for (mode, file) in &files {
+            group.throughput(Throughput::Bytes(file.len() as u64));
+            group.bench_function(mode.name(), |b| {
+                b.iter(|| {
+                    let rows = read_file(file.clone(), reader_options(*mode, num_columns));
+                    assert_eq!(rows, num_rows);
+                })
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, encryption_benchmark);
+criterion_main!(benches);
@@ -38,7 +38,7 @@ use crate::bloom_filter::{
 };
 use crate::column::page::{PageIterator, PageReader};
 #[cfg(feature = "encryption")]
-use crate::encryption::decrypt::FileDecryptionProperties;
+use crate::encryption::decrypt::{CryptoContext, FileDecryptionProperties};
 use crate::errors::{ParquetError, Result};
 use crate::file::metadata::{
     PageIndexPolicy, ParquetMetaData, ParquetMetaDataOptions, ParquetMetaDataReader,
@@ -873,6 +873,10 @@ impl ArrowReaderMetadata {
     /// If `options` has [`ArrowReaderOptions::with_page_index`] true, but
     /// `Self::metadata` is missing the page index, this function will attempt
     /// to load the page index by making an object store request.
+    ///
+    /// To cheaply read the schema and row counts of an encrypted file with only the
+    /// footer key, use decryption properties built with
+    /// [`with_footer_only_decryption`](crate::encryption::decrypt::DecryptionPropertiesBuilder::with_footer_only_decryption).
     pub fn load<T: ChunkReader>(reader: &T, options: ArrowReaderOptions) -> Result<Self> {
         let metadata = ParquetMetaDataReader::new()
             .with_column_index_policy(options.column_index)
@@ -1006,6 +1010,26 @@ impl ArrowReaderMetadata {
     pub fn schema(&self) -> &SchemaRef {
         &self.schema
     }
+
+    /// Returns a map from the path of each leaf column to whether it is encrypted.
+    ///
+    /// A column is considered encrypted if any of its column chunks has crypto metadata,
+    /// so columns of a file without row groups are reported as not encrypted.
+    #[cfg(feature = "encryption")]
+    pub fn encryption_map(&self) -> std::collections::HashMap<String, bool> {
+        let mut encrypted = vec![false; self.parquet_schema().num_columns()];
+        for row_group in self.metadata.row_groups() {
+            for (i, column) in row_group.columns().iter().enumerate() {
+                encrypted[i] |= column.crypto_metadata().is_some();
+            }
+        }
+        self.parquet_schema()
+            .columns()
+            .iter()
+            .zip(encrypted)
+            .map(|(column, encrypted)| (column.path().string(), encrypted))
+            .collect()
+    }
 }
 
 #[doc(hidden)]
@@ -1133,6 +1157,20 @@ impl<T: ChunkReader + 'static> ParquetRecordBatchReaderBuilder<T> {
             return Ok(None);
         };
 
+        #[cfg(feature = "encryption")]
+        if let Some(crypto_context) = CryptoContext::for_column_chunk(
+            self.metadata.file_decryptor(),
+            column_metadata,
+            row_group_idx,
+            column_idx,
+        )? {
+            return Sbbf::read_encrypted_from_column_chunk(
+                column_metadata,
+                &self.input.0,
+                &crypto_context,
+            );
+        }
+
         let buffer = match column_metadata.bloom_filter_length() {
             Some(length) => self.input.0.get_bytes(offset, length as usize),
             None => self.input.0.get_bytes(offset, SBBF_HEADER_SIZE_ESTIMATE),
@@ -1575,6 +1613,105 @@ impl ParquetRecordBatchReader {
     }
 }
 
+/// Read all record batches from the encrypted Parquet file at `path`, returning them
+/// with their schema.
+///
+/// This is a convenience for reading a whole file in one call. See
+/// [`read_encrypted_parquet_with`] to also configure the reader, for example to set a
+/// projection or batch size, or use a [`ParquetRecordBatchReaderBuilder`] directly for
+/// more control over how the file is read.
+///
+/// # Example
+/// ```
+/// # use std::sync::Arc;
+/// # use arrow_array::{ArrayRef, Int32Array, RecordBatch};
+/// # use parquet::arrow::arrow_reader::read_encrypted_parquet;
+/// # use parquet::arrow::arrow_writer::write_encrypted_parquet;
+/// # use parquet::encryption::decrypt::FileDecryptionProperties;
+/// # use parquet::encryption::encrypt::FileEncryptionProperties;
+/// # let batch = RecordBatch::try_from_iter(vec![
+/// #     ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+/// # ])?;
+/// # let encryption_properties = FileEncryptionProperties::builder(b"0123456789012345".into())
+/// #     .with_uniform_encryption()
+/// #     .build()?;
+/// # let temp_dir = tempfile::TempDir::new()?;
+/// # let path = temp_dir.path().join("encrypted.parquet");
+/// # write_encrypted_parquet(&path, batch.schema(), &[batch.clone()], encryption_properties)?;
+/// let decryption_properties =
+///     FileDecryptionProperties::builder(b"0123456789012345".into()).build()?;
+/// let (schema, batches) = read_encrypted_parquet(&path, decryption_properties)?;
+/// assert_eq!(schema, batch.schema());
+/// assert_eq!(batches, vec![batch]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "encryption")]
+pub fn read_encrypted_parquet<P: AsRef<std::path::Path>>(
+    path: P,
+    decryption_properties: Arc<FileDecryptionProperties>,
+) -> Result<(SchemaRef, Vec<RecordBatch>)> {
+    read_encrypted_parquet_with(path, decryption_properties, |builder| builder)
+}
+
+/// Read all record batches from the encrypted Parquet file at `path`, returning them
+/// with their schema, after configuring the reader with `configure`.
+///
+/// The returned schema is that of the batches read, so reflects any projection.
+///
+/// # Example
+/// ```
+/// # use std::sync::Arc;
+/// # use arrow_array::{ArrayRef, Int32Array, RecordBatch};
+/// # use parquet::arrow::ProjectionMask;
+/// # use parquet::arrow::arrow_reader::read_encrypted_parquet_with;
+/// # use parquet::arrow::arrow_writer::write_encrypted_parquet;
+/// # use parquet::encryption::decrypt::FileDecryptionProperties;
+/// # use parquet::encryption::encrypt::FileEncryptionProperties;
+/// # let batch = RecordBatch::try_from_iter(vec![
+/// #     ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+/// #     ("value", Arc::new(Int32Array::from(vec![4, 5, 6])) as ArrayRef),
+/// # ])?;
+/// # let encryption_properties = FileEncryptionProperties::builder(b"0123456789012345".into())
+/// #     .with_uniform_encryption()
+/// #     .build()?;
+/// # let temp_dir = tempfile::TempDir::new()?;
+/// # let path = temp_dir.path().join("encrypted.parquet");
+/// # write_encrypted_parquet(&path, batch.schema(), &[batch], encryption_properties)?;
+/// let decryption_properties =
+///     FileDecryptionProperties::builder(b"0123456789012345".into()).build()?;
+/// // Read only the "value" column, two rows at a time
+/// let (schema, batches) = read_encrypted_parquet_with(&path, decryption_properties, |builder| {
+///     let mask = ProjectionMask::columns(builder.parquet_schema(), ["value"]);
+///     builder.with_projection(mask).with_batch_size(2)
+/// })?;
+/// assert_eq!(schema.fields().len(), 1);
+/// assert_eq!(batches.len(), 2);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "encryption")]
+pub fn read_encrypted_parquet_with<P, F>(
+    path: P,
+    decryption_properties: Arc<FileDecryptionProperties>,
+    configure: F,
+) -> Result<(SchemaRef, Vec<RecordBatch>)>
+where
+    P: AsRef<std::path::Path>,
+    F: FnOnce(
+        ParquetRecordBatchReaderBuilder<std::fs::File>,
+    ) -> ParquetRecordBatchReaderBuilder<std::fs::File>,
+{
+    let file = std::fs::File::open(path)?;
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let builder = ParquetRecordBatchReaderBuilder::try_new_with_options(file, options)?;
+    let reader = configure(builder).build()?;
+    let schema = reader.schema();
+    let batches = reader
+        .map(|batch| batch.map_err(ParquetError::from))
+        .collect::<Result<_>>()?;
+    Ok((schema, batches))
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use std::cmp::min;
@@ -21,6 +21,7 @@ use crate::column::chunker::ContentDefinedChunker;
 
 use bytes::Bytes;
 use std::io::{Read, Write};
+use std::ops::Range;
 use std::slice::Iter;
 use std::sync::{Arc, Mutex};
 use std::vec::IntoIter;
@@ -45,7 +46,7 @@ use crate::column::writer::{
 };
 use crate::data_type::{ByteArray, FixedLenByteArray};
 #[cfg(feature = "encryption")]
-use crate::encryption::encrypt::FileEncryptor;
+use crate::encryption::encrypt::{FileEncryptionProperties, FileEncryptor};
 use crate::errors::{ParquetError, Result};
 use crate::file::metadata::{KeyValue, ParquetMetaData, RowGroupMetaData};
 use crate::file::properties::{WriterProperties, WriterPropertiesPtr};
@@ -344,6 +345,14 @@ impl<W: Write + Send> ArrowWriter<W> {
         self.writer.bytes_written()
     }
 
+    /// Returns the byte range of the serialized file metadata, or `None` if the
+    /// writer has not been finished with [`Self::finish`].
+    ///
+    /// See [`SerializedFileWriter::footer_range`] for details.
+    pub fn footer_range(&self) -> Option<Range<u64>> {
+        self.writer.footer_range()
+    }
+
     /// Encodes the provided [`RecordBatch`]
     ///
     /// If this would cause the current row group to exceed [`WriterProperties::max_row_group_row_count`]
@@ -556,6 +565,60 @@ impl<W: Write + Send> RecordBatchWriter for ArrowWriter<W> {
     }
 }
 
+/// Write record batches to a new encrypted Parquet file at `path`, replacing any existing file.
+///
+/// This is a convenience for writing a whole file in one call using default
+/// [`WriterProperties`] other than the file encryption properties.
+/// Use an [`ArrowWriter`] directly for more control over how the file is written.
+///
+/// # Example
+/// ```
+/// # use std::sync::Arc;
+/// # use arrow_array::{ArrayRef, Int32Array, RecordBatch};
+/// # use parquet::arrow::arrow_writer::write_encrypted_parquet;
+/// # use parquet::encryption::encrypt::FileEncryptionProperties;
+/// let batch = RecordBatch::try_from_iter(vec![
+///     ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+/// ])?;
+/// let encryption_properties = FileEncryptionProperties::builder(b"0123456789012345".into())
+///     .with_uniform_encryption()
+///     .build()?;
+///
+/// let temp_dir = tempfile::TempDir::new()?;
+/// let path = temp_dir.path().join("encrypted.parquet");
+/// write_encrypted_parquet(&path, batch.schema(), &[batch], encryption_properties)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "encryption")]
+pub fn write_encrypted_parquet<P: AsRef<std::path::Path>>(
+    path: P,
+    schema: SchemaRef,
+    batches: &[RecordBatch],
+    encryption_properties: Arc<FileEncryptionProperties>,
+) -> Result<()> {
+    let file = std::fs::File::create(path)?;
+    write_encrypted_batches(file, schema, batches, encryption_properties)?;
+    Ok(())
+}
+
+/// Write record batches to an encrypted Parquet file in `writer`, returning the writer
+#[cfg(feature = "encryption")]
+pub(crate) fn write_encrypted_batches<W: Write + Send>(
+    writer: W,
+    schema: SchemaRef,
+    batches: &[RecordBatch],
+    encryption_properties: Arc<FileEncryptionProperties>,
+) -> Result<W> {
+    let writer_properties = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+    let mut writer = ArrowWriter::try_new(writer, schema, Some(writer_properties))?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.into_inner()
+}
+
 /// Arrow-specific configuration settings for writing parquet files.
 ///
 /// See [`ArrowWriter`] for how to configure the writer.
@@ -931,6 +994,9 @@ impl PageWriter for ArrowPageWriter {
     }
 
     fn close(&mut self) -> Result<()> {
+        if let Some(page_encryptor) = self.page_encryptor_mut() {
+            page_encryptor.finish();
+        }
         Ok(())
     }
 }
@@ -1425,12 +1491,11 @@ impl ArrowColumnWriterFactory {
         column_descriptor: &ColumnDescPtr,
         column_index: usize,
     ) -> Result<Box<ArrowPageWriter>> {
-        let column_path = column_descriptor.path().string();
         let page_encryptor = PageEncryptor::create_if_column_encrypted(
             &self.file_encryptor,
             self.row_group_index,
             column_index,
-            &column_path,
+            column_descriptor.path(),
         )?;
         let args = PageStoreArgs::new(column_index, column_descriptor);
         let store = self.page_store_factory.create(&args)?;
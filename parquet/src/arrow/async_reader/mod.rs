@@ -41,9 +41,13 @@ use crate::arrow::arrow_reader::{
 };
 
 use crate::basic::{BloomFilterAlgorithm, BloomFilterCompression, BloomFilterHash};
+#[cfg(feature = "encryption")]
+use crate::bloom_filter::encrypted_module_length;
 use crate::bloom_filter::{
     SBBF_HEADER_SIZE_ESTIMATE, Sbbf, chunk_read_bloom_filter_header_and_offset,
 };
+#[cfg(feature = "encryption")]
+use crate::encryption::{ciphers::SIZE_LEN, decrypt::CryptoContext};
 use crate::errors::{ParquetError, Result};
 use crate::file::metadata::{ParquetMetaData, ParquetMetaDataReader};
 
@@ -524,6 +528,38 @@ impl<T: AsyncFileReader + Send + 'static> ParquetRecordBatchStreamBuilder<T> {
             return Ok(None);
         };
 
+        #[cfg(feature = "encryption")]
+        if let Some(crypto_context) = CryptoContext::for_column_chunk(
+            self.metadata.file_decryptor(),
+            column_metadata,
+            row_group_idx,
+            column_idx,
+        )? {
+            let length: u64 = match column_metadata.bloom_filter_length() {
+                Some(length) => length.try_into().map_err(|_| {
+                    ParquetError::General("Bloom filter length is invalid".to_string())
+                })?,
+                None => {
+                    // Read the length prefixes of the header and bitset modules
+                    let size_len = SIZE_LEN as u64;
+                    let header_length = encrypted_module_length(
+                        &self.input.0.get_bytes(offset..offset + size_len).await?,
+                    )? as u64;
+                    let bitset_offset = offset + header_length;
+                    let bitset_length = encrypted_module_length(
+                        &self
+                            .input
+                            .0
+                            .get_bytes(bitset_offset..bitset_offset + size_len)
+                            .await?,
+                    )? as u64;
+                    header_length + bitset_length
+                }
+            };
+            let buffer = self.input.0.get_bytes(offset..offset + length).await?;
+            return Sbbf::decrypt(&buffer, &crypto_context).map(Some);
+        }
+
         let buffer = match column_metadata.bloom_filter_length() {
             Some(length) => self.input.0.get_bytes(offset..offset + length as u64),
             None => self
@@ -75,6 +75,7 @@ use bytes::Bytes;
 use futures::FutureExt;
 use futures::future::BoxFuture;
 use std::mem;
+use std::ops::Range;
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 /// The asynchronous interface used by [`AsyncArrowWriter`] to write parquet files.
@@ -212,6 +213,14 @@ impl<W: AsyncFileWriter> AsyncArrowWriter<W> {
         self.sync_writer.bytes_written()
     }
 
+    /// Returns the byte range of the serialized file metadata, or `None` if the
+    /// writer has not been finished with [`Self::finish`].
+    ///
+    /// See [`ArrowWriter::footer_range`] for details.
+    pub fn footer_range(&self) -> Option<Range<u64>> {
+        self.sync_writer.footer_range()
+    }
+
     /// Enqueues the provided `RecordBatch` to be written
     ///
     /// After every sync write by the inner [ArrowWriter], the inner buffer will be
@@ -114,6 +114,13 @@
 
 use crate::basic::{BloomFilterAlgorithm, BloomFilterCompression, BloomFilterHash};
 use crate::data_type::AsBytes;
+#[cfg(feature = "encryption")]
+use crate::encryption::{
+    ciphers::{BlockEncryptor, NONCE_LEN, SIZE_LEN, TAG_LEN},
+    decrypt::CryptoContext,
+    encrypt::encrypt_thrift_object,
+    modules::ModuleType,
+};
 use crate::errors::{ParquetError, Result};
 use crate::file::metadata::ColumnChunkMetaData;
 use crate::file::reader::ChunkReader;
@@ -354,6 +361,27 @@ fn read_bloom_filter_header_and_length_from_bytes(
     Ok((header, (total_length - prot.as_slice().len()) as u64))
 }
 
+/// Get the total length of an encrypted module, including its length prefix,
+/// from the start of the module.
+///
+/// The length prefix is not authenticated, so lengths that could not hold a
+/// bitset of at most [BITSET_MAX_LENGTH] bytes are rejected before any data is read.
+#[cfg(feature = "encryption")]
+pub(crate) fn encrypted_module_length(buffer: &[u8]) -> Result<usize, ParquetError> {
+    let length_prefix = buffer
+        .get(..SIZE_LEN)
+        .ok_or_else(|| general_err!("Encrypted bloom filter is too short"))?;
+    let length = u32::from_le_bytes(length_prefix.try_into().unwrap()) as usize;
+    if length > NONCE_LEN + BITSET_MAX_LENGTH + TAG_LEN {
+        return Err(general_err!(
+            "Encrypted bloom filter module length {} exceeds the maximum of {}",
+            length,
+            NONCE_LEN + BITSET_MAX_LENGTH + TAG_LEN
+        ));
+    }
+    Ok(SIZE_LEN + length)
+}
+
 /// The minimum number of bytes for a bloom filter bitset.
 pub const BITSET_MIN_LENGTH: usize = 32;
 /// The maximum number of bytes for a bloom filter bitset.
@@ -456,6 +484,23 @@ impl Sbbf {
         Ok(())
     }
 
+    /// Write the bloom filter header and bitset to the output as separate encrypted modules,
+    /// as is required for encrypted columns.
+    #[cfg(feature = "encryption")]
+    pub(crate) fn write_encrypted<W: Write>(
+        &self,
+        mut writer: W,
+        encryptor: &mut Box<dyn BlockEncryptor>,
+        header_aad: &[u8],
+        bitset_aad: &[u8],
+    ) -> Result<(), ParquetError> {
+        encrypt_thrift_object(&self.header(), encryptor, &mut writer, header_aad)?;
+        let mut bitset = Vec::with_capacity(self.0.len() * size_of::<Block>());
+        self.write_bitset(&mut bitset)?;
+        writer.write_all(&encryptor.encrypt(&bitset, bitset_aad)?)?;
+        Ok(())
+    }
+
     /// Create and populate [`BloomFilterHeader`] from this bitset for writing to serialized form
     fn header(&self) -> BloomFilterHeader {
         BloomFilterHeader {
@@ -517,6 +562,86 @@ impl Sbbf {
         Ok(Some(Self::new(&bitset)))
     }
 
+    /// Read and decrypt the bloom filter of an encrypted column chunk from the given reader.
+    #[cfg(feature = "encryption")]
+    pub(crate) fn read_encrypted_from_column_chunk<R: ChunkReader>(
+        column_metadata: &ColumnChunkMetaData,
+        reader: &R,
+        crypto_context: &CryptoContext,
+    ) -> Result<Option<Self>, ParquetError> {
+        let offset: u64 = if let Some(offset) = column_metadata.bloom_filter_offset() {
+            offset
+                .try_into()
+                .map_err(|_| ParquetError::General("Bloom filter offset is invalid".to_string()))?
+        } else {
+            return Ok(None);
+        };
+
+        let length = match column_metadata.bloom_filter_length() {
+            Some(length) => length
+                .try_into()
+                .map_err(|_| ParquetError::General("Bloom filter length is invalid".to_string()))?,
+            None => {
+                // Read the length prefixes of the header and bitset modules
+                let header_length = encrypted_module_length(&reader.get_bytes(offset, SIZE_LEN)?)?;
+                let bitset_length = encrypted_module_length(
+                    &reader.get_bytes(offset + header_length as u64, SIZE_LEN)?,
+                )?;
+                header_length + bitset_length
+            }
+        };
+        if offset.saturating_add(length as u64) > reader.len() {
+            return Err(general_err!(
+                "Encrypted bloom filter of {} bytes at offset {} exceeds the file length {}",
+                length,
+                offset,
+                reader.len()
+            ));
+        }
+        let buffer = reader.get_bytes(offset, length)?;
+        Self::decrypt(&buffer, crypto_context).map(Some)
+    }
+
+    /// Decrypt a bloom filter from a buffer holding the encrypted header module
+    /// followed by the encrypted bitset module.
+    #[cfg(feature = "encryption")]
+    pub(crate) fn decrypt(
+        buffer: &[u8],
+        crypto_context: &CryptoContext,
+    ) -> Result<Self, ParquetError> {
+        let decryptor = crypto_context.metadata_decryptor();
+        let header_length = encrypted_module_length(buffer)?;
+        if header_length > buffer.len() {
+            return Err(general_err!(
+                "Encrypted bloom filter header length {} exceeds the bloom filter length {}",
+                header_length,
+                buffer.len()
+            ));
+        }
+        let (header_module, bitset_module) = buffer.split_at(header_length);
+
+        crypto_context.record_key_usage(ModuleType::BloomFilterHeader);
+        let header = decryptor.decrypt(
+            header_module,
+            &crypto_context.create_bloom_filter_header_aad()?,
+        )?;
+        let (header, _) = read_bloom_filter_header_and_length_from_bytes(&header)?;
+
+        crypto_context.record_key_usage(ModuleType::BloomFilterBitset);
+        let bitset = decryptor.decrypt(
+            bitset_module,
+            &crypto_context.create_bloom_filter_bitset_aad()?,
+        )?;
+        if usize::try_from(header.num_bytes).ok() != Some(bitset.len()) {
+            return Err(general_err!(
+                "Bloom filter bitset length {} does not match the header length {}",
+                bitset.len(),
+                header.num_bytes
+            ));
+        }
+        Ok(Self::new(&bitset))
+    }
+
     /// Map a 64-bit hash to a block index in `[0, num_blocks)`.
     ///
     /// Uses the "multiply-and-shift" trick (a fast alternative to modulo):
@@ -17,12 +17,14 @@
 
 use crate::basic::PageType;
 use crate::column::page::CompressedPage;
-use crate::encryption::ciphers::BlockEncryptor;
+use crate::encryption::ciphers::{BlockEncryptor, MAX_PLAINTEXT_LEN};
 use crate::encryption::encrypt::{FileEncryptor, encrypt_thrift_object};
+use crate::encryption::metrics::EncryptionMetrics;
 use crate::encryption::modules::{ModuleType, create_module_aad};
 use crate::errors::ParquetError;
 use crate::errors::Result;
 use crate::file::metadata::thrift::PageHeader;
+use crate::schema::types::ColumnPath;
 use bytes::Bytes;
 use std::io::Write;
 use std::sync::Arc;
@@ -31,10 +33,13 @@ use std::sync::Arc;
 /// Encrypts page headers and page data for columns
 pub(crate) struct PageEncryptor {
     file_encryptor: Arc<FileEncryptor>,
-    block_encryptor: Box<dyn BlockEncryptor>,
+    data_encryptor: Box<dyn BlockEncryptor>,
+    metadata_encryptor: Box<dyn BlockEncryptor>,
     row_group_index: usize,
     column_index: usize,
     page_index: usize,
+    finished: bool,
+    metrics: Option<(Arc<dyn EncryptionMetrics>, String)>,
 }
 
 impl PageEncryptor {
@@ -43,17 +48,24 @@ impl PageEncryptor {
         file_encryptor: &Option<Arc<FileEncryptor>>,
         row_group_index: usize,
         column_index: usize,
-        column_path: &str,
+        column_path: &ColumnPath,
     ) -> Result<Option<Self>> {
         match file_encryptor {
             Some(file_encryptor) if file_encryptor.is_column_encrypted(column_path) => {
-                let block_encryptor = file_encryptor.get_column_encryptor(column_path)?;
+                let data_encryptor = file_encryptor.get_column_data_encryptor(column_path)?;
+                let metadata_encryptor = file_encryptor.get_column_encryptor(column_path)?;
                 Ok(Some(Self {
                     file_encryptor: file_encryptor.clone(),
-                    block_encryptor,
+                    data_encryptor,
+                    metadata_encryptor,
                     row_group_index,
                     column_index,
                     page_index: 0,
+                    finished: false,
+                    metrics: file_encryptor
+                        .properties()
+                        .metrics()
+                        .map(|metrics| (Arc::clone(metrics), column_path.string())),
                 }))
             }
             _ => Ok(None),
@@ -65,7 +77,40 @@ impl PageEncryptor {
         self.page_index += 1;
     }
 
+    /// Mark the column chunk as complete, after which no more pages can be encrypted
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    fn check_page_size(&self, page_size: usize) -> Result<()> {
+        if page_size > MAX_PLAINTEXT_LEN {
+            return Err(general_err!(
+                "Cannot encrypt a page of {} bytes for column {} of row group {}, as it exceeds \
+                    the maximum of {} bytes that can be safely encrypted. Write smaller pages, \
+                    for example by lowering the data page size limit",
+                page_size,
+                self.column_index,
+                self.row_group_index,
+                MAX_PLAINTEXT_LEN
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_not_finished(&self) -> Result<()> {
+        if self.finished {
+            return Err(general_err!(
+                "Cannot encrypt a page for column {} of row group {} after the column chunk has been closed",
+                self.column_index,
+                self.row_group_index
+            ));
+        }
+        Ok(())
+    }
+
     fn encrypt_page(&mut self, page: &CompressedPage) -> Result<Vec<u8>> {
+        self.check_not_finished()?;
+        self.check_page_size(page.data().len())?;
         let module_type = if page.compressed_page().is_data_page() {
             ModuleType::DataPage
         } else {
@@ -78,7 +123,10 @@ impl PageEncryptor {
             self.column_index,
             Some(self.page_index),
         )?;
-        let encrypted_buffer = self.block_encryptor.encrypt(page.data(), &aad)?;
+        let encrypted_buffer = self.data_encryptor.encrypt(page.data(), &aad)?;
+        if let Some((metrics, column_path)) = &self.metrics {
+            metrics.page_encrypted(column_path, page.data().len());
+        }
 
         Ok(encrypted_buffer)
     }
@@ -95,6 +143,7 @@ impl PageEncryptor {
         page_header: &PageHeader,
         sink: &mut W,
     ) -> Result<()> {
+        self.check_not_finished()?;
         let module_type = match page_header.r#type {
             PageType::DATA_PAGE => ModuleType::DataPageHeader,
             PageType::DATA_PAGE_V2 => ModuleType::DataPageHeader,
@@ -114,6 +163,320 @@ impl PageEncryptor {
             Some(self.page_index),
         )?;
 
-        encrypt_thrift_object(page_header, &mut self.block_encryptor, sink, &aad)
+        encrypt_thrift_object(page_header, &mut self.metadata_encryptor, sink, &aad)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic::Encoding;
+    use crate::column::page::Page;
+    use crate::encryption::backend::{AeadBackend, RingAeadBackend};
+    use crate::encryption::encrypt::{EncryptionAlgorithm, FileEncryptionProperties};
+    use crate::util::test_common::assert_send_sync;
+
+    #[test]
+    fn test_page_encryptor_send_sync() {
+        // Each column writer owns a page encryptor and may be moved to another thread
+        assert_send_sync::<PageEncryptor>();
+    }
+
+    #[test]
+    fn test_page_size_limit() {
+        let properties = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_uniform_encryption()
+            .build()
+            .unwrap();
+        let file_encryptor = Arc::new(FileEncryptor::new(properties).unwrap());
+        let page_encryptor = PageEncryptor::create_if_column_encrypted(
+            &Some(file_encryptor),
+            1,
+            2,
+            &ColumnPath::from("a"),
+        )
+        .unwrap()
+        .unwrap();
+
+        // Avoid allocating a page of over 4 GiB by checking the page size directly
+        page_encryptor.check_page_size(MAX_PLAINTEXT_LEN).unwrap();
+        let err = page_encryptor
+            .check_page_size(MAX_PLAINTEXT_LEN + 1)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains(&format!(
+                "Cannot encrypt a page of {} bytes for column 2 of row group 1",
+                MAX_PLAINTEXT_LEN + 1
+            )),
+            "{err}"
+        );
+        assert!(err.contains("Write smaller pages"), "{err}");
+    }
+
+    #[test]
+    fn test_overhead_per_page() {
+        let key = b"0123456789012345".to_vec();
+        let page = Page::DataPage {
+            buf: Bytes::from(vec![7; 100]),
+            num_values: 100,
+            encoding: Encoding::PLAIN,
+            def_level_encoding: Encoding::RLE,
+            rep_level_encoding: Encoding::RLE,
+            statistics: None,
+        };
+
+        for (algorithm, expected_overhead) in [
+            (EncryptionAlgorithm::AesGcmV1, 64),
+            (EncryptionAlgorithm::AesGcmCtrV1, 48),
+        ] {
+            let properties = FileEncryptionProperties::builder(key.clone())
+                .with_uniform_encryption()
+                .with_algorithm(algorithm)
+                .build()
+                .unwrap();
+            let file_encryptor = Arc::new(FileEncryptor::new(properties).unwrap());
+            let mut page_encryptor = PageEncryptor::create_if_column_encrypted(
+                &Some(file_encryptor.clone()),
+                0,
+                0,
+                &ColumnPath::from("a"),
+            )
+            .unwrap()
+            .unwrap();
+
+            let page = CompressedPage::new(page.clone(), 100);
+            let plaintext_header = page.to_thrift_header().unwrap();
+            let mut header = Vec::new();
+            page_encryptor
+                .encrypt_page_header(&plaintext_header, &mut header)
+                .unwrap();
+            let encrypted_page = page_encryptor.encrypt_compressed_page(page).unwrap();
+
+            let aad = create_module_aad(
+                file_encryptor.file_aad(),
+                ModuleType::DataPageHeader,
+                0,
+                0,
+                Some(0),
+            )
+            .unwrap();
+            let header_len = RingAeadBackend
+                .new_decryptor(&key)
+                .unwrap()
+                .decrypt(&header, &aad)
+                .unwrap()
+                .len();
+
+            assert_eq!(header.len() - header_len, algorithm.metadata_overhead());
+            assert_eq!(
+                encrypted_page.data().len() - 100,
+                algorithm.page_data_overhead()
+            );
+            assert_eq!(algorithm.overhead_per_page(), expected_overhead);
+        }
+    }
+
+    #[test]
+    fn test_page_module_types() {
+        let key = b"0123456789012345".to_vec();
+        let properties = FileEncryptionProperties::builder(key.clone())
+            .with_uniform_encryption()
+            .build()
+            .unwrap();
+        let file_encryptor = Arc::new(FileEncryptor::new(properties).unwrap());
+        let decryptor = RingAeadBackend.new_decryptor(&key).unwrap();
+        let buf = Bytes::from_static(b"abc");
+
+        let pages = [
+            (
+                Page::DataPage {
+                    buf: buf.clone(),
+                    num_values: 3,
+                    encoding: Encoding::PLAIN,
+                    def_level_encoding: Encoding::RLE,
+                    rep_level_encoding: Encoding::RLE,
+                    statistics: None,
+                },
+                ModuleType::DataPageHeader,
+                ModuleType::DataPage,
+            ),
+            (
+                Page::DataPageV2 {
+                    buf: buf.clone(),
+                    num_values: 3,
+                    encoding: Encoding::PLAIN,
+                    num_nulls: 0,
+                    num_rows: 3,
+                    def_levels_byte_len: 0,
+                    rep_levels_byte_len: 0,
+                    is_compressed: false,
+                    statistics: None,
+                },
+                ModuleType::DataPageHeader,
+                ModuleType::DataPage,
+            ),
+            (
+                Page::DictionaryPage {
+                    buf: buf.clone(),
+                    num_values: 3,
+                    encoding: Encoding::PLAIN,
+                    is_sorted: false,
+                },
+                ModuleType::DictionaryPageHeader,
+                ModuleType::DictionaryPage,
+            ),
+        ];
+
+        let aad = |module_type| {
+            create_module_aad(file_encryptor.file_aad(), module_type, 1, 2, Some(3)).unwrap()
+        };
+        for (page, header_module_type, page_module_type) in pages {
+            let page_type = page.page_type();
+            let mut page_encryptor = PageEncryptor::create_if_column_encrypted(
+                &Some(file_encryptor.clone()),
+                1,
+                2,
+                &ColumnPath::from("a"),
+            )
+            .unwrap()
+            .unwrap();
+            page_encryptor.page_index = 3;
+
+            let page = CompressedPage::new(page, buf.len());
+            let mut header = Vec::new();
+            page_encryptor
+                .encrypt_page_header(&page.to_thrift_header().unwrap(), &mut header)
+                .unwrap();
+            let page = page_encryptor.encrypt_compressed_page(page).unwrap();
+
+            for module_type in [ModuleType::DataPageHeader, ModuleType::DictionaryPageHeader] {
+                let result = decryptor.decrypt(&header, &aad(module_type));
+                assert_eq!(
+                    result.is_ok(),
+                    module_type == header_module_type,
+                    "{page_type:?} header decrypted with {module_type:?}"
+                );
+            }
+            for module_type in [ModuleType::DataPage, ModuleType::DictionaryPage] {
+                let result = decryptor.decrypt(page.data(), &aad(module_type));
+                if module_type == page_module_type {
+                    assert_eq!(result.unwrap(), b"abc");
+                } else {
+                    assert!(
+                        result.is_err(),
+                        "{page_type:?} page decrypted with {module_type:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_plaintext_block_cipher() {
+        use crate::encryption::backend::{
+            BlockDecryptor, PlaintextBlockDecryptor, PlaintextBlockEncryptor,
+        };
+
+        #[derive(Debug)]
+        struct PlaintextAeadBackend;
+
+        impl AeadBackend for PlaintextAeadBackend {
+            fn new_encryptor(&self, _key: &[u8]) -> Result<Box<dyn BlockEncryptor>> {
+                Ok(Box::new(PlaintextBlockEncryptor))
+            }
+
+            fn new_decryptor(&self, _key: &[u8]) -> Result<Arc<dyn BlockDecryptor>> {
+                Ok(Arc::new(PlaintextBlockDecryptor))
+            }
+        }
+
+        let properties = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_uniform_encryption()
+            .with_aead_backend(Arc::new(PlaintextAeadBackend))
+            .build()
+            .unwrap();
+        let file_encryptor = Arc::new(FileEncryptor::new(properties).unwrap());
+        let mut page_encryptor = PageEncryptor::create_if_column_encrypted(
+            &Some(file_encryptor),
+            0,
+            0,
+            &ColumnPath::from("a"),
+        )
+        .unwrap()
+        .unwrap();
+
+        let page = CompressedPage::new(
+            Page::DataPage {
+                buf: Bytes::from_static(b"abc"),
+                num_values: 3,
+                encoding: Encoding::PLAIN,
+                def_level_encoding: Encoding::RLE,
+                rep_level_encoding: Encoding::RLE,
+                statistics: None,
+            },
+            3,
+        );
+        let mut header = Vec::new();
+        page_encryptor
+            .encrypt_page_header(&page.to_thrift_header().unwrap(), &mut header)
+            .unwrap();
+        let page = page_encryptor.encrypt_compressed_page(page).unwrap();
+
+        // Modules only gain a length prefix
+        assert_eq!(page.data(), b"\x03\x00\x00\x00abc");
+        let decryptor = PlaintextBlockDecryptor;
+        assert_eq!(decryptor.decrypt(page.data(), b"").unwrap(), b"abc");
+        let header_len = decryptor.decrypt(&header, b"").unwrap().len();
+        assert_eq!(header.len(), header_len + 4);
+    }
+
+    #[test]
+    fn test_encrypt_after_finish() {
+        let properties = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_uniform_encryption()
+            .build()
+            .unwrap();
+        let file_encryptor = Arc::new(FileEncryptor::new(properties).unwrap());
+        let mut page_encryptor = PageEncryptor::create_if_column_encrypted(
+            &Some(file_encryptor),
+            1,
+            2,
+            &ColumnPath::from("a"),
+        )
+        .unwrap()
+        .unwrap();
+        let new_page = || {
+            CompressedPage::new(
+                Page::DataPage {
+                    buf: Bytes::from_static(b"abc"),
+                    num_values: 3,
+                    encoding: Encoding::PLAIN,
+                    def_level_encoding: Encoding::RLE,
+                    rep_level_encoding: Encoding::RLE,
+                    statistics: None,
+                },
+                3,
+            )
+        };
+        let header = new_page().to_thrift_header().unwrap();
+        page_encryptor
+            .encrypt_page_header(&header, &mut Vec::new())
+            .unwrap();
+        page_encryptor.encrypt_compressed_page(new_page()).unwrap();
+        page_encryptor.increment_page();
+
+        page_encryptor.finish();
+        let expected = "Parquet error: Cannot encrypt a page for column 2 of row group 1 \
+                        after the column chunk has been closed";
+        let err = page_encryptor
+            .encrypt_page_header(&header, &mut Vec::new())
+            .unwrap_err();
+        assert_eq!(err.to_string(), expected);
+        let err = page_encryptor
+            .encrypt_compressed_page(new_page())
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), expected);
     }
 }
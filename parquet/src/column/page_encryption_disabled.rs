@@ -30,6 +30,8 @@ pub(crate) struct PageEncryptor {
 impl PageEncryptor {
     pub fn increment_page(&mut self) {}
 
+    pub fn finish(&mut self) {}
+
     pub fn encrypt_compressed_page(&mut self, _page: CompressedPage) -> Result<CompressedPage> {
         unreachable!("The encryption feature is disabled")
     }
@@ -465,6 +465,8 @@ pub struct GenericColumnWriter<'a, E: ColumnValueEncoder> {
     data_page_boundary_descending: bool,
     /// (min, max)
     last_non_null_data_page_min_max: Option<(E::T, E::T)>,
+    /// Minimum estimated data page size before the row count limit ends a page
+    data_page_min_size: usize,
 }
 
 impl<'a, E: ColumnValueEncoder> GenericColumnWriter<'a, E> {
@@ -510,6 +512,8 @@ impl<'a, E: ColumnValueEncoder> GenericColumnWriter<'a, E> {
             _ => None,
         };
 
+        let data_page_min_size = Self::data_page_min_size(&descr, &props);
+
         Self {
             def_levels_encoder: Self::create_level_encoder(descr.max_def_level(), &props),
             rep_levels_encoder: Self::create_level_encoder(descr.max_rep_level(), &props),
@@ -530,9 +534,32 @@ impl<'a, E: ColumnValueEncoder> GenericColumnWriter<'a, E> {
             data_page_boundary_ascending: true,
             data_page_boundary_descending: true,
             last_non_null_data_page_min_max: None,
+            data_page_min_size,
         }
     }
 
+    /// Returns the minimum estimated size a data page must reach before the
+    /// row count limit may end it, which is only non-zero for encrypted columns
+    #[cfg(feature = "encryption")]
+    fn data_page_min_size(descr: &ColumnDescPtr, props: &WriterPropertiesPtr) -> usize {
+        match (
+            props.encrypted_data_page_min_size(),
+            props.file_encryption_properties.as_ref(),
+        ) {
+            (Some(min_size), Some(encryption_properties))
+                if get_column_crypto_metadata(encryption_properties, descr).is_some() =>
+            {
+                min_size
+            }
+            _ => 0,
+        }
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn data_page_min_size(_descr: &ColumnDescPtr, _props: &WriterPropertiesPtr) -> usize {
+        0
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn write_batch_internal(
         &mut self,
@@ -1041,9 +1068,10 @@ impl<'a, E: ColumnValueEncoder> GenericColumnWriter<'a, E> {
             return false;
         }
 
-        self.page_metrics.num_buffered_rows as usize >= self.props.data_page_row_count_limit()
-            || self.encoder.estimated_data_page_size()
-                >= self.props.column_data_page_size_limit(self.descr.path())
+        let estimated_page_size = self.encoder.estimated_data_page_size();
+        (self.page_metrics.num_buffered_rows as usize >= self.props.data_page_row_count_limit()
+            && estimated_page_size >= self.data_page_min_size)
+            || estimated_page_size >= self.props.column_data_page_size_limit(self.descr.path())
     }
 
     /// Performs dictionary fallback.
@@ -0,0 +1,169 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Pluggable implementations of the ciphers used for Parquet Modular Encryption.
+//!
+//! By default, encryption and decryption use the [`ring`] crate via [`RingAeadBackend`].
+//! Applications that need a different cryptography provider, for example a FIPS validated
+//! library or a hardware security module, can implement [`AeadBackend`] and set it with
+//! [`EncryptionPropertiesBuilder::with_aead_backend`] and
+//! [`DecryptionPropertiesBuilder::with_aead_backend`].
+//!
+//! [`ring`]: https://docs.rs/ring
+//! [`EncryptionPropertiesBuilder::with_aead_backend`]: crate::encryption::encrypt::EncryptionPropertiesBuilder::with_aead_backend
+//! [`DecryptionPropertiesBuilder::with_aead_backend`]: crate::encryption::decrypt::DecryptionPropertiesBuilder::with_aead_backend
+
+use crate::encryption::ciphers::{
+    CryptoOperation, RingCtrBlockDecryptor, RingCtrBlockEncryptor, RingGcmBlockDecryptor,
+    RingGcmBlockEncryptor,
+};
+use crate::errors::{ParquetError, Result};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+pub use crate::encryption::ciphers::{
+    BlockDecryptor, BlockEncryptor, MODULE_PREFIX_LEN, PlaintextBlockDecryptor,
+    PlaintextBlockEncryptor,
+};
+
+/// Creates the encryptors and decryptors used to encrypt and decrypt Parquet modules.
+///
+/// Keys are 16 or 32 bytes long, for AES-128 or AES-256.
+pub trait AeadBackend: Debug + Send + Sync {
+    /// Create an AES-GCM encryptor, used for all modules with the `AES_GCM_V1` algorithm,
+    /// and for all modules other than page data with the `AES_GCM_CTR_V1` algorithm.
+    /// A new random nonce must be used for each encrypted module.
+    fn new_encryptor(&self, key: &[u8]) -> Result<Box<dyn BlockEncryptor>>;
+
+    /// Create an AES-GCM decryptor
+    fn new_decryptor(&self, key: &[u8]) -> Result<Arc<dyn BlockDecryptor>>;
+
+    /// Create an AES-CTR encryptor, used for page data with the `AES_GCM_CTR_V1` algorithm.
+    fn new_ctr_encryptor(&self, _key: &[u8]) -> Result<Box<dyn BlockEncryptor>> {
+        Err(nyi_err!(
+            "The AES_GCM_CTR_V1 algorithm is not supported by this cipher backend"
+        ))
+    }
+
+    /// Create an AES-GCM encryptor whose nonces are seeded from the given random source.
+    ///
+    /// Defaults to [`Self::new_encryptor`], for backends that generate their own nonces.
+    fn new_encryptor_with_random(
+        &self,
+        key: &[u8],
+        _random: &dyn RandomSource,
+    ) -> Result<Box<dyn BlockEncryptor>> {
+        self.new_encryptor(key)
+    }
+
+    /// Create an AES-CTR encryptor whose nonces are seeded from the given random source.
+    ///
+    /// Defaults to [`Self::new_ctr_encryptor`], for backends that generate their own nonces.
+    fn new_ctr_encryptor_with_random(
+        &self,
+        key: &[u8],
+        _random: &dyn RandomSource,
+    ) -> Result<Box<dyn BlockEncryptor>> {
+        self.new_ctr_encryptor(key)
+    }
+
+    /// Create an AES-CTR decryptor
+    fn new_ctr_decryptor(&self, _key: &[u8]) -> Result<Arc<dyn BlockDecryptor>> {
+        Err(nyi_err!(
+            "The AES_GCM_CTR_V1 algorithm is not supported by this cipher backend"
+        ))
+    }
+}
+
+/// The default [`AeadBackend`], implemented with the `ring` crate
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RingAeadBackend;
+
+impl AeadBackend for RingAeadBackend {
+    fn new_encryptor(&self, key: &[u8]) -> Result<Box<dyn BlockEncryptor>> {
+        Ok(Box::new(RingGcmBlockEncryptor::new(key)?))
+    }
+
+    fn new_decryptor(&self, key: &[u8]) -> Result<Arc<dyn BlockDecryptor>> {
+        Ok(Arc::new(RingGcmBlockDecryptor::new(key)?))
+    }
+
+    fn new_ctr_encryptor(&self, key: &[u8]) -> Result<Box<dyn BlockEncryptor>> {
+        Ok(Box::new(RingCtrBlockEncryptor::new(key)?))
+    }
+
+    fn new_ctr_decryptor(&self, key: &[u8]) -> Result<Arc<dyn BlockDecryptor>> {
+        Ok(Arc::new(RingCtrBlockDecryptor::new(key)?))
+    }
+
+    fn new_encryptor_with_random(
+        &self,
+        key: &[u8],
+        random: &dyn RandomSource,
+    ) -> Result<Box<dyn BlockEncryptor>> {
+        Ok(Box::new(RingGcmBlockEncryptor::new_with_random(
+            key, random,
+        )?))
+    }
+
+    fn new_ctr_encryptor_with_random(
+        &self,
+        key: &[u8],
+        random: &dyn RandomSource,
+    ) -> Result<Box<dyn BlockEncryptor>> {
+        Ok(Box::new(RingCtrBlockEncryptor::new_with_random(
+            key, random,
+        )?))
+    }
+}
+
+/// A source of cryptographically secure random bytes, used when encrypting to generate
+/// the unique file identifier in the AAD and the initial nonce of each encryptor.
+///
+/// Defaults to [`SystemRandomSource`]. A different source can be set with
+/// [`EncryptionPropertiesBuilder::with_random_source`], for example a FIPS validated
+/// generator.
+///
+/// # Security
+///
+/// AES-GCM loses both confidentiality and integrity if a nonce is ever reused with the
+/// same key. The encryptors of a single file are given separate ranges of nonces, but
+/// nothing separates the nonces of different files: two files written with the same key
+/// and a deterministic source produce the same nonces. A deterministic source is
+/// therefore **unsafe for real data**. It should only be used for tests and test vectors
+/// with throwaway keys, and should produce a non-repeating sequence, such as a counter
+/// from a seed, rather than the same bytes on every call.
+///
+/// [`EncryptionPropertiesBuilder::with_random_source`]: crate::encryption::encrypt::EncryptionPropertiesBuilder::with_random_source
+pub trait RandomSource: Debug + Send + Sync {
+    /// Fill `dest` with random bytes
+    fn fill(&self, dest: &mut [u8]) -> Result<()>;
+}
+
+/// The default [`RandomSource`], which uses the operating system's random number generator
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemRandomSource;
+
+impl RandomSource for SystemRandomSource {
+    fn fill(&self, dest: &mut [u8]) -> Result<()> {
+        SystemRandom::new()
+            .fill(dest)
+            .map_err(CryptoOperation::RandomFill.error())?;
+        Ok(())
+    }
+}
@@ -15,23 +15,61 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::encryption::backend::{RandomSource, SystemRandomSource};
+use crate::encryption::modules::ModuleType;
 use crate::errors::ParquetError;
 use crate::errors::ParquetError::General;
 use crate::errors::Result;
-use crate::file::metadata::HeapSize;
-use ring::aead::{AES_128_GCM, AES_256_GCM, Aad, LessSafeKey, NonceSequence, UnboundKey};
-use ring::rand::{SecureRandom, SystemRandom};
+use ring::aead::{
+    AES_128_GCM, AES_256_GCM, Aad, Algorithm, LessSafeKey, Nonce, NonceSequence, UnboundKey,
+};
+use ring::hkdf;
 use std::fmt::Debug;
 
 const RIGHT_TWELVE: u128 = 0x0000_0000_ffff_ffff_ffff_ffff_ffff_ffff;
 pub(crate) const NONCE_LEN: usize = 12;
 pub(crate) const TAG_LEN: usize = 16;
 pub(crate) const SIZE_LEN: usize = 4;
+/// The number of bytes reserved at the start of the buffer passed to
+/// [`BlockEncryptor::encrypt_in_place`], for the 4 byte length and the 12 byte nonce
+pub const MODULE_PREFIX_LEN: usize = SIZE_LEN + NONCE_LEN;
+const CTR_BLOCK_LEN: usize = 16;
+/// The largest plaintext that can be encrypted as a single module. AES-GCM can safely
+/// encrypt up to 2^36 - 32 bytes per invocation, but the 4 byte module length, which
+/// includes the nonce and tag, gives a lower limit.
+pub(crate) const MAX_PLAINTEXT_LEN: usize = u32::MAX as usize - NONCE_LEN - TAG_LEN;
+const MODULE_KEY_INFO: &[u8] = b"parquet-module-key";
 
-pub(crate) trait BlockDecryptor: Debug + Send + Sync + HeapSize {
-    fn decrypt(&self, length_and_ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>>;
+/// Decrypts Parquet modules.
+///
+/// Modules are provided in the format written by the matching [`BlockEncryptor`],
+/// starting with the 4 byte little-endian ciphertext length, followed by the 12 byte nonce
+/// for AES ciphers.
+pub trait BlockDecryptor: Debug + Send + Sync {
+    /// Decrypt a module using the given additional authenticated data (AAD)
+    fn decrypt(&self, length_and_ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let mut plaintext = Vec::new();
+        self.decrypt_into(length_and_ciphertext, aad, &mut plaintext)?;
+        Ok(plaintext)
+    }
+
+    /// Decrypt into a caller provided buffer, replacing its contents.
+    /// This allows a buffer to be reused across many decryptions.
+    fn decrypt_into(
+        &self,
+        length_and_ciphertext: &[u8],
+        aad: &[u8],
+        plaintext: &mut Vec<u8>,
+    ) -> Result<()>;
 
+    /// Compute the authentication tag of a plaintext footer, which is followed by
+    /// the 12 byte nonce and 16 byte tag written when the footer was signed.
     fn compute_plaintext_tag(&self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>>;
+
+    /// Estimate of the memory allocated on the heap by this decryptor
+    fn heap_size(&self) -> usize {
+        0
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -42,16 +80,7 @@ pub(crate) struct RingGcmBlockDecryptor {
 impl RingGcmBlockDecryptor {
     /// Create a new `RingGcmBlockDecryptor` with a given key.
     pub(crate) fn new(key_bytes: &[u8]) -> Result<Self> {
-        let algorithm = if key_bytes.len() == AES_128_GCM.key_len() {
-            &AES_128_GCM
-        } else if key_bytes.len() == AES_256_GCM.key_len() {
-            &AES_256_GCM
-        } else {
-            return Err(general_err!(
-                "Error creating RingGcmBlockDecryptor with unsupported key length: {}",
-                key_bytes.len()
-            ));
-        };
+        let algorithm = aes_algorithm(key_bytes, "RingGcmBlockDecryptor")?;
         let key = UnboundKey::new(algorithm, key_bytes)
             .map_err(|_| general_err!("Failed to create {:?} key", algorithm))?;
 
@@ -61,66 +90,316 @@ impl RingGcmBlockDecryptor {
     }
 }
 
-impl HeapSize for RingGcmBlockDecryptor {
-    fn heap_size(&self) -> usize {
-        // Ring's LessSafeKey doesn't allocate on the heap
-        0
-    }
-}
-
 impl BlockDecryptor for RingGcmBlockDecryptor {
-    fn decrypt(&self, length_and_ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
-        let mut result = Vec::with_capacity(length_and_ciphertext.len() - SIZE_LEN - NONCE_LEN);
-        result.extend_from_slice(&length_and_ciphertext[SIZE_LEN + NONCE_LEN..]);
+    fn decrypt_into(
+        &self,
+        length_and_ciphertext: &[u8],
+        aad: &[u8],
+        plaintext: &mut Vec<u8>,
+    ) -> Result<()> {
+        check_module_length(length_and_ciphertext, SIZE_LEN + NONCE_LEN + TAG_LEN)?;
+        plaintext.clear();
+        plaintext.extend_from_slice(&length_and_ciphertext[SIZE_LEN + NONCE_LEN..]);
 
         let nonce = ring::aead::Nonce::try_assume_unique_for_key(
             &length_and_ciphertext[SIZE_LEN..SIZE_LEN + NONCE_LEN],
-        )?;
+        )
+        .map_err(CryptoOperation::CreateNonce.error())?;
 
-        self.key.open_in_place(nonce, Aad::from(aad), &mut result)?;
+        let plaintext_len = self
+            .key
+            .open_in_place(nonce, Aad::from(aad), plaintext)
+            .map_err(CryptoOperation::Open.error())?
+            .len();
 
-        // Truncate result to remove the tag
-        result.resize(result.len() - TAG_LEN, 0u8);
-        Ok(result)
+        // Truncate to remove the tag
+        plaintext.truncate(plaintext_len);
+        Ok(())
     }
 
     fn compute_plaintext_tag(&self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        if plaintext.len() < NONCE_LEN + TAG_LEN {
+            return Err(ParquetError::CiphertextFormatError(format!(
+                "Signed plaintext footer is too short: {} bytes, expected at least {}",
+                plaintext.len(),
+                NONCE_LEN + TAG_LEN
+            )));
+        }
         let mut plaintext = plaintext.to_vec();
         let nonce = &plaintext[plaintext.len() - NONCE_LEN - TAG_LEN..plaintext.len() - TAG_LEN];
-        let nonce = ring::aead::Nonce::try_assume_unique_for_key(nonce)?;
+        let nonce = ring::aead::Nonce::try_assume_unique_for_key(nonce)
+            .map_err(CryptoOperation::CreateNonce.error())?;
         let plaintext_end = plaintext.len() - NONCE_LEN - TAG_LEN;
-        let tag = self.key.seal_in_place_separate_tag(
-            nonce,
-            Aad::from(aad),
-            &mut plaintext[..plaintext_end],
-        )?;
+        let tag = self
+            .key
+            .seal_in_place_separate_tag(nonce, Aad::from(aad), &mut plaintext[..plaintext_end])
+            .map_err(CryptoOperation::Seal.error())?;
         Ok(tag.as_ref().to_vec())
     }
 }
 
-pub(crate) trait BlockEncryptor: Debug + Send + Sync {
+/// Decryptor for the page data modules of files written with the `AES_GCM_CTR_V1` algorithm.
+///
+/// These modules are encrypted with AES in CTR mode, so have no authentication tag.
+#[derive(Debug, Clone)]
+pub(crate) struct RingCtrBlockDecryptor {
+    key: LessSafeKey,
+}
+
+impl RingCtrBlockDecryptor {
+    /// Create a new `RingCtrBlockDecryptor` with a given key.
+    pub(crate) fn new(key_bytes: &[u8]) -> Result<Self> {
+        let algorithm = aes_algorithm(key_bytes, "RingCtrBlockDecryptor")?;
+        let key = UnboundKey::new(algorithm, key_bytes)
+            .map_err(|_| general_err!("Failed to create {:?} key", algorithm))?;
+
+        Ok(Self {
+            key: LessSafeKey::new(key),
+        })
+    }
+}
+
+impl BlockDecryptor for RingCtrBlockDecryptor {
+    fn decrypt_into(
+        &self,
+        length_and_ciphertext: &[u8],
+        _aad: &[u8],
+        plaintext: &mut Vec<u8>,
+    ) -> Result<()> {
+        check_module_length(length_and_ciphertext, SIZE_LEN + NONCE_LEN)?;
+        let nonce: [u8; NONCE_LEN] = length_and_ciphertext[SIZE_LEN..SIZE_LEN + NONCE_LEN]
+            .try_into()
+            .unwrap();
+
+        plaintext.clear();
+        plaintext.extend_from_slice(&length_and_ciphertext[SIZE_LEN + NONCE_LEN..]);
+        ctr_apply_keystream(&self.key, nonce, plaintext)
+    }
+
+    fn compute_plaintext_tag(&self, _aad: &[u8], _plaintext: &[u8]) -> Result<Vec<u8>> {
+        Err(general_err!(
+            "Cannot compute an authentication tag with an AES CTR cipher"
+        ))
+    }
+}
+
+/// Check that an encrypted module is long enough to hold the length prefix, nonce and any tag,
+/// and that its length prefix is consistent with the length of the buffer
+fn check_module_length(length_and_ciphertext: &[u8], min_len: usize) -> Result<()> {
+    if length_and_ciphertext.len() < min_len {
+        return Err(ParquetError::CiphertextFormatError(format!(
+            "Encrypted module is too short: {} bytes, expected at least {}",
+            length_and_ciphertext.len(),
+            min_len
+        )));
+    }
+    let length_prefix = u32::from_le_bytes(length_and_ciphertext[..SIZE_LEN].try_into().unwrap());
+    let ciphertext_len = length_and_ciphertext.len() - SIZE_LEN;
+    if length_prefix as usize != ciphertext_len {
+        return Err(ParquetError::CiphertextFormatError(format!(
+            "Encrypted module length {} does not match the ciphertext length {}",
+            length_prefix, ciphertext_len
+        )));
+    }
+    Ok(())
+}
+
+/// Get the ring AES-GCM algorithm matching the length of an AES key
+fn aes_algorithm(key_bytes: &[u8], cipher_name: &str) -> Result<&'static Algorithm> {
+    if key_bytes.len() == AES_128_GCM.key_len() {
+        Ok(&AES_128_GCM)
+    } else if key_bytes.len() == AES_256_GCM.key_len() {
+        Ok(&AES_256_GCM)
+    } else if key_bytes.len() == 24 {
+        Err(general_err!(
+            "Error creating {} with unsupported key length: 24. \
+             AES-192 keys are not supported, use a 16 byte (AES-128) or 32 byte (AES-256) key",
+            cipher_name
+        ))
+    } else {
+        Err(general_err!(
+            "Error creating {} with unsupported key length: {}",
+            cipher_name,
+            key_bytes.len()
+        ))
+    }
+}
+
+/// XOR data in place with the AES CTR keystream for a nonce, with the counter
+/// starting at 1 as required by the Parquet spec.
+///
+/// Ring doesn't expose AES CTR directly, so this is built on top of AES GCM,
+/// which uses AES CTR internally:
+/// * The tag of an empty message with no AAD is the first keystream block,
+///   as the GHASH of empty input is zero.
+/// * Sealing a message encrypts it with the keystream starting at the second block.
+fn ctr_apply_keystream(key: &LessSafeKey, nonce: [u8; NONCE_LEN], data: &mut [u8]) -> Result<()> {
+    let keystream_err =
+        |_| ParquetError::EncryptionError("Failed to generate AES-CTR keystream".to_string());
+    let first_block = key
+        .seal_in_place_separate_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut [])
+        .map_err(keystream_err)?;
+    let (head, tail) = data.split_at_mut(data.len().min(CTR_BLOCK_LEN));
+    head.iter_mut()
+        .zip(first_block.as_ref())
+        .for_each(|(byte, key_byte)| *byte ^= key_byte);
+    if !tail.is_empty() {
+        // The tag computed over the remaining data is discarded
+        let _tag = key
+            .seal_in_place_separate_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), tail)
+            .map_err(keystream_err)?;
+    }
+    Ok(())
+}
+
+/// Compare two byte slices in time that depends only on their lengths, not their contents
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
+/// Output length of a derived module key, which matches the column key length
+struct ModuleKeyLen(usize);
+
+impl hkdf::KeyType for ModuleKeyLen {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+/// Derive a key used to encrypt modules of the given type from a column key,
+/// with HKDF-SHA256 using the module type as context. The derived key has
+/// the same length as the column key.
+///
+/// This isn't part of the Parquet specification, and is only used when module key
+/// derivation is enabled in the file encryption and decryption properties.
+pub(crate) fn derive_module_key(key: &[u8], module_type: ModuleType) -> Result<Vec<u8>> {
+    let derivation_failed =
+        |_| general_err!("Failed to derive a key for {:?} modules", module_type);
+    let info = [MODULE_KEY_INFO, &[module_type as u8]];
+    let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, &[]).extract(key);
+    let okm = prk
+        .expand(&info, ModuleKeyLen(key.len()))
+        .map_err(derivation_failed)?;
+    let mut module_key = vec![0; key.len()];
+    okm.fill(&mut module_key).map_err(derivation_failed)?;
+    Ok(module_key)
+}
+
+/// A cryptographic operation performed with ring, used to describe the operation that
+/// failed when ring returns an opaque [`ring::error::Unspecified`] error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CryptoOperation {
+    /// Encrypting data and computing its authentication tag
+    Seal,
+    /// Verifying the authentication tag of data and decrypting it
+    Open,
+    /// Filling a buffer with random bytes
+    RandomFill,
+    /// Creating a nonce from bytes read from a module
+    CreateNonce,
+}
+
+impl CryptoOperation {
+    fn description(self) -> &'static str {
+        match self {
+            CryptoOperation::Seal => "seal (encrypt and compute authentication tag)",
+            CryptoOperation::Open => "open (verify authentication tag and decrypt)",
+            CryptoOperation::RandomFill => "fill buffer with random bytes",
+            CryptoOperation::CreateNonce => "create nonce",
+        }
+    }
+
+    /// Get a function that converts a ring error from this operation into a [`ParquetError`]
+    /// that names the operation
+    pub(crate) fn error(self) -> impl Fn(ring::error::Unspecified) -> ParquetError {
+        move |_| {
+            let message = format!("Cryptographic operation failed: {}", self.description());
+            match self {
+                CryptoOperation::Open => ParquetError::DecryptionKeyError(format!(
+                    "{message}. The key may be wrong or the data may have been tampered with"
+                )),
+                _ => ParquetError::General(message),
+            }
+        }
+    }
+}
+
+fn nonce_exhausted(_: ring::error::Unspecified) -> ParquetError {
+    ParquetError::EncryptionError(
+        "The nonce sequence is exhausted, a new encryptor must be created".to_string(),
+    )
+}
+
+fn missing_module_prefix(buffer_length: usize) -> ParquetError {
+    General(format!(
+        "Buffer of {buffer_length} bytes is too short to hold the {MODULE_PREFIX_LEN} byte module prefix"
+    ))
+}
+
+/// Encrypts Parquet modules.
+///
+/// The output of [`encrypt`](Self::encrypt) must start with the ciphertext length
+/// as a 4 byte little-endian integer. For AES ciphers this is followed by the 12 byte nonce,
+/// and for AES-GCM the ciphertext is followed by the 16 byte authentication tag.
+pub trait BlockEncryptor: Debug + Send + Sync {
+    /// Encrypt a module using the given additional authenticated data (AAD)
     fn encrypt(&mut self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>>;
+
+    /// Encrypt a module in place. On input, `buffer` holds [`MODULE_PREFIX_LEN`] reserved
+    /// bytes followed by the plaintext, so that the length and nonce can be written in front
+    /// of the ciphertext without moving it. On output it holds the encrypted module.
+    ///
+    /// The default implementation calls [`encrypt`](Self::encrypt), so briefly holds both the
+    /// plaintext and the encrypted module in memory. Implementations can override this to
+    /// reuse the buffer, which reduces peak memory use when encrypting large modules such as
+    /// the footer of a file with a very wide schema.
+    fn encrypt_in_place(&mut self, buffer: &mut Vec<u8>, aad: &[u8]) -> Result<()> {
+        let plaintext = buffer
+            .get(MODULE_PREFIX_LEN..)
+            .ok_or_else(|| missing_module_prefix(buffer.len()))?;
+        *buffer = self.encrypt(plaintext, aad)?;
+        Ok(())
+    }
+
+    /// The number of modules that can still be encrypted before the nonce
+    /// sequence is exhausted, or `None` if this is not known
+    fn remaining_nonces(&self) -> Option<u128> {
+        None
+    }
 }
 
-#[derive(Debug, Clone)]
+/// A sequence of nonces starting from a random seed.
+///
+/// This is deliberately not `Clone`, as a copy would produce the same nonces.
+#[derive(Debug)]
 struct CounterNonce {
     start: u128,
     counter: u128,
 }
 
 impl CounterNonce {
-    pub fn new(rng: &SystemRandom) -> Result<Self> {
+    pub fn new(random: &dyn RandomSource) -> Result<Self> {
         let mut buf = [0; 16];
-        rng.fill(&mut buf)?;
+        random.fill(&mut buf)?;
 
-        // Since this is a random seed value, endianness doesn't matter at all,
-        // and we can use whatever is platform-native.
-        let start = u128::from_ne_bytes(buf) & RIGHT_TWELVE;
+        // The seed is read as little-endian so that bytes 8..12 are the top 32 bits
+        // of the 96 bit counter, which `FileEncryptor` uses to give each encryptor
+        // of a file a separate range of nonces.
+        let start = u128::from_le_bytes(buf) & RIGHT_TWELVE;
         let counter = start.wrapping_add(1);
 
         Ok(Self { start, counter })
     }
 
+    /// The number of nonces left before the sequence wraps around to its start
+    pub fn remaining(&self) -> u128 {
+        self.start.wrapping_sub(self.counter) & RIGHT_TWELVE
+    }
+
     /// One accessor for the nonce bytes to avoid potentially flipping endianness
     #[inline]
     pub fn get_bytes(&self) -> [u8; NONCE_LEN] {
@@ -142,7 +421,7 @@ impl NonceSequence for CounterNonce {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub(crate) struct RingGcmBlockEncryptor {
     key: LessSafeKey,
     nonce_sequence: CounterNonce,
@@ -153,21 +432,16 @@ impl RingGcmBlockEncryptor {
     /// The nonce will advance appropriately with each block encryption and
     /// return an error if it wraps around.
     pub(crate) fn new(key_bytes: &[u8]) -> Result<Self> {
-        let rng = SystemRandom::new();
-        let algorithm = if key_bytes.len() == AES_128_GCM.key_len() {
-            &AES_128_GCM
-        } else if key_bytes.len() == AES_256_GCM.key_len() {
-            &AES_256_GCM
-        } else {
-            return Err(general_err!(
-                "Error creating RingGcmBlockEncryptor with unsupported key length: {}",
-                key_bytes.len()
-            ));
-        };
+        Self::new_with_random(key_bytes, &SystemRandomSource)
+    }
+
+    /// Create a new `RingGcmBlockEncryptor` with a given key and a nonce seeded from `random`.
+    pub(crate) fn new_with_random(key_bytes: &[u8], random: &dyn RandomSource) -> Result<Self> {
+        let algorithm = aes_algorithm(key_bytes, "RingGcmBlockEncryptor")?;
 
         let key = UnboundKey::new(algorithm, key_bytes)
             .map_err(|e| general_err!("Error creating {:?} key: {}", algorithm, e))?;
-        let nonce = CounterNonce::new(&rng)?;
+        let nonce = CounterNonce::new(random)?;
 
         Ok(Self {
             key: LessSafeKey::new(key),
@@ -187,15 +461,18 @@ impl BlockEncryptor for RingGcmBlockEncryptor {
         let mut ciphertext = Vec::with_capacity(SIZE_LEN + ciphertext_length as usize);
         ciphertext.extend((ciphertext_length).to_le_bytes());
 
-        let nonce = self.nonce_sequence.advance()?;
+        let nonce = self.nonce_sequence.advance().map_err(nonce_exhausted)?;
         ciphertext.extend(nonce.as_ref());
         ciphertext.extend(plaintext);
 
-        let tag = self.key.seal_in_place_separate_tag(
-            nonce,
-            Aad::from(aad),
-            &mut ciphertext[SIZE_LEN + NONCE_LEN..],
-        )?;
+        let tag = self
+            .key
+            .seal_in_place_separate_tag(
+                nonce,
+                Aad::from(aad),
+                &mut ciphertext[SIZE_LEN + NONCE_LEN..],
+            )
+            .map_err(|_| ParquetError::EncryptionError("AES-GCM encryption failed".to_string()))?;
 
         ciphertext.extend(tag.as_ref());
 
@@ -203,12 +480,218 @@ impl BlockEncryptor for RingGcmBlockEncryptor {
 
         Ok(ciphertext)
     }
+
+    fn encrypt_in_place(&mut self, buffer: &mut Vec<u8>, aad: &[u8]) -> Result<()> {
+        // Format is: [ciphertext size, nonce, ciphertext, authentication tag]
+        let plaintext_length = buffer
+            .len()
+            .checked_sub(MODULE_PREFIX_LEN)
+            .ok_or_else(|| missing_module_prefix(buffer.len()))?;
+        let ciphertext_length: u32 = (NONCE_LEN + plaintext_length + TAG_LEN)
+            .try_into()
+            .map_err(|err| General(format!("Plaintext data too long. {err:?}")))?;
+        let nonce = self.nonce_sequence.advance().map_err(nonce_exhausted)?;
+
+        buffer[..SIZE_LEN].copy_from_slice(&ciphertext_length.to_le_bytes());
+        buffer[SIZE_LEN..MODULE_PREFIX_LEN].copy_from_slice(nonce.as_ref());
+        let tag = self
+            .key
+            .seal_in_place_separate_tag(nonce, Aad::from(aad), &mut buffer[MODULE_PREFIX_LEN..])
+            .map_err(|_| ParquetError::EncryptionError("AES-GCM encryption failed".to_string()))?;
+
+        // Reserve the exact space needed to avoid over-allocating for large modules
+        buffer.reserve_exact(TAG_LEN);
+        buffer.extend(tag.as_ref());
+
+        debug_assert_eq!(SIZE_LEN + ciphertext_length as usize, buffer.len());
+        Ok(())
+    }
+
+    fn remaining_nonces(&self) -> Option<u128> {
+        Some(self.nonce_sequence.remaining())
+    }
+}
+
+/// Encryptor for the page data modules of files written with the `AES_GCM_CTR_V1` algorithm.
+#[derive(Debug)]
+pub(crate) struct RingCtrBlockEncryptor {
+    key: LessSafeKey,
+    nonce_sequence: CounterNonce,
+}
+
+impl RingCtrBlockEncryptor {
+    /// Create a new `RingCtrBlockEncryptor` with a given key and random nonce.
+    /// The nonce will advance appropriately with each block encryption and
+    /// return an error if it wraps around.
+    pub(crate) fn new(key_bytes: &[u8]) -> Result<Self> {
+        Self::new_with_random(key_bytes, &SystemRandomSource)
+    }
+
+    /// Create a new `RingCtrBlockEncryptor` with a given key and a nonce seeded from `random`.
+    pub(crate) fn new_with_random(key_bytes: &[u8], random: &dyn RandomSource) -> Result<Self> {
+        let algorithm = aes_algorithm(key_bytes, "RingCtrBlockEncryptor")?;
+        let key = UnboundKey::new(algorithm, key_bytes)
+            .map_err(|e| general_err!("Error creating {:?} key: {}", algorithm, e))?;
+        let nonce = CounterNonce::new(random)?;
+
+        Ok(Self {
+            key: LessSafeKey::new(key),
+            nonce_sequence: nonce,
+        })
+    }
+}
+
+impl BlockEncryptor for RingCtrBlockEncryptor {
+    fn encrypt(&mut self, plaintext: &[u8], _aad: &[u8]) -> Result<Vec<u8>> {
+        // Create encrypted buffer.
+        // Format is: [ciphertext size, nonce, ciphertext]
+        let ciphertext_length: u32 = (NONCE_LEN + plaintext.len())
+            .try_into()
+            .map_err(|err| General(format!("Plaintext data too long. {err:?}")))?;
+        let mut ciphertext = Vec::with_capacity(SIZE_LEN + ciphertext_length as usize);
+        ciphertext.extend((ciphertext_length).to_le_bytes());
+
+        let nonce = self.nonce_sequence.advance().map_err(nonce_exhausted)?;
+        let nonce: [u8; NONCE_LEN] = *nonce.as_ref();
+        ciphertext.extend(nonce);
+        ciphertext.extend(plaintext);
+
+        ctr_apply_keystream(&self.key, nonce, &mut ciphertext[SIZE_LEN + NONCE_LEN..])?;
+
+        Ok(ciphertext)
+    }
+
+    fn remaining_nonces(&self) -> Option<u128> {
+        Some(self.nonce_sequence.remaining())
+    }
+}
+
+/// A [`BlockEncryptor`] that leaves data unchanged, apart from adding the 4 byte length prefix.
+///
+/// No nonce or tag is written and the data is neither confidential nor authenticated.
+/// This is useful for testing the encryption pipeline independently of any cipher
+/// implementation, and can't be used to sign plaintext footers.
+#[derive(Debug, Default)]
+pub struct PlaintextBlockEncryptor;
+
+impl BlockEncryptor for PlaintextBlockEncryptor {
+    fn encrypt(&mut self, plaintext: &[u8], _aad: &[u8]) -> Result<Vec<u8>> {
+        // Format is: [data size, data]
+        let length: u32 = plaintext
+            .len()
+            .try_into()
+            .map_err(|err| General(format!("Plaintext data too long. {err:?}")))?;
+        let mut buffer = Vec::with_capacity(SIZE_LEN + plaintext.len());
+        buffer.extend(length.to_le_bytes());
+        buffer.extend(plaintext);
+        Ok(buffer)
+    }
+}
+
+/// A [`BlockDecryptor`] for modules written by [`PlaintextBlockEncryptor`]
+#[derive(Debug, Default)]
+pub struct PlaintextBlockDecryptor;
+
+impl BlockDecryptor for PlaintextBlockDecryptor {
+    fn decrypt_into(
+        &self,
+        length_and_ciphertext: &[u8],
+        _aad: &[u8],
+        plaintext: &mut Vec<u8>,
+    ) -> Result<()> {
+        check_module_length(length_and_ciphertext, SIZE_LEN)?;
+        plaintext.clear();
+        plaintext.extend_from_slice(&length_and_ciphertext[SIZE_LEN..]);
+        Ok(())
+    }
+
+    fn compute_plaintext_tag(&self, _aad: &[u8], _plaintext: &[u8]) -> Result<Vec<u8>> {
+        Err(general_err!(
+            "Cannot compute an authentication tag with a plaintext cipher"
+        ))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_encrypt_in_place() {
+        let key = [0u8; 16];
+        let mut encryptor = RingGcmBlockEncryptor::new(&key).unwrap();
+        let decryptor = RingGcmBlockDecryptor::new(&key).unwrap();
+        let plaintext = vec![7u8; 10_000];
+        let with_prefix = |capacity: usize| {
+            let mut buffer = Vec::with_capacity(capacity);
+            buffer.resize(MODULE_PREFIX_LEN, 0);
+            buffer.extend_from_slice(&plaintext);
+            buffer
+        };
+
+        // The buffer is reused when it has space for the tag
+        let mut buffer = with_prefix(MODULE_PREFIX_LEN + plaintext.len() + TAG_LEN);
+        let ptr = buffer.as_ptr();
+        encryptor.encrypt_in_place(&mut buffer, b"aad").unwrap();
+        assert_eq!(buffer.as_ptr(), ptr);
+        assert_eq!(
+            buffer.len(),
+            plaintext.len() + SIZE_LEN + NONCE_LEN + TAG_LEN
+        );
+        assert_eq!(decryptor.decrypt(&buffer, b"aad").unwrap(), plaintext);
+
+        // Otherwise it only grows by the tag
+        let mut buffer = with_prefix(MODULE_PREFIX_LEN + plaintext.len());
+        encryptor.encrypt_in_place(&mut buffer, b"aad").unwrap();
+        assert_eq!(
+            buffer.capacity(),
+            plaintext.len() + SIZE_LEN + NONCE_LEN + TAG_LEN
+        );
+        assert_eq!(decryptor.decrypt(&buffer, b"aad").unwrap(), plaintext);
+
+        // The default implementation produces the same format
+        let mut buffer = with_prefix(0);
+        let mut plaintext_encryptor = PlaintextBlockEncryptor;
+        plaintext_encryptor
+            .encrypt_in_place(&mut buffer, b"aad")
+            .unwrap();
+        assert_eq!(
+            PlaintextBlockDecryptor.decrypt(&buffer, b"aad").unwrap(),
+            plaintext
+        );
+
+        // Both require space for the prefix
+        let expected =
+            "Parquet error: Buffer of 15 bytes is too short to hold the 16 byte module prefix";
+        let mut buffer = vec![0u8; MODULE_PREFIX_LEN - 1];
+        let err = encryptor.encrypt_in_place(&mut buffer, b"aad").unwrap_err();
+        assert_eq!(err.to_string(), expected);
+        let err = plaintext_encryptor
+            .encrypt_in_place(&mut buffer, b"aad")
+            .unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[test]
+    fn test_plaintext_round_trip() {
+        let mut encryptor = PlaintextBlockEncryptor;
+        let decryptor = PlaintextBlockDecryptor;
+
+        let module = encryptor.encrypt(b"hello, world!", b"aad").unwrap();
+        assert_eq!(&module[..SIZE_LEN], 13u32.to_le_bytes());
+        assert_eq!(&module[SIZE_LEN..], b"hello, world!");
+        assert_eq!(encryptor.remaining_nonces(), None);
+
+        assert_eq!(
+            decryptor.decrypt(&module, b"aad").unwrap(),
+            b"hello, world!"
+        );
+        let err = decryptor
+            .decrypt(&module[..module.len() - 1], b"aad")
+            .unwrap_err();
+        assert!(matches!(err, ParquetError::CiphertextFormatError(_)));
+    }
+
     #[test]
     fn test_round_trip() {
         let key = [0u8; 16];
@@ -223,4 +706,469 @@ mod tests {
 
         assert_eq!(plaintext, decrypted.as_slice());
     }
+
+    #[test]
+    fn test_decrypt_into() {
+        let key = [0u8; 16];
+        let mut encryptor = RingGcmBlockEncryptor::new(&key).unwrap();
+        let decryptor = RingGcmBlockDecryptor::new(&key).unwrap();
+        let aad = b"some aad";
+
+        let ciphertext = encryptor.encrypt(b"hello, world!", aad).unwrap();
+        let mut decrypted = Vec::new();
+        decryptor
+            .decrypt_into(&ciphertext, aad, &mut decrypted)
+            .unwrap();
+        assert_eq!(decrypted, decryptor.decrypt(&ciphertext, aad).unwrap());
+        assert_eq!(decrypted, b"hello, world!");
+    }
+
+    #[test]
+    fn test_decrypt_into_reused_buffer() {
+        let key = [0u8; 16];
+        let mut encryptor = RingGcmBlockEncryptor::new(&key).unwrap();
+        let decryptor = RingGcmBlockDecryptor::new(&key).unwrap();
+        let aad = b"some aad";
+
+        let plaintexts: [&[u8]; 4] = [b"a long first plaintext", b"short", b"", b"medium length"];
+        let mut decrypted = Vec::new();
+        for plaintext in plaintexts {
+            let ciphertext = encryptor.encrypt(plaintext, aad).unwrap();
+            decryptor
+                .decrypt_into(&ciphertext, aad, &mut decrypted)
+                .unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
+
+        // A failed decryption doesn't prevent reusing the buffer
+        let ciphertext = encryptor.encrypt(b"plaintext", aad).unwrap();
+        assert!(
+            decryptor
+                .decrypt_into(&ciphertext, b"wrong aad", &mut decrypted)
+                .is_err()
+        );
+        decryptor
+            .decrypt_into(&ciphertext, aad, &mut decrypted)
+            .unwrap();
+        assert_eq!(decrypted, b"plaintext");
+    }
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_gcm_known_ciphertext() {
+        // Test cases 1, 2 and 4 from the GCM specification, as (key, nonce, plaintext, aad,
+        // ciphertext, tag). Java's AES/GCM/NoPadding cipher used by parquet-java produces
+        // the same ciphertext followed by the tag, which is written after the length and
+        // nonce to give the `length | nonce | ciphertext | tag` module layout.
+        let cases = [
+            (
+                "00000000000000000000000000000000",
+                "000000000000000000000000",
+                "",
+                "",
+                "",
+                "58e2fccefa7e3061367f1d57a4e7455a",
+            ),
+            (
+                "00000000000000000000000000000000",
+                "000000000000000000000000",
+                "00000000000000000000000000000000",
+                "",
+                "0388dace60b6a392f328c2b971b2fe78",
+                "ab6e47d42cec13bdf53a67b21257bddf",
+            ),
+            (
+                "feffe9928665731c6d6a8f9467308308",
+                "cafebabefacedbaddecaf888",
+                "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+                 1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+                "feedfacedeadbeeffeedfacedeadbeefabaddad2",
+                "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+                 21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091",
+                "5bc94fbc3221a5db94fae95ae7121a47",
+            ),
+        ];
+        for (key, nonce, plaintext, aad, ciphertext, tag) in cases {
+            let (nonce, ciphertext, tag) = (from_hex(nonce), from_hex(ciphertext), from_hex(tag));
+            let mut module = ((NONCE_LEN + ciphertext.len() + TAG_LEN) as u32)
+                .to_le_bytes()
+                .to_vec();
+            module.extend(&nonce);
+            module.extend(&ciphertext);
+            module.extend(&tag);
+
+            let decryptor = RingGcmBlockDecryptor::new(&from_hex(key)).unwrap();
+            let decrypted = decryptor.decrypt(&module, &from_hex(aad)).unwrap();
+            assert_eq!(decrypted, from_hex(plaintext));
+        }
+    }
+
+    #[test]
+    fn test_gcm_module_layout() {
+        let key = [3u8; 16];
+        let mut encryptor = RingGcmBlockEncryptor::new(&key).unwrap();
+        let plaintext = b"some page data";
+        let aad = b"some aad";
+        let module = encryptor.encrypt(plaintext, aad).unwrap();
+
+        // Sealing with the nonce written to the module gives the ciphertext followed by the tag
+        let nonce = &module[SIZE_LEN..SIZE_LEN + NONCE_LEN];
+        let mut expected = plaintext.to_vec();
+        let key = LessSafeKey::new(UnboundKey::new(&AES_128_GCM, &key).unwrap());
+        key.seal_in_place_append_tag(
+            ring::aead::Nonce::try_assume_unique_for_key(nonce).unwrap(),
+            Aad::from(aad),
+            &mut expected,
+        )
+        .unwrap();
+        assert_eq!(&module[SIZE_LEN + NONCE_LEN..], expected.as_slice());
+        assert_eq!(
+            u32::from_le_bytes(module[..SIZE_LEN].try_into().unwrap()) as usize,
+            NONCE_LEN + plaintext.len() + TAG_LEN
+        );
+    }
+
+    #[test]
+    fn test_gcm_empty_plaintext() {
+        let key = [0u8; 16];
+        let mut encryptor = RingGcmBlockEncryptor::new(&key).unwrap();
+        let decryptor = RingGcmBlockDecryptor::new(&key).unwrap();
+        let aad = b"some aad";
+
+        let module = encryptor.encrypt(b"", aad).unwrap();
+        assert_eq!(module.len(), SIZE_LEN + NONCE_LEN + TAG_LEN);
+        assert_eq!(
+            u32::from_le_bytes(module[..SIZE_LEN].try_into().unwrap()) as usize,
+            NONCE_LEN + TAG_LEN
+        );
+        assert_eq!(decryptor.decrypt(&module, aad).unwrap(), b"");
+
+        // Any previous contents of a reused buffer are removed
+        let mut decrypted = b"previous plaintext".to_vec();
+        decryptor
+            .decrypt_into(&module, aad, &mut decrypted)
+            .unwrap();
+        assert!(decrypted.is_empty());
+
+        let mut buffer = vec![0; MODULE_PREFIX_LEN];
+        encryptor.encrypt_in_place(&mut buffer, aad).unwrap();
+        assert_eq!(buffer.len(), SIZE_LEN + NONCE_LEN + TAG_LEN);
+        assert_eq!(decryptor.decrypt(&buffer, aad).unwrap(), b"");
+
+        // The tag is still authenticated when there is no ciphertext
+        let mut tampered = module.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decryptor.decrypt(&tampered, aad).is_err());
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"", b""));
+        assert!(constant_time_eq(b"0123456789012345", b"0123456789012345"));
+        assert!(!constant_time_eq(b"0123456789012345", b"0123456789012346"));
+        assert!(!constant_time_eq(b"1123456789012345", b"0123456789012345"));
+        assert!(!constant_time_eq(b"012345678901234", b"0123456789012345"));
+    }
+
+    #[test]
+    fn test_ctr_round_trip() {
+        let key = [0u8; 32];
+        let mut encryptor = RingCtrBlockEncryptor::new(&key).unwrap();
+        let decryptor = RingCtrBlockDecryptor::new(&key).unwrap();
+
+        for plaintext in [&b""[..], b"short", b"a plaintext longer than one block"] {
+            let ciphertext = encryptor.encrypt(plaintext, b"ignored").unwrap();
+            assert_eq!(ciphertext.len(), SIZE_LEN + NONCE_LEN + plaintext.len());
+            let length = u32::from_le_bytes(ciphertext[..SIZE_LEN].try_into().unwrap());
+            assert_eq!(length as usize, NONCE_LEN + plaintext.len());
+
+            let decrypted = decryptor.decrypt(&ciphertext, b"ignored").unwrap();
+            assert_eq!(plaintext, decrypted.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_ctr_known_ciphertext() {
+        // AES-128 CTR with the counter block starting at nonce || 00000001
+        let key: Vec<u8> = (0..16).collect();
+        let nonce: Vec<u8> = (100..112).collect();
+        let plaintext = b"The quick brown fox jumps over the lazy dog, twice over!";
+        let expected = "31eac925913278cb4d9d9c5c7b332e8b7c0d3685b177d58ae81e3866e3b80b98\
+                        58ed5893f3d3a47a9000d6bb9c86809ad999724bfd7d55b0";
+
+        let mut length_and_ciphertext = ((NONCE_LEN + plaintext.len()) as u32)
+            .to_le_bytes()
+            .to_vec();
+        length_and_ciphertext.extend(&nonce);
+        length_and_ciphertext.extend(from_hex(expected));
+
+        let decryptor = RingCtrBlockDecryptor::new(&key).unwrap();
+        let decrypted = decryptor.decrypt(&length_and_ciphertext, b"").unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_invalid_key_length() {
+        for key_len in [0, 7, 33] {
+            let key = vec![0u8; key_len];
+            let err = RingGcmBlockDecryptor::new(&key).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Parquet error: Error creating RingGcmBlockDecryptor with unsupported key length: {key_len}"
+                )
+            );
+            let err = RingGcmBlockEncryptor::new(&key).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Parquet error: Error creating RingGcmBlockEncryptor with unsupported key length: {key_len}"
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_decryption_error_kinds() {
+        let key = [0u8; 16];
+        let mut encryptor = RingGcmBlockEncryptor::new(&key).unwrap();
+        let ciphertext = encryptor.encrypt(b"hello", b"aad").unwrap();
+
+        let wrong_key_decryptor = RingGcmBlockDecryptor::new(&[1u8; 16]).unwrap();
+        let err = wrong_key_decryptor
+            .decrypt(&ciphertext, b"aad")
+            .unwrap_err();
+        assert!(
+            matches!(err, ParquetError::DecryptionKeyError(_)),
+            "unexpected error: {err:?}"
+        );
+
+        let decryptor = RingGcmBlockDecryptor::new(&key).unwrap();
+        let err = decryptor
+            .decrypt(&ciphertext[..ciphertext.len() - 1], b"aad")
+            .unwrap_err();
+        assert!(
+            matches!(err, ParquetError::CiphertextFormatError(_)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn test_crypto_operation_errors() {
+        let operations = [
+            CryptoOperation::Seal,
+            CryptoOperation::Open,
+            CryptoOperation::RandomFill,
+            CryptoOperation::CreateNonce,
+        ];
+        let messages: Vec<String> = operations
+            .iter()
+            .map(|operation| operation.error()(ring::error::Unspecified).to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Parquet error: Cryptographic operation failed: seal (encrypt and compute authentication tag)",
+                "Decryption key error: Cryptographic operation failed: open (verify authentication tag and decrypt). \
+                The key may be wrong or the data may have been tampered with",
+                "Parquet error: Cryptographic operation failed: fill buffer with random bytes",
+                "Parquet error: Cryptographic operation failed: create nonce",
+            ]
+        );
+
+        // A failed open is reported with its operation
+        let mut encryptor = RingGcmBlockEncryptor::new(&[0u8; 16]).unwrap();
+        let ciphertext = encryptor.encrypt(b"hello", b"aad").unwrap();
+        let decryptor = RingGcmBlockDecryptor::new(&[0u8; 16]).unwrap();
+        let err = decryptor.decrypt(&ciphertext, b"wrong aad").unwrap_err();
+        assert_eq!(err.to_string(), messages[1]);
+
+        // Errors converted without an operation keep the ring error as an external error
+        let err: ParquetError = ring::error::Unspecified.into();
+        assert!(matches!(err, ParquetError::External(_)), "{err}");
+        assert_eq!(err.to_string(), "External: ring::error::Unspecified");
+    }
+
+    #[test]
+    fn test_decrypt_truncated_module() {
+        let key = [0u8; 16];
+        let mut encryptor = RingGcmBlockEncryptor::new(&key).unwrap();
+        let ciphertext = encryptor.encrypt(b"hello", b"aad").unwrap();
+        let decryptor = RingGcmBlockDecryptor::new(&key).unwrap();
+
+        for len in [
+            0,
+            3,
+            SIZE_LEN + NONCE_LEN,
+            SIZE_LEN + NONCE_LEN + TAG_LEN - 1,
+        ] {
+            let err = decryptor.decrypt(&ciphertext[..len], b"aad").unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Ciphertext format error: Encrypted module is too short: {len} bytes, expected at least 32"
+                )
+            );
+        }
+
+        let ctr_decryptor = RingCtrBlockDecryptor::new(&key).unwrap();
+        let err = ctr_decryptor.decrypt(&ciphertext[..10], b"").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Ciphertext format error: Encrypted module is too short: 10 bytes, expected at least 16"
+        );
+
+        let err = decryptor
+            .compute_plaintext_tag(b"aad", &[0u8; 20])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Ciphertext format error: Signed plaintext footer is too short: 20 bytes, expected at least 28"
+        );
+    }
+
+    #[test]
+    fn test_decrypt_inconsistent_module_length() {
+        let key = [0u8; 16];
+        let mut encryptor = RingGcmBlockEncryptor::new(&key).unwrap();
+        let ciphertext = encryptor.encrypt(b"hello", b"aad").unwrap();
+        let decryptor = RingGcmBlockDecryptor::new(&key).unwrap();
+        assert_eq!(decryptor.decrypt(&ciphertext, b"aad").unwrap(), b"hello");
+
+        // Truncated data
+        let err = decryptor
+            .decrypt(&ciphertext[..ciphertext.len() - 1], b"aad")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Ciphertext format error: Encrypted module length 33 does not match the ciphertext length 32"
+        );
+
+        // Trailing data
+        let mut extended = ciphertext.clone();
+        extended.push(0);
+        let err = decryptor.decrypt(&extended, b"aad").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Ciphertext format error: Encrypted module length 33 does not match the ciphertext length 34"
+        );
+
+        // Corrupt length prefix
+        let mut corrupt = ciphertext.clone();
+        corrupt[..SIZE_LEN].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = RingCtrBlockDecryptor::new(&key)
+            .unwrap()
+            .decrypt(&corrupt, b"")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Ciphertext format error: Encrypted module length {} does not match the ciphertext length 33",
+                u32::MAX
+            )
+        );
+    }
+
+    #[test]
+    fn test_aes_192_key_rejected() {
+        let key = [0u8; 24];
+        let expected = |cipher_name: &str| {
+            format!(
+                "Parquet error: Error creating {cipher_name} with unsupported key length: 24. \
+                 AES-192 keys are not supported, use a 16 byte (AES-128) or 32 byte (AES-256) key"
+            )
+        };
+        let err = RingGcmBlockDecryptor::new(&key).unwrap_err();
+        assert_eq!(err.to_string(), expected("RingGcmBlockDecryptor"));
+        let err = RingGcmBlockEncryptor::new(&key).unwrap_err();
+        assert_eq!(err.to_string(), expected("RingGcmBlockEncryptor"));
+        let err = RingCtrBlockDecryptor::new(&key).unwrap_err();
+        assert_eq!(err.to_string(), expected("RingCtrBlockDecryptor"));
+        let err = RingCtrBlockEncryptor::new(&key).unwrap_err();
+        assert_eq!(err.to_string(), expected("RingCtrBlockEncryptor"));
+    }
+
+    #[test]
+    fn test_ctr_invalid_key_length() {
+        let err = RingCtrBlockEncryptor::new(&[0u8; 20]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Error creating RingCtrBlockEncryptor with unsupported key length: 20"
+        );
+    }
+
+    #[test]
+    fn test_remaining_nonces() {
+        let mut nonce = CounterNonce::new(&SystemRandomSource).unwrap();
+        assert_eq!(nonce.remaining(), RIGHT_TWELVE);
+        for _ in 0..10 {
+            nonce.advance().unwrap();
+        }
+        assert_eq!(nonce.remaining(), RIGHT_TWELVE - 10);
+
+        // The remaining count is correct when the counter wraps around the 96 bit range
+        let mut nonce = CounterNonce {
+            start: 1,
+            counter: RIGHT_TWELVE - 1,
+        };
+        assert_eq!(nonce.remaining(), 3);
+        for remaining in (0..3).rev() {
+            nonce.advance().unwrap();
+            assert_eq!(nonce.remaining(), remaining);
+        }
+        assert!(nonce.advance().is_err());
+
+        let mut encryptor = RingGcmBlockEncryptor::new(&[0u8; 16]).unwrap();
+        encryptor.encrypt(b"hello", b"aad").unwrap();
+        assert_eq!(encryptor.remaining_nonces(), Some(RIGHT_TWELVE - 1));
+
+        // Encrypting with an exhausted nonce sequence is an encryption error
+        let mut encryptor = RingGcmBlockEncryptor {
+            key: LessSafeKey::new(UnboundKey::new(&AES_128_GCM, &[0u8; 16]).unwrap()),
+            nonce_sequence: CounterNonce {
+                start: 1,
+                counter: 1,
+            },
+        };
+        let err = encryptor.encrypt(b"hello", b"aad").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Encryption error: The nonce sequence is exhausted, a new encryptor must be created"
+        );
+        let mut encryptor = RingCtrBlockEncryptor::new(&[0u8; 16]).unwrap();
+        encryptor.encrypt(b"hello", b"aad").unwrap();
+        assert_eq!(encryptor.remaining_nonces(), Some(RIGHT_TWELVE - 1));
+    }
+
+    #[test]
+    fn test_derive_module_key() {
+        let key = [0u8; 16];
+        let data_key = derive_module_key(&key, ModuleType::DataPage).unwrap();
+        // HKDF-SHA256 with an empty salt and info "parquet-module-key" followed by the module type
+        assert_eq!(
+            data_key,
+            [
+                0x06, 0x60, 0x2c, 0x7a, 0xbe, 0x85, 0xcb, 0xd7, 0xc8, 0x0d, 0xad, 0x3e, 0x22, 0xfa,
+                0x63, 0xca
+            ]
+        );
+        assert_eq!(
+            derive_module_key(&key, ModuleType::DataPage).unwrap(),
+            data_key
+        );
+
+        let header_key = derive_module_key(&key, ModuleType::DataPageHeader).unwrap();
+        assert_ne!(header_key, data_key);
+        assert_ne!(header_key, key);
+
+        let long_key = derive_module_key(&[0u8; 32], ModuleType::DataPage).unwrap();
+        assert_eq!(long_key.len(), 32);
+        RingGcmBlockEncryptor::new(&long_key).unwrap();
+    }
 }
@@ -17,16 +17,25 @@
 
 //! Configuration and utilities for decryption of files using Parquet Modular Encryption
 
-use crate::encryption::ciphers::{BlockDecryptor, RingGcmBlockDecryptor, TAG_LEN};
-use crate::encryption::modules::{ModuleType, create_footer_aad, create_module_aad};
+use crate::encryption::backend::{AeadBackend, RingAeadBackend};
+use crate::encryption::ciphers::{BlockDecryptor, TAG_LEN, derive_module_key};
+use crate::encryption::encrypt::{
+    EncryptionAlgorithm, KeyId, Redacted, escaped_column_path, find_column_key,
+};
+use crate::encryption::metrics::EncryptionMetrics;
+use crate::encryption::modules::{
+    ModuleType, create_footer_aad, create_module_aad, validate_aad_prefix,
+};
 use crate::errors::{ParquetError, Result};
 use crate::file::column_crypto_metadata::ColumnCryptoMetaData;
-use crate::file::metadata::HeapSize;
+use crate::file::metadata::{ColumnChunkMetaData, HeapSize, ParquetMetaDataReader};
+use crate::file::reader::ChunkReader;
+use crate::schema::types::ColumnPath;
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Formatter;
 use std::io::Read;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// Trait for retrieving an encryption key using the key's metadata
 ///
@@ -101,22 +110,95 @@ use std::sync::Arc;
 /// # Ok::<(), parquet::errors::ParquetError>(())
 /// ```
 pub trait KeyRetriever: Send + Sync {
-    /// Retrieve a decryption key given the key metadata
+    /// Retrieve a decryption key given the key metadata.
+    ///
+    /// This is called with the footer key metadata when a file's footer is read,
+    /// and with the key metadata stored in each column chunk encrypted with a
+    /// column specific key, rather than assuming a key is the same across files or
+    /// row groups. This allows reading data written before and after a key rotation
+    /// as long as the key metadata identifies which key was used.
     fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>>;
 }
 
-pub(crate) fn read_and_decrypt<T: Read>(
+/// A [`KeyRetriever`] for files whose key metadata are versioned [`KeyId`]s, as written
+/// with [`EncryptionKey::with_versioned_key_id`] or
+/// [`EncryptionPropertiesBuilder::with_footer_key_id`].
+///
+/// Use with [`FileDecryptionProperties::with_versioned_key_retriever`].
+///
+/// [`EncryptionKey::with_versioned_key_id`]: crate::encryption::encrypt::EncryptionKey::with_versioned_key_id
+/// [`EncryptionPropertiesBuilder::with_footer_key_id`]: crate::encryption::encrypt::EncryptionPropertiesBuilder::with_footer_key_id
+pub trait VersionedKeyRetriever: Send + Sync {
+    /// Retrieve a decryption key given its parsed key ID
+    fn retrieve_key(&self, key_id: &KeyId) -> Result<Vec<u8>>;
+}
+
+/// Adapts a [`VersionedKeyRetriever`] to a [`KeyRetriever`] by parsing key metadata
+struct VersionedKeyRetrieverAdapter(Arc<dyn VersionedKeyRetriever>);
+
+impl KeyRetriever for VersionedKeyRetrieverAdapter {
+    fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>> {
+        self.0
+            .retrieve_key(&KeyId::from_key_metadata(key_metadata)?)
+    }
+}
+
+/// Describes a decryption key being used to decrypt a module of a Parquet file.
+///
+/// Passed to a [`KeyUsageCallback`] to allow auditing key usage.
+/// Key material is never included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyUsageEvent {
+    /// Path of the column the module belongs to, or `None` for the file footer
+    pub column_path: Option<String>,
+    /// The type of module being decrypted
+    pub module_type: ModuleType,
+}
+
+/// Callback invoked with a [`KeyUsageEvent`] each time a key is used to decrypt a module.
+///
+/// This may be called concurrently from multiple threads, and should return quickly as it
+/// is called for every page read.
+pub type KeyUsageCallback = Arc<dyn Fn(KeyUsageEvent) + Send + Sync>;
+
+/// How the columns of a Parquet file are encrypted, as returned by
+/// [`ParquetMetaData::encryption_mode`](crate::file::metadata::ParquetMetaData::encryption_mode)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncryptionMode {
+    /// The file is not encrypted
+    None,
+    /// The file is encrypted, and only the footer key is used, for the footer
+    /// and any encrypted columns
+    Uniform,
+    /// At least one column is encrypted with a column specific key
+    PerColumn,
+}
+
+/// Read and decrypt a module using caller provided buffers for the
+/// ciphertext and resulting plaintext, so these can be reused.
+pub(crate) fn read_and_decrypt_into<T: Read>(
     decryptor: &Arc<dyn BlockDecryptor>,
     input: &mut T,
     aad: &[u8],
-) -> Result<Vec<u8>> {
+    ciphertext: &mut Vec<u8>,
+    plaintext: &mut Vec<u8>,
+) -> Result<()> {
     let mut len_bytes = [0; 4];
     input.read_exact(&mut len_bytes)?;
     let ciphertext_len = u32::from_le_bytes(len_bytes) as usize;
-    let mut ciphertext = vec![0; 4 + ciphertext_len];
-    input.read_exact(&mut ciphertext[4..])?;
+    ciphertext.clear();
+    ciphertext.resize(4 + ciphertext_len, 0);
+    ciphertext[..4].copy_from_slice(&len_bytes);
+    input
+        .read_exact(&mut ciphertext[4..])
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => ParquetError::CiphertextFormatError(format!(
+                "Encrypted module is truncated, expected {ciphertext_len} bytes after the length prefix"
+            )),
+            _ => e.into(),
+        })?;
 
-    decryptor.decrypt(&ciphertext, aad.as_ref())
+    decryptor.decrypt_into(ciphertext, aad, plaintext)
 }
 
 // CryptoContext is a data structure that holds the context required to
@@ -127,6 +209,8 @@ pub(crate) struct CryptoContext {
     pub(crate) column_ordinal: usize,
     pub(crate) page_ordinal: Option<usize>,
     pub(crate) dictionary_page: bool,
+    column_path: ColumnPath,
+    decryption_properties: Arc<FileDecryptionProperties>,
     // We have separate data and metadata decryptors because
     // in GCM CTR mode, the metadata and data pages use
     // different algorithms.
@@ -139,29 +223,23 @@ impl CryptoContext {
     pub(crate) fn for_column(
         file_decryptor: &FileDecryptor,
         column_crypto_metadata: &ColumnCryptoMetaData,
+        column_path: &ColumnPath,
         row_group_idx: usize,
         column_ordinal: usize,
     ) -> Result<Self> {
         let (data_decryptor, metadata_decryptor) = match column_crypto_metadata {
             ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY => {
-                // TODO: In GCM-CTR mode will this need to be a non-GCM decryptor?
-                let data_decryptor = file_decryptor.get_footer_decryptor()?;
+                let data_decryptor = file_decryptor.get_footer_data_decryptor();
                 let metadata_decryptor = file_decryptor.get_footer_decryptor()?;
                 (data_decryptor, metadata_decryptor)
             }
             ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(column_key_encryption) => {
                 let key_metadata = &column_key_encryption.key_metadata;
-                let full_column_name;
-                let column_name = if column_key_encryption.path_in_schema.len() == 1 {
-                    &column_key_encryption.path_in_schema[0]
-                } else {
-                    full_column_name = column_key_encryption.path_in_schema.join(".");
-                    &full_column_name
-                };
-                let data_decryptor = file_decryptor
-                    .get_column_data_decryptor(column_name, key_metadata.as_deref())?;
+                let key_path = ColumnPath::new(column_key_encryption.path_in_schema.clone());
+                let data_decryptor =
+                    file_decryptor.get_column_data_decryptor(&key_path, key_metadata.as_deref())?;
                 let metadata_decryptor = file_decryptor
-                    .get_column_metadata_decryptor(column_name, key_metadata.as_deref())?;
+                    .get_column_metadata_decryptor(&key_path, key_metadata.as_deref())?;
                 (data_decryptor, metadata_decryptor)
             }
         };
@@ -171,34 +249,75 @@ impl CryptoContext {
             column_ordinal,
             page_ordinal: None,
             dictionary_page: false,
+            column_path: column_path.clone(),
+            decryption_properties: Arc::clone(&file_decryptor.decryption_properties),
             data_decryptor,
             metadata_decryptor,
             file_aad: file_decryptor.file_aad().clone(),
         })
     }
 
+    /// Create the context for a column chunk if it is encrypted, returning an error
+    /// if the column is encrypted but no file decryptor is available
+    pub(crate) fn for_column_chunk(
+        file_decryptor: Option<&FileDecryptor>,
+        column_chunk_metadata: &ColumnChunkMetaData,
+        row_group_idx: usize,
+        column_ordinal: usize,
+    ) -> Result<Option<Self>> {
+        let Some(crypto_metadata) = column_chunk_metadata.crypto_metadata() else {
+            return Ok(None);
+        };
+        let Some(file_decryptor) = file_decryptor else {
+            return Err(general_err!(
+                "Column '{}' is encrypted but file decryption properties were not provided",
+                column_chunk_metadata.column_path().string()
+            ));
+        };
+        Self::for_column(
+            file_decryptor,
+            crypto_metadata,
+            column_chunk_metadata.column_path(),
+            row_group_idx,
+            column_ordinal,
+        )
+        .map(Some)
+    }
+
     pub(crate) fn with_page_ordinal(&self, page_ordinal: usize) -> Self {
         Self {
             row_group_idx: self.row_group_idx,
             column_ordinal: self.column_ordinal,
             page_ordinal: Some(page_ordinal),
             dictionary_page: false,
+            column_path: self.column_path.clone(),
+            decryption_properties: Arc::clone(&self.decryption_properties),
             data_decryptor: self.data_decryptor.clone(),
             metadata_decryptor: self.metadata_decryptor.clone(),
             file_aad: self.file_aad.clone(),
         }
     }
 
-    pub(crate) fn create_page_header_aad(&self) -> Result<Vec<u8>> {
-        let module_type = if self.dictionary_page {
+    pub(crate) fn page_header_module_type(&self) -> ModuleType {
+        if self.dictionary_page {
             ModuleType::DictionaryPageHeader
         } else {
             ModuleType::DataPageHeader
-        };
+        }
+    }
+
+    pub(crate) fn page_module_type(&self) -> ModuleType {
+        if self.dictionary_page {
+            ModuleType::DictionaryPage
+        } else {
+            ModuleType::DataPage
+        }
+    }
 
+    pub(crate) fn create_page_header_aad(&self) -> Result<Vec<u8>> {
         create_module_aad(
             self.file_aad(),
-            module_type,
+            self.page_header_module_type(),
             self.row_group_idx,
             self.column_ordinal,
             self.page_ordinal,
@@ -206,15 +325,9 @@ impl CryptoContext {
     }
 
     pub(crate) fn create_page_aad(&self) -> Result<Vec<u8>> {
-        let module_type = if self.dictionary_page {
-            ModuleType::DictionaryPage
-        } else {
-            ModuleType::DataPage
-        };
-
         create_module_aad(
             self.file_aad(),
-            module_type,
+            self.page_module_type(),
             self.row_group_idx,
             self.column_ordinal,
             self.page_ordinal,
@@ -241,12 +354,34 @@ impl CryptoContext {
         )
     }
 
+    pub(crate) fn create_bloom_filter_header_aad(&self) -> Result<Vec<u8>> {
+        create_module_aad(
+            self.file_aad(),
+            ModuleType::BloomFilterHeader,
+            self.row_group_idx,
+            self.column_ordinal,
+            None,
+        )
+    }
+
+    pub(crate) fn create_bloom_filter_bitset_aad(&self) -> Result<Vec<u8>> {
+        create_module_aad(
+            self.file_aad(),
+            ModuleType::BloomFilterBitset,
+            self.row_group_idx,
+            self.column_ordinal,
+            None,
+        )
+    }
+
     pub(crate) fn for_dictionary_page(&self) -> Self {
         Self {
             row_group_idx: self.row_group_idx,
             column_ordinal: self.column_ordinal,
             page_ordinal: self.page_ordinal,
             dictionary_page: true,
+            column_path: self.column_path.clone(),
+            decryption_properties: Arc::clone(&self.decryption_properties),
             data_decryptor: self.data_decryptor.clone(),
             metadata_decryptor: self.metadata_decryptor.clone(),
             file_aad: self.file_aad.clone(),
@@ -264,32 +399,91 @@ impl CryptoContext {
     pub(crate) fn file_aad(&self) -> &Vec<u8> {
         &self.file_aad
     }
+
+    /// Notify any key usage callback that the key for this column is used to decrypt a module
+    pub(crate) fn record_key_usage(&self, module_type: ModuleType) {
+        if let Some(callback) = &self.decryption_properties.key_usage_callback {
+            callback(KeyUsageEvent {
+                column_path: Some(self.column_path.string()),
+                module_type,
+            });
+        }
+    }
+
+    /// Report a decrypted page of this column to any metrics sink
+    pub(crate) fn record_page_decrypted(&self, bytes: usize) {
+        if let Some(metrics) = &self.decryption_properties.metrics {
+            metrics.page_decrypted(&self.column_path.string(), bytes);
+        }
+    }
+
+    /// Describe the column chunk and page this context is for, for use in error messages
+    pub(crate) fn location(&self) -> String {
+        let page = match (self.dictionary_page, self.page_ordinal) {
+            (true, _) => ", dictionary page".to_string(),
+            (false, Some(page_ordinal)) => format!(", page {page_ordinal}"),
+            (false, None) => String::new(),
+        };
+        format!(
+            "column '{}' (row group {}{})",
+            self.column_path.string(),
+            self.row_group_idx,
+            page
+        )
+    }
 }
 
 #[derive(Clone, PartialEq)]
 struct ExplicitDecryptionKeys {
     footer_key: Vec<u8>,
     column_keys: HashMap<String, Vec<u8>>,
+    column_path_keys: HashSet<String>,
 }
 
 impl HeapSize for ExplicitDecryptionKeys {
     fn heap_size(&self) -> usize {
-        self.footer_key.heap_size() + self.column_keys.heap_size()
+        self.footer_key.heap_size()
+            + self.column_keys.heap_size()
+            + self.column_path_keys.heap_size()
     }
 }
 
 #[derive(Clone)]
 enum DecryptionKeys {
-    Explicit(ExplicitDecryptionKeys),
+    // Keys are shared so that cloning properties is cheap
+    Explicit(Arc<ExplicitDecryptionKeys>),
     ViaRetriever(Arc<dyn KeyRetriever>),
 }
 
+impl std::fmt::Debug for DecryptionKeys {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecryptionKeys::Explicit(keys) => {
+                // Show which columns have keys, but not the keys themselves
+                let column_keys: BTreeMap<_, _> = keys
+                    .column_keys
+                    .keys()
+                    .map(|column_name| (column_name, Redacted))
+                    .collect();
+                f.debug_struct("Explicit")
+                    .field("footer_key", &Redacted)
+                    .field("column_keys", &column_keys)
+                    .finish()
+            }
+            DecryptionKeys::ViaRetriever(_) => {
+                f.debug_tuple("ViaRetriever").field(&Redacted).finish()
+            }
+        }
+    }
+}
+
 impl PartialEq for DecryptionKeys {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (DecryptionKeys::Explicit(keys), DecryptionKeys::Explicit(other_keys)) => {
                 keys.footer_key == other_keys.footer_key
                     && keys.column_keys == other_keys.column_keys
+                    && keys.column_path_keys == other_keys.column_path_keys
             }
             (DecryptionKeys::ViaRetriever(_), DecryptionKeys::ViaRetriever(_)) => true,
             _ => false,
@@ -316,6 +510,9 @@ impl HeapSize for DecryptionKeys {
 /// [`ArrowReaderOptions`](crate::arrow::arrow_reader::ArrowReaderOptions) using
 /// [`with_file_decryption_properties`](crate::arrow::arrow_reader::ArrowReaderOptions::with_file_decryption_properties).
 ///
+/// The same properties can be used to read many files that share keys. They are built
+/// within an [`Arc`], and any keys are shared between clones, so cloning is cheap.
+///
 /// # Examples
 ///
 /// Create `FileDecryptionProperties` for a file encrypted with uniform encryption,
@@ -347,11 +544,26 @@ impl HeapSize for DecryptionKeys {
 ///     .build()?;
 /// # Ok::<(), parquet::errors::ParquetError>(())
 /// ```
-#[derive(Clone, PartialEq)]
+#[derive(Clone)]
 pub struct FileDecryptionProperties {
     keys: DecryptionKeys,
     aad_prefix: Option<Vec<u8>>,
     footer_signature_verification: bool,
+    footer_only: bool,
+    module_key_derivation: bool,
+    aead_backend: Arc<dyn AeadBackend>,
+    key_usage_callback: Option<KeyUsageCallback>,
+    metrics: Option<Arc<dyn EncryptionMetrics>>,
+}
+
+impl PartialEq for FileDecryptionProperties {
+    fn eq(&self, other: &Self) -> bool {
+        self.keys == other.keys
+            && self.aad_prefix == other.aad_prefix
+            && self.footer_signature_verification == other.footer_signature_verification
+            && self.footer_only == other.footer_only
+            && self.module_key_derivation == other.module_key_derivation
+    }
 }
 
 impl HeapSize for FileDecryptionProperties {
@@ -368,12 +580,23 @@ impl FileDecryptionProperties {
 
     /// Returns a new [`FileDecryptionProperties`] builder that uses a [`KeyRetriever`]
     /// to get decryption keys based on key metadata.
+    /// The footer key is also resolved with the retriever, using the footer key metadata
+    /// stored in the file, so it doesn't need to be known ahead of time.
     pub fn with_key_retriever(
         key_retriever: Arc<dyn KeyRetriever>,
     ) -> DecryptionPropertiesBuilderWithRetriever {
         DecryptionPropertiesBuilderWithRetriever::new(key_retriever)
     }
 
+    /// Returns a new [`FileDecryptionProperties`] builder that uses a [`VersionedKeyRetriever`]
+    /// to get decryption keys based on versioned key IDs parsed from the key metadata.
+    /// Reading fails if the file has key metadata that isn't a valid [`KeyId`].
+    pub fn with_versioned_key_retriever(
+        key_retriever: Arc<dyn VersionedKeyRetriever>,
+    ) -> DecryptionPropertiesBuilderWithRetriever {
+        Self::with_key_retriever(Arc::new(VersionedKeyRetrieverAdapter(key_retriever)))
+    }
+
     /// AAD prefix string uniquely identifies the file and prevents file swapping
     pub fn aad_prefix(&self) -> Option<&Vec<u8>> {
         self.aad_prefix.as_ref()
@@ -384,13 +607,52 @@ impl FileDecryptionProperties {
         self.footer_signature_verification
     }
 
+    /// Returns true if only the footer should be decrypted when reading metadata.
+    /// See [`DecryptionPropertiesBuilder::with_footer_only_decryption`].
+    pub fn footer_only_decryption(&self) -> bool {
+        self.footer_only
+    }
+
+    /// Returns true if keys for page data and page headers are derived from column keys.
+    /// See [`DecryptionPropertiesBuilder::with_module_key_derivation`].
+    pub fn module_key_derivation(&self) -> bool {
+        self.module_key_derivation
+    }
+
+    /// The sink that decryption metrics are reported to, if one was set with
+    /// [`DecryptionPropertiesBuilder::with_metrics`]
+    pub fn metrics(&self) -> Option<&Arc<dyn EncryptionMetrics>> {
+        self.metrics.as_ref()
+    }
+
+    /// The cipher backend used to create decryptors
+    pub fn aead_backend(&self) -> &Arc<dyn AeadBackend> {
+        &self.aead_backend
+    }
+
+    /// Notify any key usage callback that a key is used to decrypt a module
+    pub(crate) fn record_key_usage(&self, column_path: Option<&str>, module_type: ModuleType) {
+        if let Some(callback) = &self.key_usage_callback {
+            callback(KeyUsageEvent {
+                column_path: column_path.map(str::to_string),
+                module_type,
+            });
+        }
+    }
+
     /// Get the encryption key for decrypting a file's footer,
     /// and also column data if uniform encryption is used.
+    ///
+    /// When a [`KeyRetriever`] is used, it is called with the footer key metadata
+    /// read from the file.
     pub fn footer_key(&self, key_metadata: Option<&[u8]>) -> Result<Cow<'_, Vec<u8>>> {
         match &self.keys {
             DecryptionKeys::Explicit(keys) => Ok(Cow::Borrowed(&keys.footer_key)),
             DecryptionKeys::ViaRetriever(retriever) => {
                 let key = retriever.retrieve_key(key_metadata.unwrap_or_default())?;
+                if let Some(metrics) = &self.metrics {
+                    metrics.key_retrieved(None);
+                }
                 Ok(Cow::Owned(key))
             }
         }
@@ -412,11 +674,43 @@ impl FileDecryptionProperties {
             },
             DecryptionKeys::ViaRetriever(retriever) => {
                 let key = retriever.retrieve_key(key_metadata.unwrap_or_default())?;
+                if let Some(metrics) = &self.metrics {
+                    metrics.key_retrieved(Some(column_name));
+                }
                 Ok(Cow::Owned(key))
             }
         }
     }
 
+    /// Get the column-specific encryption key for the column with the given path.
+    ///
+    /// This finds keys configured with either
+    /// [`DecryptionPropertiesBuilder::with_column_path_key`] or
+    /// [`DecryptionPropertiesBuilder::with_column_key`], preferring the former as it
+    /// is unambiguous when field names contain dots.
+    pub fn column_path_key(
+        &self,
+        column_path: &ColumnPath,
+        key_metadata: Option<&[u8]>,
+    ) -> Result<Cow<'_, Vec<u8>>> {
+        match &self.keys {
+            DecryptionKeys::Explicit(keys) => {
+                match find_column_key(
+                    &keys.column_keys,
+                    &keys.column_path_keys,
+                    column_path.parts(),
+                ) {
+                    Some((_, key)) => Ok(Cow::Borrowed(key)),
+                    None => Err(general_err!(
+                        "No column decryption key set for encrypted column '{}'",
+                        column_path.string()
+                    )),
+                }
+            }
+            DecryptionKeys::ViaRetriever(_) => self.column_key(&column_path.string(), key_metadata),
+        }
+    }
+
     /// Get the column names and associated decryption keys that have been configured.
     /// If a key retriever is used rather than explicit decryption keys, the result
     /// will be empty.
@@ -440,11 +734,83 @@ impl FileDecryptionProperties {
     pub fn uses_key_retriever(&self) -> bool {
         matches!(self.keys, DecryptionKeys::ViaRetriever(_))
     }
+
+    /// Check that these decryption properties can decrypt a file without reading any
+    /// column data, for example to confirm keys are correct before starting a large scan.
+    ///
+    /// This decrypts the file footer, then the first page header of each encrypted column
+    /// in the first row group only, and returns an error naming the first column whose key
+    /// is missing or wrong. Keys used only by later row groups are not checked.
+    /// No key usage events or metrics are reported for the validation reads.
+    pub fn validate_against<R: ChunkReader>(&self, file: &R) -> Result<()> {
+        let properties = Self {
+            key_usage_callback: None,
+            metrics: None,
+            ..self.clone()
+        };
+        let metadata = ParquetMetaDataReader::new()
+            .with_decryption_properties(Some(Arc::new(properties)))
+            .parse_and_finish(file)?;
+        let (Some(file_decryptor), Some(row_group)) =
+            (metadata.file_decryptor(), metadata.row_groups().first())
+        else {
+            return Ok(());
+        };
+
+        let mut ciphertext = Vec::new();
+        let mut plaintext = Vec::new();
+        for (column_idx, column) in row_group.columns().iter().enumerate() {
+            let Some(crypto_context) =
+                CryptoContext::for_column_chunk(Some(file_decryptor), column, 0, column_idx)?
+            else {
+                continue;
+            };
+            let (crypto_context, offset) = match column.dictionary_page_offset() {
+                Some(offset) => (crypto_context.for_dictionary_page(), offset),
+                None => (
+                    crypto_context.with_page_ordinal(0),
+                    column.data_page_offset(),
+                ),
+            };
+            let offset = u64::try_from(offset)
+                .map_err(|_| general_err!("Invalid page offset {}", offset))?;
+            read_and_decrypt_into(
+                crypto_context.metadata_decryptor(),
+                &mut file.get_read(offset)?,
+                &crypto_context.create_page_header_aad()?,
+                &mut ciphertext,
+                &mut plaintext,
+            )
+            .map_err(|e| {
+                e.with_context(format_args!(
+                    "Unable to decrypt page header of column '{}'",
+                    column.column_path().string()
+                ))
+            })?;
+        }
+        Ok(())
+    }
 }
 
+/// Key material is redacted so that keys can't leak into logs
 impl std::fmt::Debug for FileDecryptionProperties {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "FileDecryptionProperties {{ }}")
+        f.debug_struct("FileDecryptionProperties")
+            .field("keys", &self.keys)
+            .field("aad_prefix", &self.aad_prefix)
+            .field(
+                "footer_signature_verification",
+                &self.footer_signature_verification,
+            )
+            .field("footer_only", &self.footer_only)
+            .field("module_key_derivation", &self.module_key_derivation)
+            .field("aead_backend", &self.aead_backend)
+            .field(
+                "key_usage_callback",
+                &self.key_usage_callback.as_ref().map(|_| Redacted),
+            )
+            .field("metrics", &self.metrics)
+            .finish()
     }
 }
 
@@ -454,8 +820,14 @@ impl std::fmt::Debug for FileDecryptionProperties {
 pub struct DecryptionPropertiesBuilder {
     footer_key: Vec<u8>,
     column_keys: HashMap<String, Vec<u8>>,
+    column_path_keys: HashSet<String>,
     aad_prefix: Option<Vec<u8>>,
     footer_signature_verification: bool,
+    footer_only: bool,
+    module_key_derivation: bool,
+    aead_backend: Arc<dyn AeadBackend>,
+    key_usage_callback: Option<KeyUsageCallback>,
+    metrics: Option<Arc<dyn EncryptionMetrics>>,
 }
 
 impl DecryptionPropertiesBuilder {
@@ -465,39 +837,79 @@ impl DecryptionPropertiesBuilder {
         Self {
             footer_key,
             column_keys: HashMap::default(),
+            column_path_keys: HashSet::default(),
             aad_prefix: None,
             footer_signature_verification: true,
+            footer_only: false,
+            module_key_derivation: false,
+            aead_backend: Arc::new(RingAeadBackend),
+            key_usage_callback: None,
+            metrics: None,
         }
     }
 
     /// Finalize the builder and return created [`FileDecryptionProperties`]
     pub fn build(self) -> Result<Arc<FileDecryptionProperties>> {
-        let keys = DecryptionKeys::Explicit(ExplicitDecryptionKeys {
+        validate_aad_prefix(self.aad_prefix.as_deref())?;
+        let keys = DecryptionKeys::Explicit(Arc::new(ExplicitDecryptionKeys {
             footer_key: self.footer_key,
             column_keys: self.column_keys,
-        });
+            column_path_keys: self.column_path_keys,
+        }));
         Ok(Arc::new(FileDecryptionProperties {
             keys,
             aad_prefix: self.aad_prefix,
             footer_signature_verification: self.footer_signature_verification,
+            footer_only: self.footer_only,
+            module_key_derivation: self.module_key_derivation,
+            aead_backend: self.aead_backend,
+            key_usage_callback: self.key_usage_callback,
+            metrics: self.metrics,
         }))
     }
 
     /// Specify the expected AAD prefix to be used for decryption.
     /// This must be set if the file was written with an AAD prefix and the
     /// prefix is not stored in the file metadata.
+    /// The prefix may be at most
+    /// [`MAX_AAD_PREFIX_LEN`](crate::encryption::modules::MAX_AAD_PREFIX_LEN) bytes long.
     pub fn with_aad_prefix(mut self, value: Vec<u8>) -> Self {
         self.aad_prefix = Some(value);
         self
     }
 
-    /// Specify the decryption key to use for a column
+    /// Specify the decryption key to use for a column, identified by its dotted path.
+    ///
+    /// Dots within field names aren't escaped, so this is ambiguous if a field name
+    /// contains a dot. Use [`Self::with_column_path_key`] to identify such columns
+    /// unambiguously.
     pub fn with_column_key(mut self, column_name: &str, decryption_key: Vec<u8>) -> Self {
+        self.column_path_keys.remove(column_name);
         self.column_keys
             .insert(column_name.to_string(), decryption_key);
         self
     }
 
+    /// Specify the decryption key to use for a column, identified by its path parts.
+    ///
+    /// The key is stored under the column's dotted path with any `.` or `\` within
+    /// a field name escaped with a backslash, which is the name returned by
+    /// [`FileDecryptionProperties::column_keys`].
+    pub fn with_column_path_key(self, column_path: &ColumnPath, decryption_key: Vec<u8>) -> Self {
+        self.with_escaped_column_path_key(escaped_column_path(column_path.parts()), decryption_key)
+    }
+
+    /// Specify the decryption key to use for a column, identified by its escaped path
+    pub(crate) fn with_escaped_column_path_key(
+        mut self,
+        column_name: String,
+        decryption_key: Vec<u8>,
+    ) -> Self {
+        self.column_path_keys.insert(column_name.clone());
+        self.column_keys.insert(column_name, decryption_key);
+        self
+    }
+
     /// Specify multiple column decryption keys
     pub fn with_column_keys(mut self, column_names: Vec<&str>, keys: Vec<Vec<u8>>) -> Result<Self> {
         if column_names.len() != keys.len() {
@@ -508,6 +920,7 @@ impl DecryptionPropertiesBuilder {
             ));
         }
         for (column_name, key) in column_names.into_iter().zip(keys.into_iter()) {
+            self.column_path_keys.remove(column_name);
             self.column_keys.insert(column_name.to_string(), key);
         }
         Ok(self)
@@ -519,6 +932,45 @@ impl DecryptionPropertiesBuilder {
         self.footer_signature_verification = false;
         self
     }
+
+    /// Only decrypt the footer when reading metadata, leaving the metadata of encrypted
+    /// columns encrypted. No column keys are used, so the file schema and row counts can be
+    /// read with only the footer key. Column statistics and page indexes of encrypted columns
+    /// won't be available, and properties built this way can't be used to read column data.
+    pub fn with_footer_only_decryption(mut self) -> Self {
+        self.footer_only = true;
+        self
+    }
+
+    /// Decrypt files written with
+    /// [`EncryptionPropertiesBuilder::with_module_key_derivation`], which use separate keys
+    /// derived from each column key for page data and for page headers and other metadata.
+    /// This isn't part of the Parquet specification. Defaults to false.
+    ///
+    /// [`EncryptionPropertiesBuilder::with_module_key_derivation`]: crate::encryption::encrypt::EncryptionPropertiesBuilder::with_module_key_derivation
+    pub fn with_module_key_derivation(mut self, module_key_derivation: bool) -> Self {
+        self.module_key_derivation = module_key_derivation;
+        self
+    }
+
+    /// Set the cipher backend used to decrypt data. Defaults to [`RingAeadBackend`].
+    pub fn with_aead_backend(mut self, aead_backend: Arc<dyn AeadBackend>) -> Self {
+        self.aead_backend = aead_backend;
+        self
+    }
+
+    /// Set a callback that is invoked each time a key is used to decrypt a module,
+    /// for example to keep an audit trail of key usage.
+    pub fn with_key_usage_callback(mut self, callback: KeyUsageCallback) -> Self {
+        self.key_usage_callback = Some(callback);
+        self
+    }
+
+    /// Set a sink to report counts of decrypted pages and bytes, and of key retrievals, to
+    pub fn with_metrics(mut self, metrics: Arc<dyn EncryptionMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
 }
 
 /// Builder for [`FileDecryptionProperties`] that uses a [`KeyRetriever`]
@@ -528,6 +980,11 @@ pub struct DecryptionPropertiesBuilderWithRetriever {
     key_retriever: Arc<dyn KeyRetriever>,
     aad_prefix: Option<Vec<u8>>,
     footer_signature_verification: bool,
+    footer_only: bool,
+    module_key_derivation: bool,
+    aead_backend: Arc<dyn AeadBackend>,
+    key_usage_callback: Option<KeyUsageCallback>,
+    metrics: Option<Arc<dyn EncryptionMetrics>>,
 }
 
 impl DecryptionPropertiesBuilderWithRetriever {
@@ -538,22 +995,35 @@ impl DecryptionPropertiesBuilderWithRetriever {
             key_retriever,
             aad_prefix: None,
             footer_signature_verification: true,
+            footer_only: false,
+            module_key_derivation: false,
+            aead_backend: Arc::new(RingAeadBackend),
+            key_usage_callback: None,
+            metrics: None,
         }
     }
 
     /// Finalize the builder and return created [`FileDecryptionProperties`]
     pub fn build(self) -> Result<Arc<FileDecryptionProperties>> {
+        validate_aad_prefix(self.aad_prefix.as_deref())?;
         let keys = DecryptionKeys::ViaRetriever(self.key_retriever);
         Ok(Arc::new(FileDecryptionProperties {
             keys,
             aad_prefix: self.aad_prefix,
             footer_signature_verification: self.footer_signature_verification,
+            footer_only: self.footer_only,
+            module_key_derivation: self.module_key_derivation,
+            aead_backend: self.aead_backend,
+            key_usage_callback: self.key_usage_callback,
+            metrics: self.metrics,
         }))
     }
 
     /// Specify the expected AAD prefix to be used for decryption.
     /// This must be set if the file was written with an AAD prefix and the
     /// prefix is not stored in the file metadata.
+    /// The prefix may be at most
+    /// [`MAX_AAD_PREFIX_LEN`](crate::encryption::modules::MAX_AAD_PREFIX_LEN) bytes long.
     pub fn with_aad_prefix(mut self, value: Vec<u8>) -> Self {
         self.aad_prefix = Some(value);
         self
@@ -565,18 +1035,114 @@ impl DecryptionPropertiesBuilderWithRetriever {
         self.footer_signature_verification = false;
         self
     }
+
+    /// Only decrypt the footer when reading metadata, leaving the metadata of encrypted
+    /// columns encrypted. No column keys are used, so the file schema and row counts can be
+    /// read with only the footer key. Column statistics and page indexes of encrypted columns
+    /// won't be available, and properties built this way can't be used to read column data.
+    pub fn with_footer_only_decryption(mut self) -> Self {
+        self.footer_only = true;
+        self
+    }
+
+    /// Decrypt files written with
+    /// [`EncryptionPropertiesBuilder::with_module_key_derivation`], which use separate keys
+    /// derived from each column key for page data and for page headers and other metadata.
+    /// This isn't part of the Parquet specification. Defaults to false.
+    ///
+    /// [`EncryptionPropertiesBuilder::with_module_key_derivation`]: crate::encryption::encrypt::EncryptionPropertiesBuilder::with_module_key_derivation
+    pub fn with_module_key_derivation(mut self, module_key_derivation: bool) -> Self {
+        self.module_key_derivation = module_key_derivation;
+        self
+    }
+
+    /// Set the cipher backend used to decrypt data. Defaults to [`RingAeadBackend`].
+    pub fn with_aead_backend(mut self, aead_backend: Arc<dyn AeadBackend>) -> Self {
+        self.aead_backend = aead_backend;
+        self
+    }
+
+    /// Set a callback that is invoked each time a key is used to decrypt a module,
+    /// for example to keep an audit trail of key usage.
+    pub fn with_key_usage_callback(mut self, callback: KeyUsageCallback) -> Self {
+        self.key_usage_callback = Some(callback);
+        self
+    }
+
+    /// Set a sink to report counts of decrypted pages and bytes, and of key retrievals, to
+    pub fn with_metrics(mut self, metrics: Arc<dyn EncryptionMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+}
+
+/// Block decryptors that have already been created for this file, keyed by the key bytes.
+/// Setting up a decryptor can be expensive, so this allows each distinct key
+/// to be set up only once and then shared between row groups and columns.
+#[derive(Default)]
+struct DecryptorCache {
+    gcm_decryptors: HashMap<Vec<u8>, Arc<dyn BlockDecryptor>>,
+    ctr_decryptors: HashMap<Vec<u8>, Arc<dyn BlockDecryptor>>,
+}
+
+/// The cache is keyed by key bytes, so only the number of entries is shown.
+impl std::fmt::Debug for DecryptorCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DecryptorCache")
+            .field("gcm_decryptors", &self.gcm_decryptors.len())
+            .field("ctr_decryptors", &self.ctr_decryptors.len())
+            .finish()
+    }
+}
+
+impl HeapSize for DecryptorCache {
+    fn heap_size(&self) -> usize {
+        // With the AES_GCM_V1 algorithm only the GCM map is used. With AES_GCM_CTR_V1,
+        // column keys appear in both maps as they're needed for metadata and page data.
+        self.gcm_decryptors.heap_size() + self.ctr_decryptors.heap_size()
+    }
+}
+
+impl HeapSize for Arc<dyn BlockDecryptor> {
+    fn heap_size(&self) -> usize {
+        2 * std::mem::size_of::<usize>()
+            + std::mem::size_of_val(self.as_ref())
+            + self.as_ref().heap_size()
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub(crate) struct FileDecryptor {
     decryption_properties: Arc<FileDecryptionProperties>,
+    algorithm: EncryptionAlgorithm,
     footer_decryptor: Arc<dyn BlockDecryptor>,
+    footer_data_decryptor: Arc<dyn BlockDecryptor>,
     file_aad: Vec<u8>,
+    aad_prefix_len: usize,
+    encrypted_footer: bool,
+    // Shared between clones so that concurrent column readers reuse decryptors
+    decryptor_cache: Arc<Mutex<DecryptorCache>>,
+}
+
+/// Keys are redacted by the properties' formatting, and cached decryptors are
+/// only counted. The footer decryptors are omitted as they hold no useful detail.
+impl std::fmt::Debug for FileDecryptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileDecryptor")
+            .field("decryption_properties", &self.decryption_properties)
+            .field("algorithm", &self.algorithm)
+            .field("file_aad", &self.file_aad)
+            .field("decryptor_cache", &self.decryptor_cache)
+            .finish_non_exhaustive()
+    }
 }
 
 impl PartialEq for FileDecryptor {
     fn eq(&self, other: &Self) -> bool {
-        self.decryption_properties == other.decryption_properties && self.file_aad == other.file_aad
+        self.decryption_properties == other.decryption_properties
+            && self.algorithm == other.algorithm
+            && self.file_aad == other.file_aad
+            && self.encrypted_footer == other.encrypted_footer
     }
 }
 
@@ -587,49 +1153,90 @@ impl PartialEq for FileDecryptor {
 /// and the result will be an underestimate.
 /// If the [`FileDecryptionProperties`] are shared between multiple files then the
 /// heap size may also be an overestimate.
+/// Decryptors cached while reading column data are included,
+/// so the size may grow as more columns are read.
 impl HeapSize for FileDecryptor {
     fn heap_size(&self) -> usize {
         self.decryption_properties.heap_size()
-            + (Arc::clone(&self.footer_decryptor) as Arc<dyn HeapSize>).heap_size()
+            + self.footer_decryptor.heap_size()
             + self.file_aad.heap_size()
+            + 2 * std::mem::size_of::<usize>()
+            + std::mem::size_of::<Mutex<DecryptorCache>>()
+            + self.decryptor_cache.lock().unwrap().heap_size()
+        // The footer data decryptor is not included as it either shares the
+        // footer decryptor or doesn't allocate on the heap.
     }
 }
 
 impl FileDecryptor {
     pub(crate) fn new(
         decryption_properties: &Arc<FileDecryptionProperties>,
+        algorithm: EncryptionAlgorithm,
         footer_key_metadata: Option<&[u8]>,
         aad_file_unique: Vec<u8>,
         aad_prefix: Vec<u8>,
     ) -> Result<Self> {
         let file_aad = [aad_prefix.as_slice(), aad_file_unique.as_slice()].concat();
         let footer_key = decryption_properties.footer_key(footer_key_metadata)?;
-        let footer_decryptor = RingGcmBlockDecryptor::new(&footer_key).map_err(|e| {
-            general_err!(
-                "Invalid footer key. {}",
-                e.to_string().replace("Parquet error: ", "")
-            )
+        let aead_backend = &decryption_properties.aead_backend;
+        let footer_decryptor = aead_backend.new_decryptor(&footer_key).map_err(|e| {
+            ParquetError::InvalidKeyError("Invalid footer key".to_string(), Box::new(e))
         })?;
+        let footer_data_decryptor = match algorithm {
+            EncryptionAlgorithm::AesGcmV1 => Arc::clone(&footer_decryptor),
+            EncryptionAlgorithm::AesGcmCtrV1 => {
+                aead_backend.new_ctr_decryptor(&footer_key).map_err(|e| {
+                    ParquetError::InvalidKeyError("Invalid footer key".to_string(), Box::new(e))
+                })?
+            }
+        };
 
         Ok(Self {
-            footer_decryptor: Arc::new(footer_decryptor),
+            algorithm,
+            footer_decryptor,
+            footer_data_decryptor,
             decryption_properties: Arc::clone(decryption_properties),
             file_aad,
+            aad_prefix_len: aad_prefix.len(),
+            encrypted_footer: false,
+            decryptor_cache: Arc::default(),
         })
     }
 
+    /// Record that the file has an encrypted footer, rather than a plaintext footer
+    pub(crate) fn with_encrypted_footer(mut self, encrypted_footer: bool) -> Self {
+        self.encrypted_footer = encrypted_footer;
+        self
+    }
+
+    /// Whether the file has an encrypted footer
+    pub(crate) fn encrypted_footer(&self) -> bool {
+        self.encrypted_footer
+    }
+
+    /// The algorithm used to encrypt the file
+    pub(crate) fn algorithm(&self) -> EncryptionAlgorithm {
+        self.algorithm
+    }
+
     pub(crate) fn get_footer_decryptor(&self) -> Result<Arc<dyn BlockDecryptor>> {
         Ok(self.footer_decryptor.clone())
     }
 
+    /// Get the decryptor for page data of columns encrypted with the footer key
+    pub(crate) fn get_footer_data_decryptor(&self) -> Arc<dyn BlockDecryptor> {
+        self.footer_data_decryptor.clone()
+    }
+
     /// Verify the signature of the footer
     pub(crate) fn verify_plaintext_footer_signature(&self, plaintext_footer: &[u8]) -> Result<()> {
         // Plaintext footer format is: [plaintext metadata, nonce, authentication tag]
-        let tag = &plaintext_footer[plaintext_footer.len() - TAG_LEN..];
         let aad = create_footer_aad(self.file_aad())?;
         let footer_decryptor = self.get_footer_decryptor()?;
 
+        // Computing the tag checks the footer is long enough to hold the nonce and tag
         let computed_tag = footer_decryptor.compute_plaintext_tag(&aad, plaintext_footer)?;
+        let tag = &plaintext_footer[plaintext_footer.len() - TAG_LEN..];
 
         if computed_tag != tag {
             return Err(general_err!(
@@ -643,25 +1250,137 @@ impl FileDecryptor {
 
     pub(crate) fn get_column_data_decryptor(
         &self,
-        column_name: &str,
+        column_path: &ColumnPath,
         key_metadata: Option<&[u8]>,
     ) -> Result<Arc<dyn BlockDecryptor>> {
-        let column_key = self
-            .decryption_properties
-            .column_key(column_name, key_metadata)?;
-        Ok(Arc::new(RingGcmBlockDecryptor::new(&column_key)?))
+        let column_key = self.get_column_key(column_path, key_metadata, ModuleType::DataPage)?;
+        self.get_or_create_decryptor(
+            &column_key,
+            self.algorithm == EncryptionAlgorithm::AesGcmCtrV1,
+        )
     }
 
     pub(crate) fn get_column_metadata_decryptor(
         &self,
-        column_name: &str,
+        column_path: &ColumnPath,
         key_metadata: Option<&[u8]>,
     ) -> Result<Arc<dyn BlockDecryptor>> {
-        // Once GCM CTR mode is implemented, data and metadata decryptors may be different
-        self.get_column_data_decryptor(column_name, key_metadata)
+        let column_key =
+            self.get_column_key(column_path, key_metadata, ModuleType::DataPageHeader)?;
+        self.get_or_create_decryptor(&column_key, false)
+    }
+
+    /// Get the key used to decrypt modules of a column, which is derived from the
+    /// column key for the module type if module key derivation is enabled
+    fn get_column_key(
+        &self,
+        column_path: &ColumnPath,
+        key_metadata: Option<&[u8]>,
+        module_type: ModuleType,
+    ) -> Result<Cow<'_, [u8]>> {
+        let column_key = self
+            .decryption_properties
+            .column_path_key(column_path, key_metadata)?;
+        if self.decryption_properties.module_key_derivation {
+            return Ok(Cow::Owned(derive_module_key(&column_key, module_type)?));
+        }
+        Ok(match column_key {
+            Cow::Borrowed(key) => Cow::Borrowed(key.as_slice()),
+            Cow::Owned(key) => Cow::Owned(key),
+        })
+    }
+
+    /// Get a cached decryptor for the key, or create one if this key hasn't been used yet
+    fn get_or_create_decryptor(&self, key: &[u8], ctr: bool) -> Result<Arc<dyn BlockDecryptor>> {
+        let mut cache = self.decryptor_cache.lock().unwrap();
+        let decryptors = if ctr {
+            &mut cache.ctr_decryptors
+        } else {
+            &mut cache.gcm_decryptors
+        };
+        if let Some(decryptor) = decryptors.get(key) {
+            return Ok(Arc::clone(decryptor));
+        }
+        let aead_backend = &self.decryption_properties.aead_backend;
+        let decryptor = if ctr {
+            aead_backend.new_ctr_decryptor(key)?
+        } else {
+            aead_backend.new_decryptor(key)?
+        };
+        decryptors.insert(key.to_vec(), Arc::clone(&decryptor));
+        Ok(decryptor)
     }
 
     pub(crate) fn file_aad(&self) -> &Vec<u8> {
         &self.file_aad
     }
+
+    /// The file unique part of the file AAD, which follows any AAD prefix
+    pub(crate) fn aad_file_unique(&self) -> &[u8] {
+        &self.file_aad[self.aad_prefix_len..]
+    }
+
+    /// Whether column metadata should be left encrypted
+    pub(crate) fn footer_only(&self) -> bool {
+        self.decryption_properties.footer_only
+    }
+
+    /// Notify any key usage callback that a key is used to decrypt a module
+    pub(crate) fn record_key_usage(&self, column_path: Option<&str>, module_type: ModuleType) {
+        self.decryption_properties
+            .record_key_usage(column_path, module_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_common::assert_send_sync;
+
+    #[test]
+    fn test_decryption_types_send_sync() {
+        assert_send_sync::<FileDecryptor>();
+        assert_send_sync::<FileDecryptionProperties>();
+        assert_send_sync::<DecryptionPropertiesBuilder>();
+        assert_send_sync::<DecryptionPropertiesBuilderWithRetriever>();
+        assert_send_sync::<CryptoContext>();
+    }
+
+    #[test]
+    fn test_build_validates_aad_prefix_length() {
+        use crate::encryption::modules::MAX_AAD_PREFIX_LEN;
+
+        let properties = FileDecryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_aad_prefix(vec![0; MAX_AAD_PREFIX_LEN])
+            .build()
+            .unwrap();
+        assert_eq!(properties.aad_prefix().unwrap().len(), MAX_AAD_PREFIX_LEN);
+
+        let err = FileDecryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_aad_prefix(vec![0; MAX_AAD_PREFIX_LEN + 1])
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: AAD prefix length 4097 exceeds the maximum of 4096 bytes"
+        );
+    }
+
+    #[test]
+    fn test_debug_redacts_keys() {
+        let properties = FileDecryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_column_key("x", b"1234567890123450".to_vec())
+            .with_aad_prefix(b"file".to_vec())
+            .build()
+            .unwrap();
+        let debug = format!("{properties:?}");
+        assert_eq!(
+            debug,
+            "FileDecryptionProperties { keys: Explicit { footer_key: .., column_keys: {\"x\": ..} }, \
+            aad_prefix: Some([102, 105, 108, 101]), footer_signature_verification: true, footer_only: false, \
+            module_key_derivation: false, aead_backend: RingAeadBackend, key_usage_callback: None, metrics: None }"
+        );
+        assert!(!debug.contains(&format!("{:?}", b"0123456789012345".to_vec())));
+        assert!(!debug.contains(&format!("{:?}", b"1234567890123450".to_vec())));
+    }
 }
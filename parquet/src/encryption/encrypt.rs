@@ -17,43 +17,251 @@
 
 //! Configuration and utilities for Parquet Modular Encryption
 
+use crate::encryption::backend::{AeadBackend, RandomSource, RingAeadBackend, SystemRandomSource};
 use crate::encryption::ciphers::{
-    BlockEncryptor, NONCE_LEN, RingGcmBlockEncryptor, SIZE_LEN, TAG_LEN,
+    BlockEncryptor, CryptoOperation, MODULE_PREFIX_LEN, NONCE_LEN, SIZE_LEN, TAG_LEN,
+    constant_time_eq, derive_module_key,
 };
+use crate::encryption::decrypt::FileDecryptionProperties;
+use crate::encryption::metrics::EncryptionMetrics;
+use crate::encryption::modules::{ModuleType, create_footer_aad, validate_aad_prefix};
 use crate::errors::{ParquetError, Result};
 use crate::file::column_crypto_metadata::{ColumnCryptoMetaData, EncryptionWithColumnKey};
 use crate::parquet_thrift::{ThriftCompactOutputProtocol, WriteThrift};
-use crate::schema::types::{ColumnDescPtr, SchemaDescriptor};
+use crate::schema::types::{ColumnDescPtr, ColumnPath, SchemaDescriptor};
 use ring::rand::{SecureRandom, SystemRandom};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::io::Write;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
 
-#[derive(Debug, Clone, PartialEq)]
-struct EncryptionKey {
-    key: Vec<u8>,
+/// An encryption key, with optional metadata that allows readers to recover the key
+///
+/// # Example
+///
+/// ```
+/// # use parquet::encryption::encrypt::EncryptionKey;
+/// let key = EncryptionKey::new_with_metadata(b"0123456789012345".to_vec(), b"kf".to_vec());
+/// assert_eq!(key.key(), b"0123456789012345");
+/// assert_eq!(key.key_metadata(), Some(b"kf".as_slice()));
+/// ```
+///
+/// Keys compare equal with `==` only if both the key bytes and the key metadata are equal.
+/// Use [`EncryptionKey::same_key`] to compare only the key bytes, for example to check
+/// whether a key with new metadata is still the same key.
+///
+/// The key bytes are reference counted, so cloning a key doesn't copy them.
+/// Use [`EncryptionKey::from_arc`] to share key bytes that are already held in an [`Arc`],
+/// for example in a key store that provides the same key for many files.
+#[derive(Clone, PartialEq)]
+pub struct EncryptionKey {
+    key: Arc<[u8]>,
     key_metadata: Option<Vec<u8>>,
 }
 
 impl EncryptionKey {
-    fn new(key: Vec<u8>) -> EncryptionKey {
+    /// Create a new [`EncryptionKey`] without key metadata
+    pub fn new(key: Vec<u8>) -> EncryptionKey {
+        Self::from_arc(key.into())
+    }
+
+    /// Create a new [`EncryptionKey`] without key metadata that shares the given key bytes
+    /// rather than copying them
+    pub fn from_arc(key: Arc<[u8]>) -> EncryptionKey {
         Self {
             key,
             key_metadata: None,
         }
     }
 
-    fn with_metadata(mut self, metadata: Vec<u8>) -> Self {
+    /// Create a new [`EncryptionKey`] with the given key metadata
+    pub fn new_with_metadata(key: Vec<u8>, metadata: Vec<u8>) -> EncryptionKey {
+        Self::new(key).with_metadata(metadata)
+    }
+
+    /// Generate a new random key of `len` bytes using a cryptographically secure
+    /// random number generator. The length must be 16 or 32 bytes, for AES-128 or AES-256.
+    pub fn generate(len: usize) -> Result<EncryptionKey> {
+        if len != 16 && len != 32 {
+            return Err(general_err!(
+                "Invalid key length {}. Keys must be 16 or 32 bytes as only AES-128 and AES-256 are supported",
+                len
+            ));
+        }
+        let mut key = vec![0u8; len];
+        SystemRandom::new()
+            .fill(&mut key)
+            .map_err(CryptoOperation::RandomFill.error())?;
+        Ok(Self::new(key))
+    }
+
+    /// Set the metadata used by readers to retrieve this key, for example a key identifier
+    pub fn with_metadata(mut self, metadata: Vec<u8>) -> Self {
         self.key_metadata = Some(metadata);
         self
     }
 
-    fn key(&self) -> &Vec<u8> {
+    /// Set a UTF-8 key identifier as the key metadata, for example the ID of a key
+    /// managed by a Key Management Server
+    pub fn with_key_id(self, key_id: &str) -> Self {
+        self.with_metadata(key_id.as_bytes().to_vec())
+    }
+
+    /// The key bytes
+    pub fn key(&self) -> &[u8] {
         &self.key
     }
+
+    /// Whether this key has the same key bytes as `other`, ignoring any key metadata.
+    /// The comparison takes the same time for any keys of the same length.
+    pub fn same_key(&self, other: &EncryptionKey) -> bool {
+        constant_time_eq(&self.key, &other.key)
+    }
+
+    /// The metadata used by readers to retrieve this key, if set
+    pub fn key_metadata(&self) -> Option<&[u8]> {
+        self.key_metadata.as_deref()
+    }
+
+    /// The key metadata interpreted as a UTF-8 key identifier.
+    /// Returns `None` if no metadata is set or it is not valid UTF-8.
+    pub fn key_id(&self) -> Option<&str> {
+        self.key_metadata()
+            .and_then(|metadata| std::str::from_utf8(metadata).ok())
+    }
+
+    /// Set a [`KeyId`] with a version as the key metadata, to support key rotation
+    pub fn with_versioned_key_id(self, key_id: &KeyId) -> Self {
+        self.with_metadata(key_id.to_key_metadata())
+    }
+
+    /// The key metadata parsed as a [`KeyId`].
+    /// Returns `None` if no metadata is set or it isn't a serialized [`KeyId`].
+    pub fn versioned_key_id(&self) -> Option<KeyId> {
+        self.key_metadata()
+            .and_then(|metadata| KeyId::from_key_metadata(metadata).ok())
+    }
+}
+
+/// A key identifier with a version, for identifying keys that are rotated.
+///
+/// This is stored in key metadata as the UTF-8 string `{id}#{version}`.
+/// The identifier may itself contain `#`, as the version follows the last `#`.
+///
+/// ```
+/// # use parquet::encryption::encrypt::KeyId;
+/// let key_id = KeyId::new("footer_key", 3);
+/// assert_eq!(key_id.to_key_metadata(), b"footer_key#3");
+/// assert_eq!(KeyId::from_key_metadata(b"footer_key#3")?, key_id);
+/// # Ok::<(), parquet::errors::ParquetError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyId {
+    /// The identifier of the key, for example the ID of a key managed by a
+    /// Key Management Server
+    pub id: String,
+    /// The version of the key
+    pub version: u32,
+}
+
+impl KeyId {
+    /// Create a new [`KeyId`]
+    pub fn new(id: impl Into<String>, version: u32) -> Self {
+        Self {
+            id: id.into(),
+            version,
+        }
+    }
+
+    /// Serialize this key identifier to key metadata bytes
+    pub fn to_key_metadata(&self) -> Vec<u8> {
+        self.to_string().into_bytes()
+    }
+
+    /// Parse a key identifier from key metadata bytes
+    pub fn from_key_metadata(key_metadata: &[u8]) -> Result<Self> {
+        let key_metadata = std::str::from_utf8(key_metadata)
+            .map_err(|e| general_err!("Key metadata is not a valid versioned key ID: {}", e))?;
+        let Some((id, version)) = key_metadata.rsplit_once('#') else {
+            return Err(general_err!(
+                "Key metadata '{}' is not a valid versioned key ID, expected '<id>#<version>'",
+                key_metadata
+            ));
+        };
+        let version = version.parse().map_err(|_| {
+            general_err!(
+                "Key metadata '{}' is not a valid versioned key ID, invalid version '{}'",
+                key_metadata,
+                version
+            )
+        })?;
+        Ok(Self::new(id, version))
+    }
+}
+
+impl std::fmt::Display for KeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}#{}", self.id, self.version)
+    }
+}
+
+/// Key material is redacted so that keys can't leak into logs
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptionKey")
+            .field("len", &self.key.len())
+            .field("metadata", &self.key_metadata.as_ref().map(|_| Redacted))
+            .finish()
+    }
+}
+
+/// Formats as `..` in place of secret values
+pub(crate) struct Redacted;
+
+impl std::fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("..")
+    }
+}
+
+/// The algorithm used to encrypt a Parquet file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EncryptionAlgorithm {
+    /// All modules are encrypted with AES GCM
+    #[default]
+    AesGcmV1,
+    /// Page data is encrypted with AES CTR, which is faster but doesn't provide
+    /// integrity verification. All other modules are encrypted with AES GCM.
+    AesGcmCtrV1,
+}
+
+impl EncryptionAlgorithm {
+    /// The number of bytes added to each encrypted page body.
+    ///
+    /// Every encrypted module has a 4 byte length prefix and a 12 byte nonce.
+    /// AES GCM also appends a 16 byte authentication tag, so GCM encrypted modules are
+    /// 32 bytes larger than their plaintext and AES CTR encrypted page bodies are 16 bytes larger.
+    pub fn page_data_overhead(&self) -> usize {
+        match self {
+            Self::AesGcmV1 => SIZE_LEN + NONCE_LEN + TAG_LEN,
+            Self::AesGcmCtrV1 => SIZE_LEN + NONCE_LEN,
+        }
+    }
+
+    /// The number of bytes added to each encrypted metadata module, including page headers.
+    /// These are always encrypted with AES GCM.
+    pub fn metadata_overhead(&self) -> usize {
+        SIZE_LEN + NONCE_LEN + TAG_LEN
+    }
+
+    /// The total number of bytes added by encrypting a page's header and body.
+    pub fn overhead_per_page(&self) -> usize {
+        self.metadata_overhead() + self.page_data_overhead()
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 /// Defines how data in a Parquet file should be encrypted
 ///
 /// The `FileEncryptionProperties` should be included in the [`WriterProperties`](crate::file::properties::WriterProperties)
@@ -66,6 +274,7 @@ impl EncryptionKey {
 /// ```
 /// # use parquet::encryption::encrypt::FileEncryptionProperties;
 /// let file_encryption_properties = FileEncryptionProperties::builder(b"0123456789012345".into())
+///     .with_uniform_encryption()
 ///     .build()?;
 /// # Ok::<(), parquet::errors::ParquetError>(())
 /// ```
@@ -86,6 +295,7 @@ impl EncryptionKey {
 /// ```
 /// # use parquet::encryption::encrypt::FileEncryptionProperties;
 /// let file_encryption_properties = FileEncryptionProperties::builder(b"0123456789012345".into())
+///     .with_uniform_encryption()
 ///     .with_aad_prefix("example_file".into())
 ///     .build()?;
 /// # Ok::<(), parquet::errors::ParquetError>(())
@@ -94,8 +304,36 @@ pub struct FileEncryptionProperties {
     encrypt_footer: bool,
     footer_key: EncryptionKey,
     column_keys: HashMap<String, EncryptionKey>,
+    column_path_keys: HashSet<String>,
+    unencrypted_columns: HashSet<String>,
     aad_prefix: Option<Vec<u8>>,
     store_aad_prefix: bool,
+    store_key_metadata: bool,
+    plaintext_columns: bool,
+    module_key_derivation: bool,
+    algorithm: EncryptionAlgorithm,
+    aead_backend: Arc<dyn AeadBackend>,
+    random_source: Arc<dyn RandomSource>,
+    aad_file_unique: Option<Vec<u8>>,
+    metrics: Option<Arc<dyn EncryptionMetrics>>,
+}
+
+impl PartialEq for FileEncryptionProperties {
+    fn eq(&self, other: &Self) -> bool {
+        // The cipher backend and random source are not compared as they only change how,
+        // not what, data is encrypted
+        self.encrypt_footer == other.encrypt_footer
+            && self.footer_key == other.footer_key
+            && self.column_keys == other.column_keys
+            && self.column_path_keys == other.column_path_keys
+            && self.unencrypted_columns == other.unencrypted_columns
+            && self.aad_prefix == other.aad_prefix
+            && self.store_aad_prefix == other.store_aad_prefix
+            && self.store_key_metadata == other.store_key_metadata
+            && self.plaintext_columns == other.plaintext_columns
+            && self.module_key_derivation == other.module_key_derivation
+            && self.algorithm == other.algorithm
+    }
 }
 
 impl FileEncryptionProperties {
@@ -115,23 +353,22 @@ impl FileEncryptionProperties {
     }
 
     /// Retrieval of key used for encryption of footer and (possibly) columns
-    pub fn footer_key(&self) -> &Vec<u8> {
+    pub fn footer_key(&self) -> &[u8] {
         &self.footer_key.key
     }
 
-    /// Get the column names, keys, and metadata for columns to be encrypted
-    pub fn column_keys(&self) -> (Vec<String>, Vec<Vec<u8>>, Vec<Vec<u8>>) {
-        let mut column_names: Vec<String> = Vec::with_capacity(self.column_keys.len());
-        let mut keys: Vec<Vec<u8>> = Vec::with_capacity(self.column_keys.len());
-        let mut meta: Vec<Vec<u8>> = Vec::with_capacity(self.column_keys.len());
-        for (key, value) in self.column_keys.iter() {
-            column_names.push(key.clone());
-            keys.push(value.key.clone());
-            if let Some(metadata) = value.key_metadata.as_ref() {
-                meta.push(metadata.clone());
-            }
-        }
-        (column_names, keys, meta)
+    /// Get the paths of columns encrypted with column specific keys, together with
+    /// the metadata of their keys. Columns are returned in no particular order.
+    pub fn column_keys(&self) -> impl Iterator<Item = (&str, Option<&[u8]>)> {
+        self.column_keys
+            .iter()
+            .map(|(column_path, key)| (column_path.as_str(), key.key_metadata()))
+    }
+
+    /// Get the columns that are left unencrypted when all other columns are
+    /// encrypted with the footer key
+    pub fn unencrypted_columns(&self) -> &HashSet<String> {
+        &self.unencrypted_columns
     }
 
     /// AAD prefix string uniquely identifies the file and prevents file swapping
@@ -144,6 +381,119 @@ impl FileEncryptionProperties {
         self.store_aad_prefix && self.aad_prefix.is_some()
     }
 
+    /// Should the metadata of column keys be stored in the column crypto metadata
+    pub fn store_key_metadata(&self) -> bool {
+        self.store_key_metadata
+    }
+
+    /// Whether all columns are left unencrypted, so only the footer is encrypted or signed
+    pub fn plaintext_columns(&self) -> bool {
+        self.plaintext_columns
+    }
+
+    /// Whether keys for page data and page headers are derived from column keys.
+    /// See [`EncryptionPropertiesBuilder::with_module_key_derivation`].
+    pub fn module_key_derivation(&self) -> bool {
+        self.module_key_derivation
+    }
+
+    /// The algorithm used to encrypt the file
+    pub fn algorithm(&self) -> EncryptionAlgorithm {
+        self.algorithm
+    }
+
+    /// The cipher backend used to create encryptors
+    pub fn aead_backend(&self) -> &Arc<dyn AeadBackend> {
+        &self.aead_backend
+    }
+
+    /// The source of random bytes used for the unique file identifier and nonces
+    pub fn random_source(&self) -> &Arc<dyn RandomSource> {
+        &self.random_source
+    }
+
+    /// The sink that encryption metrics are reported to, if one was set with
+    /// [`EncryptionPropertiesBuilder::with_metrics`]
+    pub fn metrics(&self) -> Option<&Arc<dyn EncryptionMetrics>> {
+        self.metrics.as_ref()
+    }
+
+    /// The fixed unique file identifier used in the AAD suffix, if one was set with
+    /// [`EncryptionPropertiesBuilder::with_aad_file_unique`]
+    pub fn aad_file_unique(&self) -> Option<&[u8]> {
+        self.aad_file_unique.as_deref()
+    }
+
+    /// Create [`FileDecryptionProperties`] that can read files written with these properties.
+    ///
+    /// The footer key, column keys and AAD prefix are copied, along with the cipher backend
+    /// and whether module keys are derived. Metrics sinks are not copied.
+    pub fn to_decryption_properties(&self) -> Result<Arc<FileDecryptionProperties>> {
+        let mut builder = FileDecryptionProperties::builder(self.footer_key.key.to_vec())
+            .with_module_key_derivation(self.module_key_derivation)
+            .with_aead_backend(Arc::clone(&self.aead_backend));
+        if let Some(aad_prefix) = &self.aad_prefix {
+            builder = builder.with_aad_prefix(aad_prefix.clone());
+        }
+        for (column_name, key) in &self.column_keys {
+            builder = if self.column_path_keys.contains(column_name) {
+                builder.with_escaped_column_path_key(column_name.clone(), key.key.to_vec())
+            } else {
+                builder.with_column_key(column_name, key.key.to_vec())
+            };
+        }
+        builder.build()
+    }
+
+    /// Get the crypto metadata to be written to the column chunk metadata of a column,
+    /// or `None` if the column is not encrypted
+    ///
+    /// This is useful when building column chunk metadata outside of the
+    /// [`SerializedFileWriter`](crate::file::writer::SerializedFileWriter).
+    pub fn column_crypto_metadata(&self, column: &ColumnDescPtr) -> Option<ColumnCryptoMetaData> {
+        if self.is_uniform_encryption() {
+            if self.is_excluded_from_uniform_encryption(column.path()) {
+                None
+            } else {
+                Some(ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY)
+            }
+        } else {
+            self.column_key(column.path()).map(|encryption_key| {
+                // Column is encrypted with a column specific key
+                ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(EncryptionWithColumnKey {
+                    path_in_schema: column.path().parts().to_vec(),
+                    key_metadata: if self.store_key_metadata {
+                        encryption_key.key_metadata.clone()
+                    } else {
+                        None
+                    },
+                })
+            })
+        }
+    }
+
+    /// Returns whether the footer key is used to encrypt all columns
+    /// that haven't been configured as unencrypted
+    fn is_uniform_encryption(&self) -> bool {
+        self.column_keys.is_empty()
+    }
+
+    /// Returns whether a column is left unencrypted when uniform encryption is used
+    fn is_excluded_from_uniform_encryption(&self, column_path: &ColumnPath) -> bool {
+        self.plaintext_columns || self.unencrypted_columns.contains(&column_path.string())
+    }
+
+    /// Get the key configured for a column, which may have been set with either
+    /// the column's path parts or its dotted path
+    fn column_key(&self, column_path: &ColumnPath) -> Option<&EncryptionKey> {
+        find_column_key(
+            &self.column_keys,
+            &self.column_path_keys,
+            column_path.parts(),
+        )
+        .map(|(_, key)| key)
+    }
+
     /// Checks if columns that are to be encrypted are present in schema
     pub(crate) fn validate_encrypted_column_names(
         &self,
@@ -154,16 +504,22 @@ impl FileEncryptionProperties {
             .iter()
             .map(|c| c.path().string())
             .collect::<HashSet<_>>();
-        let encryption_columns = self
+        let escaped_column_paths = schema
+            .columns()
+            .iter()
+            .map(|c| escaped_column_path(c.path().parts()))
+            .collect::<HashSet<_>>();
+        // Keys set from path parts must match a column's escaped path exactly
+        let mut columns_missing_in_schema = self
             .column_keys
             .keys()
+            .filter(|name| {
+                !escaped_column_paths.contains(*name)
+                    && (self.column_path_keys.contains(*name) || !column_paths.contains(*name))
+            })
             .cloned()
-            .collect::<HashSet<String>>();
-        if !encryption_columns.is_subset(&column_paths) {
-            let mut columns_missing_in_schema = encryption_columns
-                .difference(&column_paths)
-                .cloned()
-                .collect::<Vec<String>>();
+            .collect::<Vec<String>>();
+        if !columns_missing_in_schema.is_empty() {
             columns_missing_in_schema.sort();
             return Err(ParquetError::General(
                 format!(
@@ -173,6 +529,18 @@ impl FileEncryptionProperties {
                 .to_string(),
             ));
         }
+        let mut unencrypted_columns_missing_in_schema = self
+            .unencrypted_columns
+            .difference(&column_paths)
+            .cloned()
+            .collect::<Vec<String>>();
+        if !unencrypted_columns_missing_in_schema.is_empty() {
+            unencrypted_columns_missing_in_schema.sort();
+            return Err(general_err!(
+                "The following columns specified as unencrypted were not found in the schema: {}",
+                unencrypted_columns_missing_in_schema.join(", ")
+            ));
+        }
         Ok(())
     }
 }
@@ -184,8 +552,19 @@ pub struct EncryptionPropertiesBuilder {
     encrypt_footer: bool,
     footer_key: EncryptionKey,
     column_keys: HashMap<String, EncryptionKey>,
+    column_path_keys: HashSet<String>,
+    unencrypted_columns: HashSet<String>,
+    uniform_encryption: bool,
     aad_prefix: Option<Vec<u8>>,
     store_aad_prefix: bool,
+    store_key_metadata: bool,
+    plaintext_columns: bool,
+    module_key_derivation: bool,
+    algorithm: EncryptionAlgorithm,
+    aead_backend: Arc<dyn AeadBackend>,
+    random_source: Arc<dyn RandomSource>,
+    aad_file_unique: Option<Vec<u8>>,
+    metrics: Option<Arc<dyn EncryptionMetrics>>,
 }
 
 impl EncryptionPropertiesBuilder {
@@ -194,9 +573,20 @@ impl EncryptionPropertiesBuilder {
         Self {
             footer_key: EncryptionKey::new(footer_key),
             column_keys: HashMap::default(),
+            column_path_keys: HashSet::default(),
+            unencrypted_columns: HashSet::default(),
+            uniform_encryption: false,
             aad_prefix: None,
             encrypt_footer: true,
             store_aad_prefix: false,
+            store_key_metadata: true,
+            plaintext_columns: false,
+            module_key_derivation: false,
+            algorithm: EncryptionAlgorithm::default(),
+            aead_backend: Arc::new(RingAeadBackend),
+            random_source: Arc::new(SystemRandomSource),
+            metrics: None,
+            aad_file_unique: None,
         }
     }
 
@@ -206,25 +596,82 @@ impl EncryptionPropertiesBuilder {
         self
     }
 
+    /// Set the key used for encryption of the footer and (possibly) columns, together with
+    /// its retrieval metadata. This replaces the footer key the builder was created with.
+    pub fn with_footer_key(mut self, footer_key: EncryptionKey) -> Self {
+        self.footer_key = footer_key;
+        self
+    }
+
     /// Set retrieval metadata of key used for encryption of footer and (possibly) columns
     pub fn with_footer_key_metadata(mut self, metadata: Vec<u8>) -> Self {
         self.footer_key = self.footer_key.with_metadata(metadata);
         self
     }
 
-    /// Set the key used for encryption of a column. Note that if no column keys are configured then
-    /// all columns will be encrypted with the footer key.
+    /// Set a versioned [`KeyId`] as the retrieval metadata of the footer key.
+    /// Readers can use a [`VersionedKeyRetriever`] to receive the parsed [`KeyId`].
+    ///
+    /// [`VersionedKeyRetriever`]: crate::encryption::decrypt::VersionedKeyRetriever
+    pub fn with_footer_key_id(mut self, key_id: &KeyId) -> Self {
+        self.footer_key = self.footer_key.with_versioned_key_id(key_id);
+        self
+    }
+
+    /// Encrypt all columns with the footer key, other than any columns set with
+    /// [`Self::with_unencrypted_column`].
+    ///
+    /// This must be set to build properties without column keys, unless all columns are
+    /// left unencrypted with [`Self::with_plaintext_columns`], so that forgetting to add
+    /// column keys is an error rather than silently encrypting all columns with the footer key.
+    /// This can't be combined with column keys.
+    pub fn with_uniform_encryption(mut self) -> Self {
+        self.uniform_encryption = true;
+        self
+    }
+
+    /// Set the key used for encryption of a column.
     /// If any column keys are configured then only the columns with a key will be encrypted.
+    ///
+    /// The column is identified by its dotted path, for example `a.b` for field `b`
+    /// nested within `a`. Dots within field names aren't escaped, so this is ambiguous
+    /// if a field name contains a dot. Use [`Self::with_column_path_key`] to identify
+    /// such columns unambiguously.
     pub fn with_column_key(mut self, column_name: &str, key: Vec<u8>) -> Self {
+        self.column_path_keys.remove(column_name);
         self.column_keys
             .insert(column_name.to_string(), EncryptionKey::new(key));
         self
     }
 
+    /// Set the key used for encryption of a column identified by its path parts.
+    /// If any column keys are configured then only the columns with a key will be encrypted.
+    ///
+    /// Unlike [`Self::with_column_key`], this distinguishes a top-level field named `a.b`
+    /// from field `b` nested within `a`:
+    /// ```
+    /// # use parquet::encryption::encrypt::FileEncryptionProperties;
+    /// # use parquet::schema::types::ColumnPath;
+    /// let file_encryption_properties = FileEncryptionProperties::builder(b"0123456789012345".into())
+    ///     .with_column_path_key(&ColumnPath::new(vec!["a.b".to_string()]), b"1234567890123450".into())
+    ///     .build()?;
+    /// # Ok::<(), parquet::errors::ParquetError>(())
+    /// ```
+    ///
+    /// Keys set this way are stored under the column's dotted path, with any `.` or `\`
+    /// within a field name escaped with a backslash, which is the name returned by
+    /// [`FileEncryptionProperties::column_keys`].
+    pub fn with_column_path_key(mut self, column_path: &ColumnPath, key: Vec<u8>) -> Self {
+        let column_name = escaped_column_path(column_path.parts());
+        self.column_path_keys.insert(column_name.clone());
+        self.column_keys
+            .insert(column_name, EncryptionKey::new(key));
+        self
+    }
+
     /// Set the key used for encryption of a column and its metadata. The Key's metadata field is to
     /// enable file readers to recover the key. For example, the metadata can keep a serialized
-    /// ID of a data key. Note that if no column keys are configured then all columns
-    /// will be encrypted with the footer key. If any column keys are configured then only the
+    /// ID of a data key. If any column keys are configured then only the
     /// columns with a key will be encrypted.
     pub fn with_column_key_and_metadata(
         mut self,
@@ -232,6 +679,7 @@ impl EncryptionPropertiesBuilder {
         key: Vec<u8>,
         metadata: Vec<u8>,
     ) -> Self {
+        self.column_path_keys.remove(column_name);
         self.column_keys.insert(
             column_name.to_string(),
             EncryptionKey::new(key).with_metadata(metadata),
@@ -239,6 +687,15 @@ impl EncryptionPropertiesBuilder {
         self
     }
 
+    /// Set the key used for encryption of a column, together with any retrieval metadata.
+    /// This allows a key created with [`EncryptionKey::from_arc`] to be used without
+    /// copying the key bytes. Otherwise this behaves the same as [`Self::with_column_key`].
+    pub fn with_column_encryption_key(mut self, column_name: &str, key: EncryptionKey) -> Self {
+        self.column_path_keys.remove(column_name);
+        self.column_keys.insert(column_name.to_string(), key);
+        self
+    }
+
     /// Set the keys used for encryption of columns. Analogous to
     /// with_column_key but for multiple columns. This will add column keys provided to the
     /// existing column keys. If column keys were already provided for some columns, the new keys
@@ -252,16 +709,51 @@ impl EncryptionPropertiesBuilder {
             ));
         }
         for (i, column_name) in column_names.into_iter().enumerate() {
+            self.column_path_keys.remove(column_name);
             self.column_keys
                 .insert(column_name.to_string(), EncryptionKey::new(keys[i].clone()));
         }
         Ok(self)
     }
 
+    /// Leave a column unencrypted when [uniform encryption](Self::with_uniform_encryption)
+    /// is used and all other columns are encrypted with the footer key.
+    /// This can't be combined with column keys, as when column keys are configured,
+    /// columns without a key are already left unencrypted.
+    pub fn with_unencrypted_column(mut self, column_name: &str) -> Self {
+        self.unencrypted_columns.insert(column_name.to_string());
+        self
+    }
+
+    /// Leave all columns unencrypted, so that only the footer is encrypted, or signed if
+    /// the footer is plaintext. This protects the integrity of the file metadata while
+    /// allowing fast plaintext column scans. Defaults to false.
+    /// This can't be combined with column keys.
+    pub fn with_plaintext_columns(mut self, plaintext_columns: bool) -> Self {
+        self.plaintext_columns = plaintext_columns;
+        self
+    }
+
+    /// Encrypt the page data and the page headers and other metadata of columns with
+    /// column keys using separate keys, derived from each column key with HKDF-SHA256.
+    /// Columns encrypted with the footer key use the footer key directly. Defaults to false.
+    ///
+    /// This is an experimental option that isn't part of the Parquet specification,
+    /// so files written with it can only be read by this crate, with
+    /// [`DecryptionPropertiesBuilder::with_module_key_derivation`] enabled.
+    ///
+    /// [`DecryptionPropertiesBuilder::with_module_key_derivation`]: crate::encryption::decrypt::DecryptionPropertiesBuilder::with_module_key_derivation
+    pub fn with_module_key_derivation(mut self, module_key_derivation: bool) -> Self {
+        self.module_key_derivation = module_key_derivation;
+        self
+    }
+
     /// The AAD prefix uniquely identifies the file and allows to differentiate it e.g. from
     /// older versions of the file or from other partition files in the same data set (table).
     /// These bytes are optionally passed by a writer upon file creation. When not specified, no
-    /// AAD prefix is used.
+    /// AAD prefix is used. The prefix may be at most [`MAX_AAD_PREFIX_LEN`] bytes long.
+    ///
+    /// [`MAX_AAD_PREFIX_LEN`]: crate::encryption::modules::MAX_AAD_PREFIX_LEN
     pub fn with_aad_prefix(mut self, aad_prefix: Vec<u8>) -> Self {
         self.aad_prefix = Some(aad_prefix);
         self
@@ -269,37 +761,202 @@ impl EncryptionPropertiesBuilder {
 
     /// Should the AAD prefix be stored in the file. If false, readers will need to provide the
     /// AAD prefix to be able to decrypt data. Defaults to false.
+    /// An AAD prefix must be set with [`Self::with_aad_prefix`] if this is true.
     pub fn with_aad_prefix_storage(mut self, store_aad_prefix: bool) -> Self {
         self.store_aad_prefix = store_aad_prefix;
         self
     }
 
+    /// Should the metadata of column keys be stored in the file. Defaults to true.
+    ///
+    /// When false, the `key_metadata` of column crypto metadata is left unset even if column
+    /// keys were set with metadata, so files don't reveal which keys were used. Readers then
+    /// need to know the column keys out-of-band, for example by setting them explicitly with
+    /// [`DecryptionPropertiesBuilder::with_column_key`], as a [`KeyRetriever`] will be called
+    /// with empty key metadata. This doesn't affect the footer key metadata, which is only
+    /// stored if set with [`Self::with_footer_key_metadata`] or [`Self::with_footer_key`].
+    ///
+    /// [`DecryptionPropertiesBuilder::with_column_key`]: crate::encryption::decrypt::DecryptionPropertiesBuilder::with_column_key
+    /// [`KeyRetriever`]: crate::encryption::decrypt::KeyRetriever
+    pub fn with_store_key_metadata(mut self, store_key_metadata: bool) -> Self {
+        self.store_key_metadata = store_key_metadata;
+        self
+    }
+
+    /// Set the algorithm used to encrypt the file. Defaults to [`EncryptionAlgorithm::AesGcmV1`].
+    ///
+    /// The algorithm applies to all encrypted columns, whether they use the footer key or a
+    /// column specific key. The Parquet format records a single algorithm in the file crypto
+    /// metadata and column crypto metadata has no algorithm field, so algorithms can't be
+    /// mixed between columns of a file.
+    pub fn with_algorithm(mut self, algorithm: EncryptionAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Set the cipher backend used to encrypt data. Defaults to [`RingAeadBackend`].
+    pub fn with_aead_backend(mut self, aead_backend: Arc<dyn AeadBackend>) -> Self {
+        self.aead_backend = aead_backend;
+        self
+    }
+
+    /// Set the source of random bytes used to generate the unique file identifier in the
+    /// AAD suffix and the initial nonce of each encryptor. Defaults to [`SystemRandomSource`].
+    ///
+    /// The random source is passed to the cipher backend with
+    /// [`AeadBackend::new_encryptor_with_random`], so a custom backend may ignore it.
+    ///
+    /// **A deterministic source is unsafe for real data**, as every file written with it
+    /// and the same key reuses the same nonces. See [`RandomSource`] for details.
+    pub fn with_random_source(mut self, random_source: Arc<dyn RandomSource>) -> Self {
+        self.random_source = random_source;
+        self
+    }
+
+    /// Set a sink to report counts of encrypted pages and bytes to
+    pub fn with_metrics(mut self, metrics: Arc<dyn EncryptionMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Use a fixed unique file identifier in the AAD suffix rather than generating
+    /// a random 8 byte identifier for each file written.
+    ///
+    /// This is an advanced option intended for reproducible writes and generating test vectors.
+    /// The file identifier protects against modules being swapped between files encrypted
+    /// with the same key, so reusing it for multiple files weakens this protection and
+    /// it should not be set for production data.
+    ///
+    /// Note that nonces are still generated randomly, so byte-identical output also
+    /// requires a deterministic [`RandomSource`] set with [`Self::with_random_source`],
+    /// which is likewise only safe for test data.
+    pub fn with_aad_file_unique(mut self, aad_file_unique: Vec<u8>) -> Self {
+        self.aad_file_unique = Some(aad_file_unique);
+        self
+    }
+
     /// Build the encryption properties
     pub fn build(self) -> Result<Arc<FileEncryptionProperties>> {
+        if !self.unencrypted_columns.is_empty() && !self.column_keys.is_empty() {
+            return Err(general_err!(
+                "Unencrypted columns can only be specified when no column keys are set"
+            ));
+        }
+        if self.plaintext_columns && !self.column_keys.is_empty() {
+            return Err(general_err!(
+                "Plaintext columns can only be specified when no column keys are set"
+            ));
+        }
+        if self.uniform_encryption && !self.column_keys.is_empty() {
+            return Err(general_err!(
+                "Uniform encryption can only be specified when no column keys are set"
+            ));
+        }
+        if self.column_keys.is_empty() && !self.uniform_encryption && !self.plaintext_columns {
+            return Err(general_err!(
+                "No column keys were set. Use with_uniform_encryption to encrypt all columns with the footer key"
+            ));
+        }
+        if self.store_aad_prefix && self.aad_prefix.is_none() {
+            return Err(general_err!(
+                "AAD prefix storage is enabled but no AAD prefix was set"
+            ));
+        }
+        validate_aad_prefix(self.aad_prefix.as_deref())?;
         Ok(Arc::new(FileEncryptionProperties {
             encrypt_footer: self.encrypt_footer,
             footer_key: self.footer_key,
             column_keys: self.column_keys,
+            column_path_keys: self.column_path_keys,
+            unencrypted_columns: self.unencrypted_columns,
             aad_prefix: self.aad_prefix,
             store_aad_prefix: self.store_aad_prefix,
+            store_key_metadata: self.store_key_metadata,
+            plaintext_columns: self.plaintext_columns,
+            module_key_derivation: self.module_key_derivation,
+            algorithm: self.algorithm,
+            aead_backend: self.aead_backend,
+            random_source: self.random_source,
+            metrics: self.metrics,
+            aad_file_unique: self.aad_file_unique,
         }))
     }
 }
 
+/// Wraps the [`RandomSource`] of a file when creating one of its encryptors, XORing
+/// the index of the encryptor into bytes 8..12 of the nonce seed. These are the top
+/// 32 bits of the little-endian 96 bit nonce counter, so encryptors with different
+/// indices count through separate ranges of nonces.
 #[derive(Debug)]
+struct NonceSeedSource<'a> {
+    random_source: &'a dyn RandomSource,
+    index: u32,
+}
+
+impl RandomSource for NonceSeedSource<'_> {
+    fn fill(&self, dest: &mut [u8]) -> Result<()> {
+        self.random_source.fill(dest)?;
+        if let Some(top) = dest.get_mut(8..12) {
+            for (byte, index_byte) in top.iter_mut().zip(self.index.to_le_bytes()) {
+                *byte ^= index_byte;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// The encryption configuration for a single Parquet file
+///
+/// Every [`BlockEncryptor`] a `FileEncryptor` creates starts from a new random nonce,
+/// with the index of the encryptor within the file mixed into the top 32 bits of the
+/// 96 bit nonce. Encryptors of one file therefore use separate ranges of nonces, even
+/// if the [`RandomSource`] repeats itself. A clone shares the keys and AAD of the
+/// original, including the unique file identifier, and the encryptor index, so the
+/// encryptors created from each never reuse nonces.
+///
+/// Cloning is not intended for producing two writers to the same file, as module AADs
+/// depend on the row group, column and page ordinals assigned by a single writer.
+/// Nor should clones be used for separate files, which would then share a file AAD.
+#[derive(Clone)]
 pub(crate) struct FileEncryptor {
     properties: Arc<FileEncryptionProperties>,
     aad_file_unique: Vec<u8>,
     file_aad: Vec<u8>,
+    encryptor_count: Arc<AtomicU32>,
+}
+
+/// Keys are redacted by the properties' formatting. The unique file identifier is stored in
+/// plaintext in the file so is shown, but the file AAD is omitted as it only repeats the
+/// AAD prefix and unique file identifier.
+impl std::fmt::Debug for FileEncryptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileEncryptor")
+            .field("properties", &self.properties)
+            .field("aad_file_unique", &self.aad_file_unique)
+            .finish_non_exhaustive()
+    }
 }
 
 impl FileEncryptor {
+    /// Create a new encryptor for a file, checking that all keys are accepted
+    /// by the cipher backend so that invalid keys fail before any data is written
+    pub(crate) fn for_properties(properties: &Arc<FileEncryptionProperties>) -> Result<Self> {
+        Self::validate_keys(properties)?;
+        Self::new(Arc::clone(properties))
+    }
+
+    /// Create a new encryptor for a file without validating keys,
+    /// so invalid keys are only reported when a module is first encrypted
     pub(crate) fn new(properties: Arc<FileEncryptionProperties>) -> Result<Self> {
-        // Generate unique AAD for file
-        let rng = SystemRandom::new();
-        let mut aad_file_unique = vec![0u8; 8];
-        rng.fill(&mut aad_file_unique)?;
+        // Generate unique AAD for file unless a fixed value was provided
+        let aad_file_unique = match properties.aad_file_unique.as_ref() {
+            Some(aad_file_unique) => aad_file_unique.clone(),
+            None => {
+                let mut aad_file_unique = vec![0u8; 8];
+                properties.random_source.fill(&mut aad_file_unique)?;
+                aad_file_unique
+            }
+        };
 
         let file_aad = match properties.aad_prefix.as_ref() {
             None => aad_file_unique.clone(),
@@ -310,9 +967,43 @@ impl FileEncryptor {
             properties,
             aad_file_unique,
             file_aad,
+            encryptor_count: Arc::new(AtomicU32::new(0)),
         })
     }
 
+    /// The random source for the next encryptor, which mixes the index of the
+    /// encryptor into its nonce seed
+    fn next_nonce_seed(&self) -> NonceSeedSource<'_> {
+        NonceSeedSource {
+            random_source: self.properties.random_source.as_ref(),
+            index: self.encryptor_count.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    fn validate_keys(properties: &FileEncryptionProperties) -> Result<()> {
+        let aead_backend = &properties.aead_backend;
+        let validate_key = |key: &[u8]| -> Result<()> {
+            aead_backend.new_encryptor(key)?;
+            if properties.algorithm == EncryptionAlgorithm::AesGcmCtrV1 {
+                aead_backend.new_ctr_encryptor(key)?;
+            }
+            Ok(())
+        };
+
+        validate_key(properties.footer_key.key()).map_err(|e| {
+            ParquetError::InvalidKeyError("Invalid footer key".to_string(), Box::new(e))
+        })?;
+        for (column_path, column_key) in properties.column_keys.iter() {
+            validate_key(column_key.key()).map_err(|e| {
+                ParquetError::InvalidKeyError(
+                    format!("Invalid key for column '{column_path}'"),
+                    Box::new(e),
+                )
+            })?;
+        }
+        Ok(())
+    }
+
     /// Get the encryptor's file encryption properties
     pub fn properties(&self) -> &Arc<FileEncryptionProperties> {
         &self.properties
@@ -331,40 +1022,115 @@ impl FileEncryptor {
         &self.aad_file_unique
     }
 
-    /// Returns whether data for the specified column should be encrypted
-    pub fn is_column_encrypted(&self, column_path: &str) -> bool {
-        if self.properties.column_keys.is_empty() {
-            // Uniform encryption
-            true
+    /// The AAD used when encrypting or signing the footer
+    pub(crate) fn footer_aad(&self) -> Result<Vec<u8>> {
+        create_footer_aad(&self.file_aad)
+    }
+
+    /// Returns whether data for the specified column should be encrypted.
+    /// This is false for all columns if [`FileEncryptionProperties::plaintext_columns`] is set.
+    pub fn is_column_encrypted(&self, column_path: &ColumnPath) -> bool {
+        if self.properties.is_uniform_encryption() {
+            !self
+                .properties
+                .is_excluded_from_uniform_encryption(column_path)
         } else {
-            self.properties.column_keys.contains_key(column_path)
+            self.properties.column_key(column_path).is_some()
         }
     }
 
+    /// Encrypt a serialized footer with the footer key and footer AAD, and write it to a sink
+    pub(crate) fn encrypt_footer<T: WriteThrift, W: Write>(
+        &self,
+        footer: &T,
+        sink: &mut W,
+    ) -> Result<()> {
+        let encrypted_footer = self.encrypt_footer_to_vec(footer)?;
+        sink.write_all(&encrypted_footer)?;
+        Ok(())
+    }
+
+    /// Encrypt a serialized footer with the footer key and footer AAD
+    pub(crate) fn encrypt_footer_to_vec<T: WriteThrift>(&self, footer: &T) -> Result<Vec<u8>> {
+        let aad = self.footer_aad()?;
+        let mut encryptor = self.get_footer_encryptor()?;
+        encrypt_thrift_object_to_vec(footer, &mut encryptor, &aad)
+    }
+
     /// Get the BlockEncryptor for the footer
     pub(crate) fn get_footer_encryptor(&self) -> Result<Box<dyn BlockEncryptor>> {
-        Ok(Box::new(RingGcmBlockEncryptor::new(
-            &self.properties.footer_key.key,
-        )?))
+        self.properties
+            .aead_backend
+            .new_encryptor_with_random(&self.properties.footer_key.key, &self.next_nonce_seed())
     }
 
-    /// Get the encryptor for a column.
+    /// Get the encryptor for a column's metadata and page headers.
     /// Will return an error if the column is not an encrypted column.
     pub(crate) fn get_column_encryptor(
         &self,
-        column_path: &str,
+        column_path: &ColumnPath,
+    ) -> Result<Box<dyn BlockEncryptor>> {
+        let key = self.get_column_key(column_path, ModuleType::DataPageHeader)?;
+        self.properties
+            .aead_backend
+            .new_encryptor_with_random(&key, &self.next_nonce_seed())
+    }
+
+    /// Get the encryptor for a column's page data, which depends on the encryption algorithm.
+    /// Will return an error if the column is not an encrypted column.
+    pub(crate) fn get_column_data_encryptor(
+        &self,
+        column_path: &ColumnPath,
     ) -> Result<Box<dyn BlockEncryptor>> {
-        if self.properties.column_keys.is_empty() {
-            return self.get_footer_encryptor();
+        let key = self.get_column_key(column_path, ModuleType::DataPage)?;
+        let aead_backend = &self.properties.aead_backend;
+        let random_source = self.next_nonce_seed();
+        match self.properties.algorithm {
+            EncryptionAlgorithm::AesGcmV1 => {
+                aead_backend.new_encryptor_with_random(&key, &random_source)
+            }
+            EncryptionAlgorithm::AesGcmCtrV1 => {
+                aead_backend.new_ctr_encryptor_with_random(&key, &random_source)
+            }
+        }
+    }
+
+    /// Get the key used to encrypt modules of a column, which is derived from the
+    /// column key for the module type if module key derivation is enabled
+    fn get_column_key(
+        &self,
+        column_path: &ColumnPath,
+        module_type: ModuleType,
+    ) -> Result<Cow<'_, [u8]>> {
+        if self.properties.is_uniform_encryption() {
+            if self
+                .properties
+                .is_excluded_from_uniform_encryption(column_path)
+            {
+                return Err(general_err!(
+                    "Column '{}' is not encrypted",
+                    column_path.string()
+                ));
+            }
+            return Ok(Cow::Borrowed(self.properties.footer_key.key()));
         }
-        match self.properties.column_keys.get(column_path) {
-            None => Err(general_err!("Column '{}' is not encrypted", column_path)),
-            Some(column_key) => Ok(Box::new(RingGcmBlockEncryptor::new(column_key.key())?)),
+        match self.properties.column_key(column_path) {
+            None => Err(general_err!(
+                "Column '{}' is not encrypted",
+                column_path.string()
+            )),
+            Some(column_key) if self.properties.module_key_derivation => Ok(Cow::Owned(
+                derive_module_key(column_key.key(), module_type)?,
+            )),
+            Some(column_key) => Ok(Cow::Borrowed(column_key.key())),
         }
     }
 }
 
-/// Write an encrypted Thrift serializable object
+/// Write an encrypted Thrift serializable object.
+///
+/// Failures writing to the sink are returned as [`ParquetError::External`] errors
+/// with an [`std::io::Error`] source, so they can be told apart from encryption errors.
 pub(crate) fn encrypt_thrift_object<T: WriteThrift, W: Write>(
     object: &T,
     encryptor: &mut Box<dyn BlockEncryptor>,
@@ -405,13 +1171,49 @@ pub(crate) fn encrypt_thrift_object_to_vec<T: WriteThrift>(
     encryptor: &mut Box<dyn BlockEncryptor>,
     module_aad: &[u8],
 ) -> Result<Vec<u8>> {
-    let mut buffer: Vec<u8> = vec![];
+    // Serialize after space for the length and nonce, so that encrypting in place
+    // neither moves the plaintext nor holds a second copy of large objects such as the footer
+    let mut buffer: Vec<u8> = vec![0; MODULE_PREFIX_LEN];
     {
         let mut unencrypted_protocol = ThriftCompactOutputProtocol::new(&mut buffer);
         object.write_thrift(&mut unencrypted_protocol)?;
     }
+    encryptor.encrypt_in_place(&mut buffer, module_aad)?;
+    Ok(buffer)
+}
 
-    encryptor.encrypt(buffer.as_ref(), module_aad)
+/// Escape a column path so that it can be used as an unambiguous key name, by escaping
+/// any `.` or `\` in field names with a backslash before joining them with `.`
+pub(crate) fn escaped_column_path(parts: &[String]) -> String {
+    parts
+        .iter()
+        .map(|part| part.replace('\\', "\\\\").replace('.', "\\."))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Find the key for a column with the given path parts, returning the name it was
+/// configured under.
+///
+/// A key stored under the column's escaped path takes precedence. Otherwise, a key set
+/// by dotted path may also match a column whose field names contain dots, unless that
+/// key was set from path parts as it then identifies a different column.
+pub(crate) fn find_column_key<'a, V>(
+    column_keys: &'a HashMap<String, V>,
+    column_path_keys: &HashSet<String>,
+    parts: &[String],
+) -> Option<(&'a str, &'a V)> {
+    let escaped = escaped_column_path(parts);
+    if let Some((name, key)) = column_keys.get_key_value(&escaped) {
+        return Some((name, key));
+    }
+    let dotted = parts.join(".");
+    if column_path_keys.contains(&dotted) {
+        return None;
+    }
+    column_keys
+        .get_key_value(&dotted)
+        .map(|(name, key)| (name.as_str(), key))
 }
 
 /// Get the crypto metadata for a column from the file encryption properties
@@ -419,19 +1221,931 @@ pub(crate) fn get_column_crypto_metadata(
     properties: &Arc<FileEncryptionProperties>,
     column: &ColumnDescPtr,
 ) -> Option<ColumnCryptoMetaData> {
-    if properties.column_keys.is_empty() {
-        // Uniform encryption
-        Some(ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY)
-    } else {
-        properties
-            .column_keys
-            .get(&column.path().string())
-            .map(|encryption_key| {
-                // Column is encrypted with a column specific key
-                ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(EncryptionWithColumnKey {
-                    path_in_schema: column.path().parts().to_vec(),
-                    key_metadata: encryption_key.key_metadata.clone(),
+    properties.column_crypto_metadata(column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encryption::ciphers::{BlockDecryptor, RingGcmBlockDecryptor};
+    use crate::encryption::modules::MAX_AAD_PREFIX_LEN;
+    use crate::file::metadata::KeyValue;
+    use crate::schema::parser::parse_message_type;
+    use crate::util::test_common::assert_send_sync;
+    use std::sync::atomic::AtomicU8;
+
+    #[test]
+    fn test_encrypt_thrift_object_errors() {
+        #[derive(Debug)]
+        struct FailingEncryptor;
+
+        impl BlockEncryptor for FailingEncryptor {
+            fn encrypt(&mut self, _plaintext: &[u8], _aad: &[u8]) -> Result<Vec<u8>> {
+                Err(ParquetError::EncryptionError("failed".to_string()))
+            }
+        }
+
+        struct FailingSink;
+
+        impl Write for FailingSink {
+            fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionReset,
+                    "connection reset",
+                ))
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let object = KeyValue::new("key".to_string(), "value".to_string());
+        let properties = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_uniform_encryption()
+            .build()
+            .unwrap();
+        let file_encryptor = FileEncryptor::new(properties).unwrap();
+
+        let mut encryptor = file_encryptor.get_footer_encryptor().unwrap();
+        let err =
+            encrypt_thrift_object(&object, &mut encryptor, &mut FailingSink, b"aad").unwrap_err();
+        match err {
+            ParquetError::External(e) => {
+                let io_error = e.downcast_ref::<std::io::Error>().unwrap();
+                assert_eq!(io_error.kind(), std::io::ErrorKind::ConnectionReset);
+            }
+            e => panic!("unexpected error: {e:?}"),
+        }
+
+        let mut encryptor: Box<dyn BlockEncryptor> = Box::new(FailingEncryptor);
+        let err =
+            encrypt_thrift_object(&object, &mut encryptor, &mut Vec::new(), b"aad").unwrap_err();
+        assert!(
+            matches!(err, ParquetError::EncryptionError(_)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn test_encryption_types_send_sync() {
+        // Encryptors are shared between threads when writing columns in parallel
+        assert_send_sync::<FileEncryptor>();
+        assert_send_sync::<Arc<FileEncryptor>>();
+        assert_send_sync::<FileEncryptionProperties>();
+        assert_send_sync::<EncryptionPropertiesBuilder>();
+        assert_send_sync::<EncryptionKey>();
+        assert_send_sync::<Box<dyn BlockEncryptor>>();
+    }
+
+    #[test]
+    fn test_encryption_key_metadata() {
+        let key = EncryptionKey::new(b"0123456789012345".to_vec());
+        assert_eq!(key.key(), b"0123456789012345");
+        assert_eq!(key.key_metadata(), None);
+
+        let key = key.with_metadata(b"kf".to_vec());
+        assert_eq!(key.key_metadata(), Some(b"kf".as_slice()));
+
+        let key = EncryptionKey::new_with_metadata(b"0123456789012345".to_vec(), b"kf".to_vec());
+        assert_eq!(key.key(), b"0123456789012345");
+        assert_eq!(key.key_metadata(), Some(b"kf".as_slice()));
+    }
+
+    #[test]
+    fn test_encryption_key_from_arc() {
+        let shared: Arc<[u8]> = Arc::from(b"0123456789012345".as_slice());
+        let key = EncryptionKey::from_arc(Arc::clone(&shared)).with_key_id("kf");
+        assert_eq!(key.key(), b"0123456789012345");
+        assert_eq!(key.key_id(), Some("kf"));
+        assert_eq!(
+            key,
+            EncryptionKey::new_with_metadata(shared.to_vec(), b"kf".to_vec())
+        );
+
+        // Neither creating nor cloning the key copies the key bytes
+        assert!(std::ptr::eq(key.key(), shared.as_ref()));
+        assert!(std::ptr::eq(key.clone().key(), shared.as_ref()));
+
+        let props = FileEncryptionProperties::builder(b"1234567890123450".into())
+            .with_footer_key(key.clone())
+            .with_column_encryption_key("x", key)
+            .build()
+            .unwrap();
+        assert!(std::ptr::eq(props.footer_key(), shared.as_ref()));
+        let column_key = props.column_key(&ColumnPath::from("x")).unwrap();
+        assert!(std::ptr::eq(column_key.key(), shared.as_ref()));
+    }
+
+    #[test]
+    fn test_encryption_properties_accessors() {
+        let props = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_uniform_encryption()
+            .with_footer_key_metadata(b"kf".into())
+            .with_plaintext_footer(true)
+            .with_aad_prefix(b"file_id".into())
+            .with_aad_prefix_storage(true)
+            .with_algorithm(EncryptionAlgorithm::AesGcmCtrV1)
+            .with_unencrypted_column("x")
+            .build()
+            .unwrap();
+
+        assert_eq!(props.footer_key(), b"0123456789012345");
+        assert_eq!(props.footer_key_metadata(), Some(&b"kf".to_vec()));
+        assert!(!props.encrypt_footer());
+        assert_eq!(props.aad_prefix(), Some(&b"file_id".to_vec()));
+        assert!(props.store_aad_prefix());
+        assert_eq!(props.algorithm(), EncryptionAlgorithm::AesGcmCtrV1);
+        assert_eq!(
+            props.unencrypted_columns(),
+            &HashSet::from(["x".to_owned()])
+        );
+        assert_eq!(props.column_keys().count(), 0);
+
+        let default_props = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_uniform_encryption()
+            .build()
+            .unwrap();
+        assert_eq!(default_props.footer_key_metadata(), None);
+        assert!(default_props.encrypt_footer());
+        assert_eq!(default_props.aad_prefix(), None);
+        assert!(!default_props.store_aad_prefix());
+        assert_eq!(default_props.algorithm(), EncryptionAlgorithm::AesGcmV1);
+    }
+
+    #[test]
+    fn test_with_footer_key() {
+        let props = FileEncryptionProperties::builder(b"0000000000000000".into())
+            .with_uniform_encryption()
+            .with_footer_key(EncryptionKey::new_with_metadata(
+                b"0123456789012345".into(),
+                b"kf".into(),
+            ))
+            .build()
+            .unwrap();
+        assert_eq!(props.footer_key(), b"0123456789012345");
+        assert_eq!(props.footer_key_metadata(), Some(&b"kf".to_vec()));
+    }
+
+    #[test]
+    fn test_build_rejects_unencrypted_columns_with_column_keys() {
+        let err = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_column_key("x", b"1234567890123450".into())
+            .with_unencrypted_column("y")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Unencrypted columns can only be specified when no column keys are set"
+        );
+    }
+
+    #[test]
+    fn test_build_rejects_plaintext_columns_with_column_keys() {
+        let err = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_column_key("x", b"1234567890123450".into())
+            .with_plaintext_columns(true)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Plaintext columns can only be specified when no column keys are set"
+        );
+    }
+
+    #[test]
+    fn test_build_requires_explicit_uniform_encryption() {
+        let err = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: No column keys were set. Use with_uniform_encryption to encrypt all columns with the footer key"
+        );
+
+        let props = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_uniform_encryption()
+            .build()
+            .unwrap();
+        assert!(props.is_uniform_encryption());
+        assert_eq!(props.column_keys().count(), 0);
+
+        let err = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_uniform_encryption()
+            .with_column_key("x", b"1234567890123450".into())
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Uniform encryption can only be specified when no column keys are set"
+        );
+
+        // Leaving all columns unencrypted doesn't require column keys or uniform encryption
+        let props = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_plaintext_columns(true)
+            .build()
+            .unwrap();
+        assert!(props.plaintext_columns());
+    }
+
+    #[test]
+    fn test_build_validates_aad_prefix_storage() {
+        let err = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_uniform_encryption()
+            .with_aad_prefix_storage(true)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: AAD prefix storage is enabled but no AAD prefix was set"
+        );
+
+        // Storing the AAD prefix is valid with both encrypted and plaintext footers
+        for plaintext_footer in [false, true] {
+            let props = FileEncryptionProperties::builder(b"0123456789012345".into())
+                .with_uniform_encryption()
+                .with_aad_prefix(b"file_id".into())
+                .with_aad_prefix_storage(true)
+                .with_plaintext_footer(plaintext_footer)
+                .build()
+                .unwrap();
+            assert!(props.store_aad_prefix());
+        }
+
+        // An AAD prefix that isn't stored must be provided by readers
+        let props = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_uniform_encryption()
+            .with_aad_prefix(b"file_id".into())
+            .build()
+            .unwrap();
+        assert!(!props.store_aad_prefix());
+    }
+
+    #[test]
+    fn test_build_validates_aad_prefix_length() {
+        let props = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_uniform_encryption()
+            .with_aad_prefix(vec![0; MAX_AAD_PREFIX_LEN])
+            .build()
+            .unwrap();
+        assert_eq!(props.aad_prefix().unwrap().len(), MAX_AAD_PREFIX_LEN);
+
+        let err = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_uniform_encryption()
+            .with_aad_prefix(vec![0; MAX_AAD_PREFIX_LEN + 1])
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: AAD prefix length 4097 exceeds the maximum of 4096 bytes"
+        );
+    }
+
+    #[test]
+    fn test_column_keys() {
+        let props = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_column_key_and_metadata("x", b"1234567890123450".into(), b"kc1".into())
+            .with_column_key("y", b"1234567890123451".into())
+            .with_column_key_and_metadata("z.a", b"1234567890123452".into(), b"kc2".into())
+            .build()
+            .unwrap();
+
+        let mut column_keys: Vec<_> = props.column_keys().collect();
+        column_keys.sort();
+        assert_eq!(
+            column_keys,
+            vec![
+                ("x", Some(b"kc1".as_slice())),
+                ("y", None),
+                ("z.a", Some(b"kc2".as_slice())),
+            ]
+        );
+
+        let uniform = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_uniform_encryption()
+            .build()
+            .unwrap();
+        assert_eq!(uniform.column_keys().count(), 0);
+    }
+
+    #[test]
+    fn test_column_crypto_metadata() {
+        let schema = parse_message_type(
+            "message schema { required int32 x; required int32 y; optional group z { required int32 a; } }",
+        )
+        .unwrap();
+        let schema = SchemaDescriptor::new(Arc::new(schema));
+        let (x, y, z_a) = (schema.column(0), schema.column(1), schema.column(2));
+
+        let uniform = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_uniform_encryption()
+            .with_unencrypted_column("y")
+            .build()
+            .unwrap();
+        assert_eq!(
+            uniform.column_crypto_metadata(&x),
+            Some(ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY)
+        );
+        assert_eq!(uniform.column_crypto_metadata(&y), None);
+        assert_eq!(
+            uniform.column_crypto_metadata(&z_a),
+            Some(ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY)
+        );
+
+        let per_column = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_column_key("x", b"1234567890123450".into())
+            .with_column_key_and_metadata("z.a", b"1234567890123452".into(), b"kc2".into())
+            .build()
+            .unwrap();
+        assert_eq!(
+            per_column.column_crypto_metadata(&x),
+            Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(
+                EncryptionWithColumnKey {
+                    path_in_schema: vec!["x".to_owned()],
+                    key_metadata: None,
+                }
+            ))
+        );
+        assert_eq!(per_column.column_crypto_metadata(&y), None);
+        assert_eq!(
+            per_column.column_crypto_metadata(&z_a),
+            Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(
+                EncryptionWithColumnKey {
+                    path_in_schema: vec!["z".to_owned(), "a".to_owned()],
+                    key_metadata: Some(b"kc2".to_vec()),
+                }
+            ))
+        );
+    }
+
+    /// Schema with a top-level column named `a.b` and a column `b` nested within `a`
+    fn dotted_name_schema() -> SchemaDescriptor {
+        use crate::basic::{Repetition, Type as PhysicalType};
+        use crate::schema::types::Type;
+
+        let int_field = |name: &str| {
+            Arc::new(
+                Type::primitive_type_builder(name, PhysicalType::INT32)
+                    .with_repetition(Repetition::REQUIRED)
+                    .build()
+                    .unwrap(),
+            )
+        };
+        let group = Type::group_type_builder("a")
+            .with_repetition(Repetition::REQUIRED)
+            .with_fields(vec![int_field("b")])
+            .build()
+            .unwrap();
+        let schema = Type::group_type_builder("schema")
+            .with_fields(vec![int_field("a.b"), Arc::new(group)])
+            .build()
+            .unwrap();
+        SchemaDescriptor::new(Arc::new(schema))
+    }
+
+    #[test]
+    fn test_store_key_metadata() {
+        let schema = dotted_name_schema();
+        let column = schema.column(0);
+        let key_metadata = |store_key_metadata: Option<bool>| {
+            let mut builder = FileEncryptionProperties::builder(b"0123456789012345".into())
+                .with_column_key_and_metadata("a.b", b"1234567890123450".into(), b"kc1".into());
+            if let Some(store_key_metadata) = store_key_metadata {
+                builder = builder.with_store_key_metadata(store_key_metadata);
+            }
+            let properties = builder.build().unwrap();
+            assert_eq!(
+                properties.store_key_metadata(),
+                store_key_metadata.unwrap_or(true)
+            );
+            match properties.column_crypto_metadata(&column) {
+                Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(key)) => {
+                    assert_eq!(key.path_in_schema, vec!["a.b".to_owned()]);
+                    key.key_metadata
+                }
+                other => panic!("Unexpected crypto metadata {other:?}"),
+            }
+        };
+
+        assert_eq!(key_metadata(None), Some(b"kc1".to_vec()));
+        assert_eq!(key_metadata(Some(true)), Some(b"kc1".to_vec()));
+        assert_eq!(key_metadata(Some(false)), None);
+    }
+
+    #[test]
+    fn test_column_key_for_path_with_dot() {
+        let schema = dotted_name_schema();
+        let (top_level, nested) = (schema.column(0), schema.column(1));
+        assert_eq!(top_level.path().string(), nested.path().string());
+
+        let key_metadata = |properties: &FileEncryptionProperties, column| match properties
+            .column_crypto_metadata(column)
+        {
+            Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(key)) => key.key_metadata,
+            other => panic!("Unexpected crypto metadata {other:?}"),
+        };
+
+        // Path parts distinguish the two columns
+        let by_parts = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_column_path_key(
+                &ColumnPath::new(vec!["a.b".to_owned()]),
+                b"1234567890123450".into(),
+            )
+            .build()
+            .unwrap();
+        by_parts.validate_encrypted_column_names(&schema).unwrap();
+        assert!(by_parts.column_crypto_metadata(&top_level).is_some());
+        assert_eq!(by_parts.column_crypto_metadata(&nested), None);
+        let names: Vec<_> = by_parts.column_keys().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["a\\.b"]);
+
+        let by_parts = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_column_path_key(
+                &ColumnPath::new(vec!["a".to_owned(), "b".to_owned()]),
+                b"1234567890123450".into(),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(by_parts.column_crypto_metadata(&top_level), None);
+        assert!(by_parts.column_crypto_metadata(&nested).is_some());
+
+        // A parts-based key takes precedence over an ambiguous dotted key
+        let mixed = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_column_key_and_metadata("a.b", b"1234567890123450".into(), b"dotted".into())
+            .with_column_path_key(
+                &ColumnPath::new(vec!["a.b".to_owned()]),
+                b"1234567890123451".into(),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(key_metadata(&mixed, &top_level), None);
+        assert_eq!(key_metadata(&mixed, &nested), Some(b"dotted".to_vec()));
+
+        // A dotted key matches both columns, as it always has
+        let dotted = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_column_key("a.b", b"1234567890123450".into())
+            .build()
+            .unwrap();
+        dotted.validate_encrypted_column_names(&schema).unwrap();
+        assert!(dotted.column_crypto_metadata(&top_level).is_some());
+        assert!(dotted.column_crypto_metadata(&nested).is_some());
+
+        let missing = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_column_path_key(
+                &ColumnPath::new(vec!["a".to_owned(), "b.c".to_owned()]),
+                b"1234567890123450".into(),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(
+            missing
+                .validate_encrypted_column_names(&schema)
+                .unwrap_err()
+                .to_string(),
+            "Parquet error: The following columns with encryption keys specified were not found in the schema: a.b\\.c"
+        );
+    }
+
+    #[test]
+    fn test_escaped_column_path() {
+        let path = |parts: &[&str]| parts.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        assert_eq!(escaped_column_path(&path(&["a", "b"])), "a.b");
+        assert_eq!(escaped_column_path(&path(&["a.b"])), "a\\.b");
+        assert_eq!(escaped_column_path(&path(&["a\\", "b"])), "a\\\\.b");
+    }
+
+    #[test]
+    fn test_to_decryption_properties() {
+        let encryption_properties = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_column_key_and_metadata("x", b"1234567890123450".into(), b"kc1".into())
+            .with_column_path_key(
+                &ColumnPath::new(vec!["a.b".to_owned()]),
+                b"1234567890123451".into(),
+            )
+            .with_aad_prefix(b"prefix".into())
+            .with_module_key_derivation(true)
+            .build()
+            .unwrap();
+        let decryption_properties = encryption_properties.to_decryption_properties().unwrap();
+
+        assert_eq!(
+            decryption_properties.footer_key(None).unwrap().as_ref(),
+            b"0123456789012345"
+        );
+        assert_eq!(
+            decryption_properties
+                .column_key("x", Some(b"kc1"))
+                .unwrap()
+                .as_ref(),
+            b"1234567890123450"
+        );
+        assert_eq!(
+            decryption_properties
+                .column_path_key(&ColumnPath::new(vec!["a.b".to_owned()]), None)
+                .unwrap()
+                .as_ref(),
+            b"1234567890123451"
+        );
+        // The dotted path of a nested column doesn't match a key set from path parts
+        assert!(
+            decryption_properties
+                .column_path_key(&ColumnPath::new(vec!["a".to_owned(), "b".to_owned()]), None)
+                .is_err()
+        );
+        assert_eq!(
+            decryption_properties.aad_prefix(),
+            Some(&b"prefix".to_vec())
+        );
+        assert!(decryption_properties.module_key_derivation());
+    }
+
+    #[test]
+    fn test_encryption_key_same_key() {
+        let key = EncryptionKey::new_with_metadata(b"0123456789012345".to_vec(), b"kf1".to_vec());
+
+        // Same key with different metadata
+        let other_metadata =
+            EncryptionKey::new_with_metadata(b"0123456789012345".to_vec(), b"kf2".to_vec());
+        assert!(key.same_key(&other_metadata));
+        assert!(key != other_metadata);
+        assert!(key.same_key(&EncryptionKey::new(b"0123456789012345".to_vec())));
+
+        // Different key with the same metadata
+        let other_key =
+            EncryptionKey::new_with_metadata(b"1234567890123450".to_vec(), b"kf1".to_vec());
+        assert!(!key.same_key(&other_key));
+        assert!(key != other_key);
+
+        let longer_key = EncryptionKey::new(b"01234567890123450123456789012345".to_vec());
+        assert!(!key.same_key(&longer_key));
+
+        assert!(key.same_key(&key.clone()));
+        assert!(key == key.clone());
+    }
+
+    #[test]
+    fn test_encryption_key_id() {
+        let key = EncryptionKey::new(b"0123456789012345".to_vec());
+        assert_eq!(key.key_id(), None);
+
+        let key = key.with_key_id("kf");
+        assert_eq!(key.key_id(), Some("kf"));
+        assert_eq!(key.key_metadata(), Some(b"kf".as_slice()));
+
+        let key = key.with_metadata(vec![0xff, 0xfe]);
+        assert_eq!(key.key_id(), None);
+    }
+
+    #[test]
+    fn test_versioned_key_id() {
+        let key_id = KeyId::new("kms/key#1", 7);
+        assert_eq!(key_id.to_key_metadata(), b"kms/key#1#7");
+        assert_eq!(KeyId::from_key_metadata(b"kms/key#1#7").unwrap(), key_id);
+
+        let key = EncryptionKey::new(b"0123456789012345".to_vec()).with_versioned_key_id(&key_id);
+        assert_eq!(key.versioned_key_id(), Some(key_id));
+        assert_eq!(key.key_id(), Some("kms/key#1#7"));
+        assert_eq!(
+            EncryptionKey::new(b"0123456789012345".to_vec())
+                .with_key_id("kf")
+                .versioned_key_id(),
+            None
+        );
+
+        let properties = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_uniform_encryption()
+            .with_footer_key_id(&KeyId::new("kf", 2))
+            .build()
+            .unwrap();
+        assert_eq!(properties.footer_key_metadata(), Some(&b"kf#2".to_vec()));
+
+        let err = KeyId::from_key_metadata(b"kf").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Key metadata 'kf' is not a valid versioned key ID, expected '<id>#<version>'"
+        );
+        let err = KeyId::from_key_metadata(b"kf#v2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Key metadata 'kf#v2' is not a valid versioned key ID, invalid version 'v2'"
+        );
+        assert!(KeyId::from_key_metadata(&[0xff, b'#', b'1']).is_err());
+    }
+
+    #[test]
+    fn test_debug_redacts_keys() {
+        let key = EncryptionKey::new_with_metadata(b"0123456789012345".to_vec(), b"kf".to_vec());
+        assert_eq!(
+            format!("{key:?}"),
+            "EncryptionKey { len: 16, metadata: Some(..) }"
+        );
+
+        let properties = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_footer_key_metadata(b"kf".to_vec())
+            .with_column_key("x", b"1234567890123450".to_vec())
+            .with_plaintext_footer(true)
+            .build()
+            .unwrap();
+        let debug = format!("{properties:?}");
+        assert!(!debug.contains("0123456789012345"), "{debug}");
+        assert!(!debug.contains("1234567890123450"), "{debug}");
+        assert!(!debug.contains(&format!("{:?}", b"0123456789012345".to_vec())));
+        assert!(!debug.contains(&format!("{:?}", b"1234567890123450".to_vec())));
+        assert!(debug.contains("encrypt_footer: false"), "{debug}");
+        assert!(debug.contains("store_aad_prefix: false"), "{debug}");
+        assert!(
+            debug.contains("\"x\": EncryptionKey { len: 16, metadata: None }"),
+            "{debug}"
+        );
+    }
+
+    #[test]
+    fn test_file_encryptor_debug_redacts_keys() {
+        let footer_key = b"0123456789012345".to_vec();
+        let column_key = b"1234567890123450".to_vec();
+        for module_key_derivation in [false, true] {
+            let properties = FileEncryptionProperties::builder(footer_key.clone())
+                .with_footer_key_metadata(b"kf".to_vec())
+                .with_column_key_and_metadata("x", column_key.clone(), b"kc1".to_vec())
+                .with_aad_prefix(b"file".to_vec())
+                .with_aad_file_unique(b"unique".to_vec())
+                .with_module_key_derivation(module_key_derivation)
+                .build()
+                .unwrap();
+            let file_encryptor = FileEncryptor::new(properties).unwrap();
+            // Creating encryptors must not store keys in a form that is printed
+            file_encryptor.get_footer_encryptor().unwrap();
+            file_encryptor
+                .get_column_data_encryptor(&ColumnPath::from("x"))
+                .unwrap();
+
+            let debug = format!("{file_encryptor:?}");
+            for key in [&footer_key, &column_key] {
+                assert!(
+                    !debug.contains(std::str::from_utf8(key).unwrap()),
+                    "{debug}"
+                );
+                assert!(!debug.contains(&format!("{key:?}")), "{debug}");
+                assert!(!debug.contains(&format!("{:?}", key.as_slice())), "{debug}");
+            }
+            assert!(debug.starts_with("FileEncryptor { properties: "), "{debug}");
+            assert!(
+                debug.ends_with(&format!("aad_file_unique: {:?}, .. }}", b"unique".to_vec())),
+                "{debug}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_encryption_key() {
+        for len in [16, 32] {
+            let key = EncryptionKey::generate(len).unwrap();
+            assert_eq!(key.key().len(), len);
+            assert_eq!(key.key_metadata(), None);
+            assert_ne!(key, EncryptionKey::generate(len).unwrap());
+        }
+
+        for len in [0, 8, 24, 64] {
+            let err = EncryptionKey::generate(len).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Parquet error: Invalid key length {len}. Keys must be 16 or 32 bytes as only AES-128 and AES-256 are supported"
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_file_encryptor_rejects_invalid_footer_key() {
+        let properties = FileEncryptionProperties::builder(b"012345678901234".to_vec())
+            .with_uniform_encryption()
+            .build()
+            .unwrap();
+        // Keys are only validated when requested
+        FileEncryptor::new(Arc::clone(&properties)).unwrap();
+
+        let err = FileEncryptor::for_properties(&properties).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid key error: Invalid footer key. Parquet error: Error creating RingGcmBlockEncryptor with unsupported key length: 15"
+        );
+        let source = std::error::Error::source(&err).unwrap();
+        assert_eq!(
+            source.to_string(),
+            "Parquet error: Error creating RingGcmBlockEncryptor with unsupported key length: 15"
+        );
+    }
+
+    #[test]
+    fn test_file_encryptor_rejects_invalid_column_key() {
+        for algorithm in [
+            EncryptionAlgorithm::AesGcmV1,
+            EncryptionAlgorithm::AesGcmCtrV1,
+        ] {
+            let properties = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+                .with_column_key("x", b"1234567890123450".to_vec())
+                .with_column_key("y", b"12345678901234501234".to_vec())
+                .with_algorithm(algorithm)
+                .build()
+                .unwrap();
+            let err = FileEncryptor::for_properties(&properties).unwrap_err();
+            match &err {
+                ParquetError::InvalidKeyError(message, source) => {
+                    assert_eq!(message, "Invalid key for column 'y'");
+                    assert!(
+                        source.to_string().contains("unsupported key length: 20"),
+                        "{err}"
+                    );
+                }
+                other => panic!("Unexpected error: {other}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_aad_file_unique() {
+        let properties = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_uniform_encryption()
+            .with_aad_prefix(b"prefix".to_vec())
+            .build()
+            .unwrap();
+        assert_eq!(properties.aad_file_unique(), None);
+        let encryptor_a = FileEncryptor::new(Arc::clone(&properties)).unwrap();
+        let encryptor_b = FileEncryptor::new(properties).unwrap();
+        assert_eq!(encryptor_a.aad_file_unique().len(), 8);
+        assert_ne!(encryptor_a.aad_file_unique(), encryptor_b.aad_file_unique());
+
+        let properties = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_uniform_encryption()
+            .with_aad_prefix(b"prefix".to_vec())
+            .with_aad_file_unique(b"fixed".to_vec())
+            .build()
+            .unwrap();
+        assert_eq!(properties.aad_file_unique(), Some(b"fixed".as_slice()));
+        let encryptor = FileEncryptor::new(properties).unwrap();
+        assert_eq!(encryptor.aad_file_unique(), b"fixed");
+        assert_eq!(encryptor.file_aad(), b"prefixfixed");
+    }
+
+    #[test]
+    fn test_cloned_file_encryptor_nonces() {
+        let properties = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_column_key("x", b"1234567890123450".into())
+            .build()
+            .unwrap();
+        let file_encryptor = FileEncryptor::new(properties).unwrap();
+        let cloned = file_encryptor.clone();
+        assert_eq!(cloned.file_aad(), file_encryptor.file_aad());
+        assert_eq!(cloned.aad_file_unique(), file_encryptor.aad_file_unique());
+
+        let nonces = |file_encryptor: &FileEncryptor| {
+            let mut encryptor = file_encryptor
+                .get_column_encryptor(&ColumnPath::from("x"))
+                .unwrap();
+            (0..100)
+                .map(|_| {
+                    let ciphertext = encryptor.encrypt(b"plaintext", b"aad").unwrap();
+                    ciphertext[SIZE_LEN..SIZE_LEN + NONCE_LEN].to_vec()
                 })
-            })
+                .collect::<HashSet<_>>()
+        };
+        let original_nonces = nonces(&file_encryptor);
+        let cloned_nonces = nonces(&cloned);
+        assert_eq!(original_nonces.len(), 100);
+        assert_eq!(cloned_nonces.len(), 100);
+        assert!(original_nonces.is_disjoint(&cloned_nonces));
+    }
+
+    #[test]
+    fn test_random_source() {
+        /// A deterministic, non-repeating source that fills each request with the next byte
+        #[derive(Debug)]
+        struct SequenceRandomSource(AtomicU8);
+
+        impl RandomSource for SequenceRandomSource {
+            fn fill(&self, dest: &mut [u8]) -> Result<()> {
+                dest.fill(self.0.fetch_add(1, Ordering::Relaxed));
+                Ok(())
+            }
+        }
+
+        let encrypt = || {
+            let properties = FileEncryptionProperties::builder(b"0123456789012345".into())
+                .with_uniform_encryption()
+                .with_random_source(Arc::new(SequenceRandomSource(AtomicU8::new(7))))
+                .build()
+                .unwrap();
+            let file_encryptor = FileEncryptor::new(properties).unwrap();
+            let footer = KeyValue::new("key".to_string(), "value".to_string());
+            let encrypted_footer = file_encryptor.encrypt_footer_to_vec(&footer).unwrap();
+            (file_encryptor.aad_file_unique().clone(), encrypted_footer)
+        };
+
+        let (aad_file_unique, encrypted_footer) = encrypt();
+        assert_eq!(aad_file_unique, vec![7; 8]);
+        // The footer encryptor is the first of the file, so its seed is unchanged,
+        // and the nonce counter starts one after the 96 bit seed
+        let mut expected_nonce = [8u8; NONCE_LEN];
+        expected_nonce[0] = 9;
+        assert_eq!(
+            &encrypted_footer[SIZE_LEN..SIZE_LEN + NONCE_LEN],
+            &expected_nonce
+        );
+
+        assert_eq!(encrypt(), (aad_file_unique, encrypted_footer));
+    }
+
+    #[test]
+    fn test_repeating_random_source_nonces() {
+        #[derive(Debug)]
+        struct RepeatingRandomSource;
+
+        impl RandomSource for RepeatingRandomSource {
+            fn fill(&self, dest: &mut [u8]) -> Result<()> {
+                dest.fill(7);
+                Ok(())
+            }
+        }
+
+        let properties = FileEncryptionProperties::builder(b"0123456789012345".into())
+            .with_uniform_encryption()
+            .with_random_source(Arc::new(RepeatingRandomSource))
+            .build()
+            .unwrap();
+        let file_encryptor = FileEncryptor::new(properties).unwrap();
+        let cloned = file_encryptor.clone();
+        let column_path = ColumnPath::from("x");
+
+        // Each encryptor of the file counts through its own range of nonces,
+        // even though every seed drawn from the source is the same
+        let mut encryptors = [
+            file_encryptor.get_footer_encryptor().unwrap(),
+            file_encryptor.get_column_encryptor(&column_path).unwrap(),
+            file_encryptor
+                .get_column_data_encryptor(&column_path)
+                .unwrap(),
+            cloned.get_column_encryptor(&column_path).unwrap(),
+        ];
+        let mut nonces = HashSet::new();
+        for (index, encryptor) in encryptors.iter_mut().enumerate() {
+            for _ in 0..10 {
+                let ciphertext = encryptor.encrypt(b"plaintext", b"aad").unwrap();
+                let nonce = &ciphertext[SIZE_LEN..SIZE_LEN + NONCE_LEN];
+                assert_eq!(nonce[8..], (0x07070707 ^ index as u32).to_le_bytes());
+                assert!(nonces.insert(nonce.to_vec()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_footer_aad() {
+        let properties = FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+            .with_uniform_encryption()
+            .with_aad_prefix(b"prefix".to_vec())
+            .build()
+            .unwrap();
+        let file_encryptor = FileEncryptor::new(properties).unwrap();
+
+        let footer_aad = file_encryptor.footer_aad().unwrap();
+        let expected_aad = [b"prefix", file_encryptor.aad_file_unique().as_slice(), &[0]].concat();
+        assert_eq!(footer_aad, expected_aad);
+    }
+
+    #[test]
+    fn test_encrypt_footer() {
+        let footer_key = b"0123456789012345".to_vec();
+        let properties = FileEncryptionProperties::builder(footer_key.clone())
+            .with_uniform_encryption()
+            .build()
+            .unwrap();
+        let file_encryptor = FileEncryptor::new(properties).unwrap();
+        let footer = KeyValue::new("key".to_string(), "value".to_string());
+
+        let mut encrypted_footer = Vec::new();
+        file_encryptor
+            .encrypt_footer(&footer, &mut encrypted_footer)
+            .unwrap();
+
+        let mut expected_plaintext = Vec::new();
+        footer
+            .write_thrift(&mut ThriftCompactOutputProtocol::new(
+                &mut expected_plaintext,
+            ))
+            .unwrap();
+
+        let decryptor = RingGcmBlockDecryptor::new(&footer_key).unwrap();
+        let aad = file_encryptor.footer_aad().unwrap();
+        let plaintext = decryptor.decrypt(&encrypted_footer, &aad).unwrap();
+        assert_eq!(plaintext, expected_plaintext);
+
+        // Each encryption uses a new nonce
+        let encrypted_footer_2 = file_encryptor.encrypt_footer_to_vec(&footer).unwrap();
+        assert_ne!(encrypted_footer, encrypted_footer_2);
+        let plaintext = decryptor.decrypt(&encrypted_footer_2, &aad).unwrap();
+        assert_eq!(plaintext, expected_plaintext);
+
+        // The footer AAD is required for decryption
+        assert!(decryptor.decrypt(&encrypted_footer, b"wrong aad").is_err());
     }
 }
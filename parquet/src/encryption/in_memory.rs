@@ -0,0 +1,94 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+//! Helpers for encrypting Arrow data to an in-memory Parquet buffer and decrypting it again,
+//! without going through the file system.
+//!
+//! These are useful for tests, and for sending encrypted data over the network,
+//! for example in RPC responses. For more control over how data is written or read,
+//! use [`ArrowWriter`] with a [`Vec<u8>`] sink and [`ParquetRecordBatchReaderBuilder`]
+//! with a [`Bytes`] input directly.
+//!
+//! [`ArrowWriter`]: crate::arrow::ArrowWriter
+//!
+//! # Example
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use arrow_array::{ArrayRef, Int32Array, RecordBatch, StringArray};
+//! # use parquet::encryption::decrypt::FileDecryptionProperties;
+//! # use parquet::encryption::encrypt::FileEncryptionProperties;
+//! use parquet::encryption::in_memory::{decrypt_record_batches, encrypt_record_batches};
+//!
+//! let batch = RecordBatch::try_from_iter(vec![
+//!     ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+//!     ("name", Arc::new(StringArray::from(vec!["a", "b", "c"])) as ArrayRef),
+//! ])?;
+//!
+//! let encryption_properties = FileEncryptionProperties::builder(b"0123456789012345".into())
+//!     .with_column_key("name", b"1234567890123450".into())
+//!     .build()?;
+//! let encrypted = encrypt_record_batches(&[batch.clone()], encryption_properties)?;
+//!
+//! let decryption_properties = FileDecryptionProperties::builder(b"0123456789012345".into())
+//!     .with_column_key("name", b"1234567890123450".into())
+//!     .build()?;
+//! let batches = decrypt_record_batches(encrypted, decryption_properties)?;
+//! assert_eq!(batches, vec![batch]);
+//! # Ok::<(), parquet::errors::ParquetError>(())
+//! ```
+
+use crate::arrow::arrow_reader::{ArrowReaderOptions, ParquetRecordBatchReaderBuilder};
+use crate::arrow::arrow_writer::write_encrypted_batches;
+use crate::encryption::decrypt::FileDecryptionProperties;
+use crate::encryption::encrypt::FileEncryptionProperties;
+use crate::errors::{ParquetError, Result};
+use arrow_array::RecordBatch;
+use bytes::Bytes;
+use std::sync::Arc;
+
+/// Write record batches to an encrypted Parquet file held in memory.
+///
+/// All batches must have the same schema, and at least one batch is required
+/// so that the schema is known.
+pub fn encrypt_record_batches(
+    batches: &[RecordBatch],
+    encryption_properties: Arc<FileEncryptionProperties>,
+) -> Result<Bytes> {
+    let schema = match batches.first() {
+        Some(batch) => batch.schema(),
+        None => {
+            return Err(general_err!(
+                "At least one record batch is required to write an encrypted file"
+            ));
+        }
+    };
+    let buffer = write_encrypted_batches(Vec::new(), schema, batches, encryption_properties)?;
+    Ok(Bytes::from(buffer))
+}
+
+/// Read all record batches from an encrypted Parquet file held in memory
+pub fn decrypt_record_batches(
+    data: Bytes,
+    decryption_properties: Arc<FileDecryptionProperties>,
+) -> Result<Vec<RecordBatch>> {
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    ParquetRecordBatchReaderBuilder::try_new_with_options(data, options)?
+        .build()?
+        .map(|batch| batch.map_err(ParquetError::from))
+        .collect()
+}
@@ -0,0 +1,323 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Load encryption keys from a textual description, for example one provided to a
+//! command line tool via an argument or environment variable.
+//!
+//! Keys are written with a prefix that identifies their encoding, either `hex:` or `base64:`.
+//! A prefix is required because many hex strings are also valid base64.
+//!
+//! ```
+//! # use parquet::encryption::key_loader::KeyConfig;
+//! let config = KeyConfig::from_json(
+//!     r#"{
+//!         "footer_key": "hex:30313233343536373839303132333435",
+//!         "column_keys": {"x": "base64:MTIzNDU2Nzg5MDEyMzQ1MA=="}
+//!     }"#,
+//! )?;
+//! let encryption_properties = config.encryption_properties()?;
+//! let decryption_properties = config.decryption_properties()?;
+//! # Ok::<(), parquet::errors::ParquetError>(())
+//! ```
+
+use crate::encryption::decrypt::FileDecryptionProperties;
+use crate::encryption::encrypt::{FileEncryptionProperties, Redacted};
+use crate::errors::{ParquetError, Result};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+const HEX_PREFIX: &str = "hex:";
+const BASE64_PREFIX: &str = "base64:";
+const FOOTER_KEY_FIELD: &str = "footer_key";
+const COLUMN_KEYS_FIELD: &str = "column_keys";
+
+/// Decode a key prefixed with its encoding, either `hex:` or `base64:`
+pub fn decode_key(encoded: &str) -> Result<Vec<u8>> {
+    if let Some(hex) = encoded.strip_prefix(HEX_PREFIX) {
+        decode_hex(hex)
+    } else if let Some(base64) = encoded.strip_prefix(BASE64_PREFIX) {
+        BASE64_STANDARD
+            .decode(base64)
+            .map_err(|e| general_err!("Invalid base64 key: {}", e))
+    } else {
+        Err(general_err!(
+            "Key must be prefixed with '{}' or '{}' to specify its encoding",
+            HEX_PREFIX,
+            BASE64_PREFIX
+        ))
+    }
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return Err(general_err!(
+            "Invalid hex key: odd number of digits ({})",
+            hex.len()
+        ));
+    }
+    let digit = |c: u8| match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(general_err!(
+            "Invalid hex key: unexpected character {:?}",
+            c as char
+        )),
+    };
+    hex.as_bytes()
+        .chunks_exact(2)
+        .map(|pair| Ok((digit(pair[0])? << 4) | digit(pair[1])?))
+        .collect()
+}
+
+/// Decoded footer and column keys, used to build encryption and decryption properties.
+///
+/// Columns without a key are left unencrypted. If no column keys are given,
+/// all columns are encrypted with the footer key.
+#[derive(Clone, PartialEq)]
+pub struct KeyConfig {
+    footer_key: Vec<u8>,
+    column_keys: HashMap<String, Vec<u8>>,
+}
+
+impl KeyConfig {
+    /// Create a new [`KeyConfig`] from an encoded footer key
+    pub fn new(footer_key: &str) -> Result<Self> {
+        Ok(Self {
+            footer_key: decode_key(footer_key)?,
+            column_keys: HashMap::default(),
+        })
+    }
+
+    /// Add an encoded key for the column with the given path
+    pub fn with_column_key(mut self, column_path: &str, key: &str) -> Result<Self> {
+        self.column_keys
+            .insert(column_path.to_string(), decode_key(key)?);
+        Ok(self)
+    }
+
+    /// Parse a JSON object with a `footer_key` string field and an optional `column_keys`
+    /// object that maps column paths to encoded keys.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let map: Map<String, Value> = match serde_json::from_str(json) {
+            Ok(Value::Object(map)) => map,
+            Ok(_) => return Err(general_err!("Key configuration is not a JSON object")),
+            Err(e) => {
+                return Err(general_err!(
+                    "Failed to parse key configuration JSON: {}",
+                    e
+                ));
+            }
+        };
+        let mut config = match map.get(FOOTER_KEY_FIELD) {
+            Some(Value::String(footer_key)) => Self::new(footer_key)?,
+            Some(_) => return Err(general_err!("'{}' is not a string", FOOTER_KEY_FIELD)),
+            None => return Err(general_err!("'{}' is missing", FOOTER_KEY_FIELD)),
+        };
+        match map.get(COLUMN_KEYS_FIELD) {
+            Some(Value::Object(column_keys)) => {
+                for (column_path, key) in column_keys {
+                    let Value::String(key) = key else {
+                        return Err(general_err!(
+                            "Key for column '{}' is not a string",
+                            column_path
+                        ));
+                    };
+                    config = config.with_column_key(column_path, key)?;
+                }
+            }
+            Some(_) => return Err(general_err!("'{}' is not an object", COLUMN_KEYS_FIELD)),
+            None => {}
+        }
+        Ok(config)
+    }
+
+    /// The decoded footer key
+    pub fn footer_key(&self) -> &[u8] {
+        &self.footer_key
+    }
+
+    /// The decoded column keys, keyed by column path
+    pub fn column_keys(&self) -> &HashMap<String, Vec<u8>> {
+        &self.column_keys
+    }
+
+    /// Build [`FileEncryptionProperties`] that use these keys
+    pub fn encryption_properties(&self) -> Result<Arc<FileEncryptionProperties>> {
+        let mut builder = FileEncryptionProperties::builder(self.footer_key.clone());
+        if self.column_keys.is_empty() {
+            builder = builder.with_uniform_encryption();
+        }
+        for (column_path, key) in &self.column_keys {
+            builder = builder.with_column_key(column_path, key.clone());
+        }
+        builder.build()
+    }
+
+    /// Build [`FileDecryptionProperties`] that use these keys
+    pub fn decryption_properties(&self) -> Result<Arc<FileDecryptionProperties>> {
+        let mut builder = FileDecryptionProperties::builder(self.footer_key.clone());
+        for (column_path, key) in &self.column_keys {
+            builder = builder.with_column_key(column_path, key.clone());
+        }
+        builder.build()
+    }
+}
+
+/// Key material is redacted so that keys can't leak into logs
+impl std::fmt::Debug for KeyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyConfig")
+            .field("footer_key", &Redacted)
+            .field(
+                "column_keys",
+                &self
+                    .column_keys
+                    .keys()
+                    .map(|column_path| (column_path, Redacted))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_hex_key() {
+        assert_eq!(
+            decode_key("hex:30313233343536373839616263444546").unwrap(),
+            b"0123456789abcDEF"
+        );
+        assert_eq!(decode_key("hex:0aFf").unwrap(), vec![0x0a, 0xff]);
+
+        let err = decode_key("hex:abc").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Invalid hex key: odd number of digits (3)"
+        );
+        let err = decode_key("hex:0g").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Invalid hex key: unexpected character 'g'"
+        );
+    }
+
+    #[test]
+    fn test_decode_base64_key() {
+        assert_eq!(
+            decode_key("base64:MDEyMzQ1Njc4OTAxMjM0NQ==").unwrap(),
+            b"0123456789012345"
+        );
+        assert!(decode_key("base64:not base64").is_err());
+
+        let err = decode_key("MDEyMzQ1Njc4OTAxMjM0NQ==").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Key must be prefixed with 'hex:' or 'base64:' to specify its encoding"
+        );
+    }
+
+    #[test]
+    fn test_build_properties_from_json() {
+        let config = KeyConfig::from_json(
+            r#"{
+                "footer_key": "base64:MDEyMzQ1Njc4OTAxMjM0NQ==",
+                "column_keys": {
+                    "x": "hex:31323334353637383930313233343530",
+                    "a.b": "base64:MTIzNDU2Nzg5MDEyMzQ1MQ=="
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.footer_key(), b"0123456789012345");
+        assert_eq!(config.column_keys()["x"], b"1234567890123450");
+        assert_eq!(config.column_keys()["a.b"], b"1234567890123451");
+
+        let encryption_properties = config.encryption_properties().unwrap();
+        assert_eq!(encryption_properties.footer_key(), b"0123456789012345");
+        let mut column_names: Vec<_> = encryption_properties
+            .column_keys()
+            .map(|(column_path, _)| column_path)
+            .collect();
+        column_names.sort();
+        assert_eq!(column_names, vec!["a.b", "x"]);
+
+        let decryption_properties = config.decryption_properties().unwrap();
+        assert_eq!(
+            decryption_properties.footer_key(None).unwrap().as_ref(),
+            b"0123456789012345"
+        );
+        assert_eq!(
+            decryption_properties
+                .column_key("x", None)
+                .unwrap()
+                .as_ref(),
+            b"1234567890123450"
+        );
+
+        let debug = format!("{config:?}");
+        assert_eq!(
+            debug,
+            r#"KeyConfig { footer_key: .., column_keys: {"a.b": .., "x": ..} }"#
+        );
+        for key in [
+            b"0123456789012345",
+            b"1234567890123450",
+            b"1234567890123451",
+        ] {
+            let hex: String = key.iter().map(|b| format!("{b:02x}")).collect();
+            assert!(!debug.contains(&hex), "{debug}");
+            assert!(!debug.contains(&BASE64_STANDARD.encode(key)), "{debug}");
+            assert!(!debug.contains(&format!("{:?}", key.as_slice())), "{debug}");
+        }
+    }
+
+    #[test]
+    fn test_uniform_encryption_without_column_keys() {
+        let config =
+            KeyConfig::from_json(r#"{"footer_key": "hex:30313233343536373839303132333435"}"#)
+                .unwrap();
+        let encryption_properties = config.encryption_properties().unwrap();
+        assert_eq!(encryption_properties.column_keys().count(), 0);
+        assert!(encryption_properties.encrypt_footer());
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let err = KeyConfig::from_json(r#"{"column_keys": {}}"#).unwrap_err();
+        assert_eq!(err.to_string(), "Parquet error: 'footer_key' is missing");
+
+        let err = KeyConfig::from_json(r#"{"footer_key": "hex:00", "column_keys": {"x": 1}}"#)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Key for column 'x' is not a string"
+        );
+
+        let err = KeyConfig::from_json("[]").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Key configuration is not a JSON object"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Creation of file encryption and decryption properties using keys managed by a KMS
+
+use crate::encryption::decrypt::FileDecryptionProperties;
+use crate::encryption::encrypt::{EncryptionAlgorithm, EncryptionKey, FileEncryptionProperties};
+use crate::encryption::key_management::file_key_unwrapper::FileKeyUnwrapper;
+use crate::encryption::key_management::file_key_wrapper::FileKeyWrapper;
+use crate::encryption::key_management::kms::KmsClient;
+use crate::errors::{ParquetError, Result};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Defines how a file should be encrypted with keys managed by a KMS
+///
+/// # Example
+///
+/// ```
+/// # use parquet::encryption::key_management::crypto_factory::EncryptionConfiguration;
+/// // Encrypt the footer with a data key wrapped by master key "kf",
+/// // and columns x and y with data keys wrapped by master key "kc1".
+/// let config = EncryptionConfiguration::builder("kf".into())
+///     .with_column_keys("kc1".into(), vec!["x".into(), "y".into()])
+///     .build()?;
+/// # Ok::<(), parquet::errors::ParquetError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncryptionConfiguration {
+    footer_key_id: String,
+    column_key_ids: Vec<(String, Vec<String>)>,
+    plaintext_footer: bool,
+    double_wrapping: bool,
+    data_key_length_bits: u32,
+    algorithm: EncryptionAlgorithm,
+}
+
+impl EncryptionConfiguration {
+    /// Create a new builder for an [`EncryptionConfiguration`], with the ID of the
+    /// master key used to wrap the footer key
+    pub fn builder(footer_key_id: String) -> EncryptionConfigurationBuilder {
+        EncryptionConfigurationBuilder::new(footer_key_id)
+    }
+
+    /// ID of the master key used to wrap the footer key
+    pub fn footer_key_id(&self) -> &str {
+        &self.footer_key_id
+    }
+
+    /// IDs of the master keys used to wrap column keys, with the columns they apply to
+    pub fn column_key_ids(&self) -> &[(String, Vec<String>)] {
+        &self.column_key_ids
+    }
+
+    /// Whether the footer is written in plaintext
+    pub fn plaintext_footer(&self) -> bool {
+        self.plaintext_footer
+    }
+
+    /// Whether data keys are wrapped by key encryption keys rather than master keys
+    pub fn double_wrapping(&self) -> bool {
+        self.double_wrapping
+    }
+
+    /// Length in bits of the generated data keys
+    pub fn data_key_length_bits(&self) -> u32 {
+        self.data_key_length_bits
+    }
+
+    /// The algorithm used to encrypt the file
+    pub fn algorithm(&self) -> EncryptionAlgorithm {
+        self.algorithm
+    }
+}
+
+/// Builder for [`EncryptionConfiguration`]
+///
+/// See [`EncryptionConfiguration`] for example usage.
+pub struct EncryptionConfigurationBuilder {
+    footer_key_id: String,
+    column_key_ids: Vec<(String, Vec<String>)>,
+    plaintext_footer: bool,
+    double_wrapping: bool,
+    data_key_length_bits: u32,
+    algorithm: EncryptionAlgorithm,
+}
+
+impl EncryptionConfigurationBuilder {
+    /// Create a new [`EncryptionConfigurationBuilder`] with the ID of the
+    /// master key used to wrap the footer key
+    pub fn new(footer_key_id: String) -> Self {
+        Self {
+            footer_key_id,
+            column_key_ids: Vec::new(),
+            plaintext_footer: false,
+            double_wrapping: true,
+            data_key_length_bits: 128,
+            algorithm: EncryptionAlgorithm::default(),
+        }
+    }
+
+    /// Encrypt the given columns with data keys wrapped by the master key with ID `master_key_id`.
+    /// If no column keys are configured then all columns are encrypted with the footer key,
+    /// otherwise only the columns with a key are encrypted.
+    pub fn with_column_keys(mut self, master_key_id: String, column_paths: Vec<String>) -> Self {
+        self.column_key_ids.push((master_key_id, column_paths));
+        self
+    }
+
+    /// Set if the footer should be stored in plaintext (not encrypted). Defaults to false.
+    pub fn with_plaintext_footer(mut self, plaintext_footer: bool) -> Self {
+        self.plaintext_footer = plaintext_footer;
+        self
+    }
+
+    /// Set whether data keys are wrapped by key encryption keys (KEKs), which are in turn wrapped
+    /// by master keys in the KMS. This reduces the number of KMS calls. Defaults to true.
+    pub fn with_double_wrapping(mut self, double_wrapping: bool) -> Self {
+        self.double_wrapping = double_wrapping;
+        self
+    }
+
+    /// Set the length in bits of the generated data keys, either 128 or 256. Defaults to 128.
+    pub fn with_data_key_length_bits(mut self, data_key_length_bits: u32) -> Self {
+        self.data_key_length_bits = data_key_length_bits;
+        self
+    }
+
+    /// Set the algorithm used to encrypt the file. Defaults to [`EncryptionAlgorithm::AesGcmV1`].
+    pub fn with_algorithm(mut self, algorithm: EncryptionAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Build the encryption configuration
+    pub fn build(self) -> Result<EncryptionConfiguration> {
+        if !matches!(self.data_key_length_bits, 128 | 256) {
+            return Err(general_err!(
+                "Unsupported data key length: {} bits. Data keys must be 128 or 256 bits",
+                self.data_key_length_bits
+            ));
+        }
+        let mut columns = HashSet::new();
+        for column_path in self.column_key_ids.iter().flat_map(|(_, paths)| paths) {
+            if !columns.insert(column_path) {
+                return Err(general_err!(
+                    "Multiple keys specified for column '{}'",
+                    column_path
+                ));
+            }
+        }
+        Ok(EncryptionConfiguration {
+            footer_key_id: self.footer_key_id,
+            column_key_ids: self.column_key_ids,
+            plaintext_footer: self.plaintext_footer,
+            double_wrapping: self.double_wrapping,
+            data_key_length_bits: self.data_key_length_bits,
+            algorithm: self.algorithm,
+        })
+    }
+}
+
+/// Creates file encryption and decryption properties using keys managed by a KMS,
+/// compatible with the `PropertiesDrivenCryptoFactory` of parquet-mr (parquet-java).
+///
+/// When writing a file, random data encryption keys (DEKs) are generated and wrapped with
+/// master keys using the [`KmsClient`], and the resulting key material is stored in the file's
+/// key metadata. When reading, the key metadata is used to unwrap the data keys.
+pub struct CryptoFactory {
+    kms_client: Arc<dyn KmsClient>,
+}
+
+impl CryptoFactory {
+    /// Create a new [`CryptoFactory`] that wraps and unwraps keys with the given KMS client
+    pub fn new(kms_client: Arc<dyn KmsClient>) -> Self {
+        Self { kms_client }
+    }
+
+    /// Create file encryption properties for writing a file, using newly generated data keys
+    pub fn file_encryption_properties(
+        &self,
+        config: &EncryptionConfiguration,
+    ) -> Result<Arc<FileEncryptionProperties>> {
+        let key_length = config.data_key_length_bits as usize / 8;
+        let generate_key =
+            || -> Result<Vec<u8>> { Ok(EncryptionKey::generate(key_length)?.key().to_vec()) };
+        let mut key_wrapper = FileKeyWrapper::new(self.kms_client.clone(), config.double_wrapping);
+
+        let footer_key = generate_key()?;
+        let footer_key_metadata =
+            key_wrapper.get_key_metadata(&footer_key, &config.footer_key_id, true)?;
+        let mut builder = FileEncryptionProperties::builder(footer_key)
+            .with_footer_key_metadata(footer_key_metadata)
+            .with_plaintext_footer(config.plaintext_footer)
+            .with_algorithm(config.algorithm);
+
+        let mut uniform_encryption = true;
+        for (master_key_id, column_paths) in &config.column_key_ids {
+            for column_path in column_paths {
+                let column_key = generate_key()?;
+                let key_metadata =
+                    key_wrapper.get_key_metadata(&column_key, master_key_id, false)?;
+                builder =
+                    builder.with_column_key_and_metadata(column_path, column_key, key_metadata);
+                uniform_encryption = false;
+            }
+        }
+        if uniform_encryption {
+            builder = builder.with_uniform_encryption();
+        }
+
+        builder.build()
+    }
+
+    /// Create file decryption properties for reading files written with properties
+    /// created by [`Self::file_encryption_properties`]
+    pub fn file_decryption_properties(&self) -> Result<Arc<FileDecryptionProperties>> {
+        let key_retriever = Arc::new(FileKeyUnwrapper::new(self.kms_client.clone()));
+        FileDecryptionProperties::with_key_retriever(key_retriever).build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_encryption_configuration() {
+        let err = EncryptionConfiguration::builder("kf".into())
+            .with_data_key_length_bits(192)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Unsupported data key length: 192 bits. Data keys must be 128 or 256 bits"
+        );
+
+        let err = EncryptionConfiguration::builder("kf".into())
+            .with_column_keys("kc1".into(), vec!["x".into()])
+            .with_column_keys("kc2".into(), vec!["y".into(), "x".into()])
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Multiple keys specified for column 'x'"
+        );
+    }
+}
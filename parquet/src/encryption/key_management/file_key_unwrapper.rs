@@ -0,0 +1,322 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Retrieval of data keys from key material using a KMS
+
+use crate::encryption::decrypt::KeyRetriever;
+use crate::encryption::key_management::key_material::{KeyMaterial, KeyMaterialStore, KeyMetadata};
+use crate::encryption::key_management::key_toolkit::decrypt_key_locally;
+use crate::encryption::key_management::kms::KmsClient;
+use crate::errors::{ParquetError, Result};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A [`KeyRetriever`] that recovers data keys by unwrapping the key material
+/// referenced by a file's key metadata with a [`KmsClient`].
+///
+/// Key material may be stored internally in the key metadata, or externally in a
+/// [`KeyMaterialStore`], which must then be provided with [`Self::with_key_material_store`].
+///
+/// Double wrapped data keys are supported, in which case unwrapped key encryption keys
+/// are cached so that the KMS is usually only called once per key encryption key.
+/// The KMS may be called more than once for a key encryption key if it is first
+/// needed by concurrent reads.
+pub struct FileKeyUnwrapper {
+    kms_client: Arc<dyn KmsClient>,
+    key_material_store: Option<Arc<KeyMaterialStore>>,
+    key_encryption_keys: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl FileKeyUnwrapper {
+    /// Create a new [`FileKeyUnwrapper`] that unwraps keys with the given KMS client
+    pub fn new(kms_client: Arc<dyn KmsClient>) -> Self {
+        Self {
+            kms_client,
+            key_material_store: None,
+            key_encryption_keys: Mutex::default(),
+        }
+    }
+
+    /// Set the store holding key material for files written with external key material
+    pub fn with_key_material_store(mut self, key_material_store: Arc<KeyMaterialStore>) -> Self {
+        self.key_material_store = Some(key_material_store);
+        self
+    }
+
+    fn unwrap_double_wrapped_key(
+        &self,
+        key_material: &KeyMaterial,
+        kek_id: &str,
+        wrapped_kek: &str,
+    ) -> Result<Vec<u8>> {
+        let kek_id_bytes = BASE64_STANDARD
+            .decode(kek_id)
+            .map_err(|e| general_err!("Could not decode key encryption key ID: {}", e))?;
+        let cached_kek = self.key_encryption_keys()?.get(kek_id).cloned();
+        let kek = match cached_kek {
+            Some(kek) => kek,
+            None => {
+                // The lock isn't held while calling the KMS, so unwrapping other keys
+                // doesn't wait for the KMS and a failing client can't poison the cache
+                let kek = self
+                    .kms_client
+                    .unwrap_key(wrapped_kek, key_material.master_key_id())?;
+                self.key_encryption_keys()?
+                    .insert(kek_id.to_owned(), kek.clone());
+                kek
+            }
+        };
+        decrypt_key_locally(key_material.wrapped_dek(), &kek, &kek_id_bytes)
+    }
+
+    fn key_encryption_keys(&self) -> Result<MutexGuard<'_, HashMap<String, Vec<u8>>>> {
+        self.key_encryption_keys
+            .lock()
+            .map_err(|_| general_err!("Key encryption key cache lock is poisoned"))
+    }
+}
+
+impl KeyRetriever for FileKeyUnwrapper {
+    fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>> {
+        let key_material = match KeyMetadata::parse(key_metadata)? {
+            KeyMetadata::Internal(key_material) => key_material,
+            KeyMetadata::External { key_reference } => {
+                let key_material_store = self.key_material_store.as_ref().ok_or_else(|| {
+                    general_err!(
+                        "Key material is stored externally but no key material store was provided"
+                    )
+                })?;
+                key_material_store
+                    .get_key_material(&key_reference)
+                    .ok_or_else(|| {
+                        general_err!("Key material not found for reference '{}'", key_reference)
+                    })?
+                    .clone()
+            }
+        };
+        match (
+            key_material.key_encryption_key_id(),
+            key_material.wrapped_kek(),
+        ) {
+            (Some(kek_id), Some(wrapped_kek)) => {
+                self.unwrap_double_wrapped_key(&key_material, kek_id, wrapped_kek)
+            }
+            _ => self
+                .kms_client
+                .unwrap_key(key_material.wrapped_dek(), key_material.master_key_id()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encryption::key_management::key_material::{FOOTER_KEY_REFERENCE, KeyMaterial};
+    use crate::encryption::key_management::key_toolkit::encrypt_key_locally;
+    use std::collections::HashSet;
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// A mock KMS that "wraps" keys by prefixing them with the master key ID
+    struct MockKmsClient {
+        master_keys: HashSet<String>,
+    }
+
+    impl KmsClient for MockKmsClient {
+        fn wrap_key(&self, key_bytes: &[u8], master_key_identifier: &str) -> Result<String> {
+            let key = String::from_utf8(key_bytes.to_vec()).unwrap();
+            Ok(format!("{master_key_identifier}:{key}"))
+        }
+
+        fn unwrap_key(&self, wrapped_key: &str, master_key_identifier: &str) -> Result<Vec<u8>> {
+            if !self.master_keys.contains(master_key_identifier) {
+                return Err(general_err!(
+                    "Unknown master key '{}'",
+                    master_key_identifier
+                ));
+            }
+            match wrapped_key.split_once(':') {
+                Some((key_id, key)) if key_id == master_key_identifier => Ok(key.into()),
+                _ => Err(general_err!(
+                    "Key was not wrapped by '{}'",
+                    master_key_identifier
+                )),
+            }
+        }
+    }
+
+    fn kms_client() -> Arc<dyn KmsClient> {
+        Arc::new(MockKmsClient {
+            master_keys: HashSet::from(["kf".to_owned(), "kc1".to_owned()]),
+        })
+    }
+
+    #[test]
+    fn test_retrieve_key_from_external_key_material() {
+        let kms_client = kms_client();
+        let mut store = KeyMaterialStore::new();
+        store.add_key_material(
+            FOOTER_KEY_REFERENCE.to_owned(),
+            KeyMaterial::new(
+                "kf".to_owned(),
+                kms_client.wrap_key(b"0123456789012345", "kf").unwrap(),
+            )
+            .with_footer_key("DEFAULT".to_owned(), "DEFAULT".to_owned()),
+        );
+        store.add_key_material(
+            "columnKey0".to_owned(),
+            KeyMaterial::new(
+                "kc1".to_owned(),
+                kms_client.wrap_key(b"1234567890123450", "kc1").unwrap(),
+            ),
+        );
+        // Check that the store is read back from its serialized form
+        let store = KeyMaterialStore::parse(&store.serialize()).unwrap();
+
+        let unwrapper = FileKeyUnwrapper::new(kms_client).with_key_material_store(Arc::new(store));
+        let key_metadata = |key_reference: &str| {
+            KeyMetadata::External {
+                key_reference: key_reference.to_owned(),
+            }
+            .serialize()
+        };
+        assert_eq!(
+            unwrapper
+                .retrieve_key(&key_metadata(FOOTER_KEY_REFERENCE))
+                .unwrap(),
+            b"0123456789012345"
+        );
+        assert_eq!(
+            unwrapper.retrieve_key(&key_metadata("columnKey0")).unwrap(),
+            b"1234567890123450"
+        );
+        let err = unwrapper
+            .retrieve_key(&key_metadata("columnKey1"))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Key material not found for reference 'columnKey1'"
+        );
+    }
+
+    #[test]
+    fn test_retrieve_key_from_internal_key_material() {
+        let kms_client = kms_client();
+        let key_metadata = KeyMetadata::Internal(KeyMaterial::new(
+            "kc1".to_owned(),
+            kms_client.wrap_key(b"1234567890123450", "kc1").unwrap(),
+        ))
+        .serialize();
+
+        let unwrapper = FileKeyUnwrapper::new(kms_client);
+        assert_eq!(
+            unwrapper.retrieve_key(&key_metadata).unwrap(),
+            b"1234567890123450"
+        );
+    }
+
+    #[test]
+    fn test_retrieve_key_without_key_material_store() {
+        let key_metadata = KeyMetadata::External {
+            key_reference: FOOTER_KEY_REFERENCE.to_owned(),
+        }
+        .serialize();
+        let unwrapper = FileKeyUnwrapper::new(kms_client());
+        let err = unwrapper.retrieve_key(&key_metadata).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Key material is stored externally but no key material store was provided"
+        );
+    }
+
+    /// Wraps the mock KMS, counting unwrap calls and optionally panicking on the next call
+    struct CountingKmsClient {
+        inner: Arc<dyn KmsClient>,
+        unwrap_calls: AtomicUsize,
+        panic_next_unwrap: AtomicBool,
+    }
+
+    impl KmsClient for CountingKmsClient {
+        fn wrap_key(&self, key_bytes: &[u8], master_key_identifier: &str) -> Result<String> {
+            self.inner.wrap_key(key_bytes, master_key_identifier)
+        }
+
+        fn unwrap_key(&self, wrapped_key: &str, master_key_identifier: &str) -> Result<Vec<u8>> {
+            self.unwrap_calls.fetch_add(1, Ordering::Relaxed);
+            if self.panic_next_unwrap.swap(false, Ordering::Relaxed) {
+                panic!("KMS client failure");
+            }
+            self.inner.unwrap_key(wrapped_key, master_key_identifier)
+        }
+    }
+
+    fn double_wrapped_key_metadata(kms_client: &dyn KmsClient) -> Vec<u8> {
+        let kek = b"abcdefghijklmnop";
+        let kek_id = BASE64_STANDARD.encode(b"kek id");
+        let wrapped_dek = encrypt_key_locally(b"1234567890123450", kek, b"kek id").unwrap();
+        let wrapped_kek = kms_client.wrap_key(kek, "kc1").unwrap();
+        KeyMetadata::Internal(
+            KeyMaterial::new("kc1".to_owned(), wrapped_dek)
+                .with_key_encryption_key(kek_id, wrapped_kek),
+        )
+        .serialize()
+    }
+
+    #[test]
+    fn test_key_encryption_keys_cached_after_kms_panic() {
+        let kms_client = Arc::new(CountingKmsClient {
+            inner: kms_client(),
+            unwrap_calls: AtomicUsize::new(0),
+            panic_next_unwrap: AtomicBool::new(true),
+        });
+        let key_metadata = double_wrapped_key_metadata(kms_client.as_ref());
+        let unwrapper = FileKeyUnwrapper::new(kms_client.clone());
+
+        let result = catch_unwind(AssertUnwindSafe(|| unwrapper.retrieve_key(&key_metadata)));
+        assert!(result.is_err());
+
+        // The panic didn't poison the cache, and the key encryption key is cached once unwrapped
+        for _ in 0..2 {
+            assert_eq!(
+                unwrapper.retrieve_key(&key_metadata).unwrap(),
+                b"1234567890123450"
+            );
+        }
+        assert_eq!(kms_client.unwrap_calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_poisoned_key_encryption_key_cache() {
+        let kms_client = kms_client();
+        let key_metadata = double_wrapped_key_metadata(kms_client.as_ref());
+        let unwrapper = FileKeyUnwrapper::new(kms_client);
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let _guard = unwrapper.key_encryption_keys.lock().unwrap();
+            panic!("poison the lock");
+        }));
+        assert!(result.is_err());
+
+        let err = unwrapper.retrieve_key(&key_metadata).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Key encryption key cache lock is poisoned"
+        );
+    }
+}
@@ -0,0 +1,119 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Wrapping of the data keys used to encrypt a file
+
+use crate::encryption::ciphers::CryptoOperation;
+use crate::encryption::key_management::key_material::{KeyMaterial, KeyMetadata};
+use crate::encryption::key_management::key_toolkit::encrypt_key_locally;
+use crate::encryption::key_management::kms::KmsClient;
+use crate::errors::Result;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// ID of the KMS instance recorded in footer key material
+pub(crate) const KMS_INSTANCE_ID_DEFAULT: &str = "DEFAULT";
+
+/// URL of the KMS instance recorded in footer key material
+pub(crate) const KMS_INSTANCE_URL_DEFAULT: &str = "DEFAULT";
+
+const KEK_LENGTH: usize = 16;
+const KEK_ID_LENGTH: usize = 16;
+
+/// A key encryption key (KEK), used to wrap data keys when double wrapping is enabled
+struct KeyEncryptionKey {
+    key: Vec<u8>,
+    id: Vec<u8>,
+    encoded_id: String,
+    encoded_wrapped_key: String,
+}
+
+/// Creates the key metadata for the data keys used to encrypt a single file.
+///
+/// With double wrapping, data keys are wrapped locally by a key encryption key that is
+/// generated per master key, so that the KMS is only called once per master key.
+pub(crate) struct FileKeyWrapper {
+    kms_client: Arc<dyn KmsClient>,
+    double_wrapping: bool,
+    rng: SystemRandom,
+    key_encryption_keys: HashMap<String, KeyEncryptionKey>,
+}
+
+impl FileKeyWrapper {
+    pub(crate) fn new(kms_client: Arc<dyn KmsClient>, double_wrapping: bool) -> Self {
+        Self {
+            kms_client,
+            double_wrapping,
+            rng: SystemRandom::new(),
+            key_encryption_keys: HashMap::new(),
+        }
+    }
+
+    /// Wrap a data key with the master key identified by `master_key_id`, and
+    /// return the key metadata to store in the file
+    pub(crate) fn get_key_metadata(
+        &mut self,
+        data_key: &[u8],
+        master_key_id: &str,
+        is_footer_key: bool,
+    ) -> Result<Vec<u8>> {
+        let key_material = if self.double_wrapping {
+            let kek = self.get_key_encryption_key(master_key_id)?;
+            let wrapped_dek = encrypt_key_locally(data_key, &kek.key, &kek.id)?;
+            KeyMaterial::new(master_key_id.to_owned(), wrapped_dek)
+                .with_key_encryption_key(kek.encoded_id.clone(), kek.encoded_wrapped_key.clone())
+        } else {
+            let wrapped_dek = self.kms_client.wrap_key(data_key, master_key_id)?;
+            KeyMaterial::new(master_key_id.to_owned(), wrapped_dek)
+        };
+        let key_material = if is_footer_key {
+            key_material.with_footer_key(
+                KMS_INSTANCE_ID_DEFAULT.to_owned(),
+                KMS_INSTANCE_URL_DEFAULT.to_owned(),
+            )
+        } else {
+            key_material
+        };
+        Ok(KeyMetadata::Internal(key_material).serialize())
+    }
+
+    fn get_key_encryption_key(&mut self, master_key_id: &str) -> Result<&KeyEncryptionKey> {
+        if !self.key_encryption_keys.contains_key(master_key_id) {
+            let mut key = vec![0u8; KEK_LENGTH];
+            self.rng
+                .fill(&mut key)
+                .map_err(CryptoOperation::RandomFill.error())?;
+            let mut id = vec![0u8; KEK_ID_LENGTH];
+            self.rng
+                .fill(&mut id)
+                .map_err(CryptoOperation::RandomFill.error())?;
+            let encoded_wrapped_key = self.kms_client.wrap_key(&key, master_key_id)?;
+            let kek = KeyEncryptionKey {
+                encoded_id: BASE64_STANDARD.encode(&id),
+                key,
+                id,
+                encoded_wrapped_key,
+            };
+            self.key_encryption_keys
+                .insert(master_key_id.to_owned(), kek);
+        }
+        Ok(&self.key_encryption_keys[master_key_id])
+    }
+}
@@ -0,0 +1,450 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! JSON key material and key metadata formats, as used by parquet-mr (parquet-java)
+
+use crate::errors::{ParquetError, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+const KEY_MATERIAL_TYPE_FIELD: &str = "keyMaterialType";
+const KEY_MATERIAL_TYPE: &str = "PKMT1";
+const INTERNAL_STORAGE_FIELD: &str = "internalStorage";
+const KEY_REFERENCE_FIELD: &str = "keyReference";
+const IS_FOOTER_KEY_FIELD: &str = "isFooterKey";
+const KMS_INSTANCE_ID_FIELD: &str = "kmsInstanceID";
+const KMS_INSTANCE_URL_FIELD: &str = "kmsInstanceURL";
+const MASTER_KEY_ID_FIELD: &str = "masterKeyID";
+const WRAPPED_DEK_FIELD: &str = "wrappedDEK";
+const DOUBLE_WRAPPING_FIELD: &str = "doubleWrapping";
+const KEK_ID_FIELD: &str = "keyEncryptionKeyID";
+const WRAPPED_KEK_FIELD: &str = "wrappedKEK";
+
+/// Reference used for the footer key material in a [`KeyMaterialStore`]
+pub const FOOTER_KEY_REFERENCE: &str = "footerKey";
+
+/// Prefix of the references used for column key materials in a [`KeyMaterialStore`],
+/// which is followed by the index of the column key
+pub const COLUMN_KEY_REFERENCE_PREFIX: &str = "columnKey";
+
+/// The information required to recover a data encryption key (DEK) using a KMS
+///
+/// # Example
+///
+/// ```
+/// # use parquet::encryption::key_management::key_material::KeyMaterial;
+/// let key_material = KeyMaterial::new("kc1".to_owned(), "d3JhcHBlZA==".to_owned());
+/// let json = key_material.serialize();
+/// assert_eq!(KeyMaterial::parse(&json)?, key_material);
+/// # Ok::<(), parquet::errors::ParquetError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMaterial {
+    is_footer_key: bool,
+    kms_instance_id: Option<String>,
+    kms_instance_url: Option<String>,
+    master_key_id: String,
+    wrapped_dek: String,
+    key_encryption_key: Option<(String, String)>,
+}
+
+impl KeyMaterial {
+    /// Create key material for a column key that was wrapped directly by the master key
+    /// with ID `master_key_id`
+    pub fn new(master_key_id: String, wrapped_dek: String) -> Self {
+        Self {
+            is_footer_key: false,
+            kms_instance_id: None,
+            kms_instance_url: None,
+            master_key_id,
+            wrapped_dek,
+            key_encryption_key: None,
+        }
+    }
+
+    /// Mark this as footer key material, which also records the KMS instance used
+    pub fn with_footer_key(mut self, kms_instance_id: String, kms_instance_url: String) -> Self {
+        self.is_footer_key = true;
+        self.kms_instance_id = Some(kms_instance_id);
+        self.kms_instance_url = Some(kms_instance_url);
+        self
+    }
+
+    /// Set the key encryption key (KEK) used for double wrapping. The data key is wrapped
+    /// by the KEK, and the KEK is wrapped by the master key.
+    pub fn with_key_encryption_key(mut self, kek_id: String, wrapped_kek: String) -> Self {
+        self.key_encryption_key = Some((kek_id, wrapped_kek));
+        self
+    }
+
+    /// Whether this is the key material for the footer key
+    pub fn is_footer_key(&self) -> bool {
+        self.is_footer_key
+    }
+
+    /// ID of the KMS instance, only set for footer key material
+    pub fn kms_instance_id(&self) -> Option<&str> {
+        self.kms_instance_id.as_deref()
+    }
+
+    /// URL of the KMS instance, only set for footer key material
+    pub fn kms_instance_url(&self) -> Option<&str> {
+        self.kms_instance_url.as_deref()
+    }
+
+    /// ID of the master key used to wrap the data key or key encryption key
+    pub fn master_key_id(&self) -> &str {
+        &self.master_key_id
+    }
+
+    /// The wrapped data encryption key
+    pub fn wrapped_dek(&self) -> &str {
+        &self.wrapped_dek
+    }
+
+    /// Whether the data key is wrapped by a key encryption key rather than the master key
+    pub fn is_double_wrapped(&self) -> bool {
+        self.key_encryption_key.is_some()
+    }
+
+    /// ID of the key encryption key, only set when double wrapping is used
+    pub fn key_encryption_key_id(&self) -> Option<&str> {
+        self.key_encryption_key.as_ref().map(|(id, _)| id.as_str())
+    }
+
+    /// The key encryption key wrapped by the master key, only set when double wrapping is used
+    pub fn wrapped_kek(&self) -> Option<&str> {
+        self.key_encryption_key
+            .as_ref()
+            .map(|(_, wrapped_kek)| wrapped_kek.as_str())
+    }
+
+    /// Parse key material from JSON
+    pub fn parse(key_material: &str) -> Result<Self> {
+        Self::from_json_map(&parse_json_object(key_material)?)
+    }
+
+    /// Serialize key material to JSON
+    pub fn serialize(&self) -> String {
+        Value::Object(self.to_json_map()).to_string()
+    }
+
+    fn from_json_map(map: &Map<String, Value>) -> Result<Self> {
+        let key_material_type = get_string(map, KEY_MATERIAL_TYPE_FIELD)?;
+        if key_material_type != KEY_MATERIAL_TYPE {
+            return Err(general_err!(
+                "Unsupported key material type: {}",
+                key_material_type
+            ));
+        }
+        let is_footer_key = get_bool(map, IS_FOOTER_KEY_FIELD)?;
+        let (kms_instance_id, kms_instance_url) = if is_footer_key {
+            (
+                Some(get_string(map, KMS_INSTANCE_ID_FIELD)?.to_owned()),
+                Some(get_string(map, KMS_INSTANCE_URL_FIELD)?.to_owned()),
+            )
+        } else {
+            (None, None)
+        };
+        let key_encryption_key = if get_bool(map, DOUBLE_WRAPPING_FIELD)? {
+            Some((
+                get_string(map, KEK_ID_FIELD)?.to_owned(),
+                get_string(map, WRAPPED_KEK_FIELD)?.to_owned(),
+            ))
+        } else {
+            None
+        };
+        Ok(Self {
+            is_footer_key,
+            kms_instance_id,
+            kms_instance_url,
+            master_key_id: get_string(map, MASTER_KEY_ID_FIELD)?.to_owned(),
+            wrapped_dek: get_string(map, WRAPPED_DEK_FIELD)?.to_owned(),
+            key_encryption_key,
+        })
+    }
+
+    fn to_json_map(&self) -> Map<String, Value> {
+        let mut map = Map::new();
+        map.insert(KEY_MATERIAL_TYPE_FIELD.into(), KEY_MATERIAL_TYPE.into());
+        map.insert(IS_FOOTER_KEY_FIELD.into(), self.is_footer_key.into());
+        if let Some(kms_instance_id) = &self.kms_instance_id {
+            map.insert(
+                KMS_INSTANCE_ID_FIELD.into(),
+                kms_instance_id.as_str().into(),
+            );
+        }
+        if let Some(kms_instance_url) = &self.kms_instance_url {
+            map.insert(
+                KMS_INSTANCE_URL_FIELD.into(),
+                kms_instance_url.as_str().into(),
+            );
+        }
+        map.insert(
+            MASTER_KEY_ID_FIELD.into(),
+            self.master_key_id.as_str().into(),
+        );
+        map.insert(WRAPPED_DEK_FIELD.into(), self.wrapped_dek.as_str().into());
+        map.insert(
+            DOUBLE_WRAPPING_FIELD.into(),
+            self.key_encryption_key.is_some().into(),
+        );
+        if let Some((kek_id, wrapped_kek)) = &self.key_encryption_key {
+            map.insert(KEK_ID_FIELD.into(), kek_id.as_str().into());
+            map.insert(WRAPPED_KEK_FIELD.into(), wrapped_kek.as_str().into());
+        }
+        map
+    }
+}
+
+/// Key metadata stored in a Parquet file, which either contains the key material
+/// or references key material held in a [`KeyMaterialStore`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyMetadata {
+    /// The key material is stored in the key metadata
+    Internal(KeyMaterial),
+    /// The key material is stored externally, under the given reference
+    External {
+        /// The reference to the key material in the key material store
+        key_reference: String,
+    },
+}
+
+impl KeyMetadata {
+    /// Parse key metadata read from a Parquet file
+    pub fn parse(key_metadata: &[u8]) -> Result<Self> {
+        let key_metadata = std::str::from_utf8(key_metadata)
+            .map_err(|e| general_err!("Key metadata is not valid UTF-8: {}", e))?;
+        let map = parse_json_object(key_metadata)?;
+        if get_bool(&map, INTERNAL_STORAGE_FIELD)? {
+            Ok(Self::Internal(KeyMaterial::from_json_map(&map)?))
+        } else {
+            Ok(Self::External {
+                key_reference: get_string(&map, KEY_REFERENCE_FIELD)?.to_owned(),
+            })
+        }
+    }
+
+    /// Serialize key metadata to be stored in a Parquet file
+    pub fn serialize(&self) -> Vec<u8> {
+        let map = match self {
+            Self::Internal(key_material) => {
+                let mut map = key_material.to_json_map();
+                map.insert(INTERNAL_STORAGE_FIELD.into(), true.into());
+                map
+            }
+            Self::External { key_reference } => {
+                let mut map = Map::new();
+                map.insert(KEY_MATERIAL_TYPE_FIELD.into(), KEY_MATERIAL_TYPE.into());
+                map.insert(INTERNAL_STORAGE_FIELD.into(), false.into());
+                map.insert(KEY_REFERENCE_FIELD.into(), key_reference.as_str().into());
+                map
+            }
+        };
+        Value::Object(map).to_string().into_bytes()
+    }
+}
+
+/// Key material stored outside of a Parquet file, keyed by the key references
+/// stored in the file's key metadata.
+///
+/// This is serialized as a JSON object mapping key references to serialized key material,
+/// which is the format parquet-mr uses for key material files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyMaterialStore {
+    key_materials: HashMap<String, KeyMaterial>,
+}
+
+impl KeyMaterialStore {
+    /// Create a new empty [`KeyMaterialStore`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a key material store from JSON
+    pub fn parse(json: &str) -> Result<Self> {
+        let key_materials = parse_json_object(json)?
+            .iter()
+            .map(|(key_reference, key_material)| {
+                let key_material = key_material.as_str().ok_or_else(|| {
+                    general_err!("Key material for '{}' is not a string", key_reference)
+                })?;
+                Ok((key_reference.clone(), KeyMaterial::parse(key_material)?))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { key_materials })
+    }
+
+    /// Serialize the key material store to JSON
+    pub fn serialize(&self) -> String {
+        let map = self
+            .key_materials
+            .iter()
+            .map(|(key_reference, key_material)| {
+                (key_reference.clone(), key_material.serialize().into())
+            })
+            .collect::<Map<_, _>>();
+        Value::Object(map).to_string()
+    }
+
+    /// Add key material under the given key reference, replacing any existing key material
+    pub fn add_key_material(&mut self, key_reference: String, key_material: KeyMaterial) {
+        self.key_materials.insert(key_reference, key_material);
+    }
+
+    /// Get the key material with the given key reference
+    pub fn get_key_material(&self, key_reference: &str) -> Option<&KeyMaterial> {
+        self.key_materials.get(key_reference)
+    }
+
+    /// Iterate over the references of all stored key materials
+    pub fn key_references(&self) -> impl Iterator<Item = &str> {
+        self.key_materials
+            .keys()
+            .map(|key_reference| key_reference.as_str())
+    }
+}
+
+fn parse_json_object(json: &str) -> Result<Map<String, Value>> {
+    match serde_json::from_str(json) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Err(general_err!("Key material is not a JSON object")),
+        Err(e) => Err(general_err!("Failed to parse key material JSON: {}", e)),
+    }
+}
+
+fn get_string<'a>(map: &'a Map<String, Value>, field: &str) -> Result<&'a str> {
+    match map.get(field) {
+        Some(Value::String(value)) => Ok(value),
+        Some(_) => Err(general_err!(
+            "Key material field '{}' is not a string",
+            field
+        )),
+        None => Err(general_err!("Key material field '{}' is missing", field)),
+    }
+}
+
+fn get_bool(map: &Map<String, Value>, field: &str) -> Result<bool> {
+    match map.get(field) {
+        Some(Value::Bool(value)) => Ok(*value),
+        Some(_) => Err(general_err!(
+            "Key material field '{}' is not a boolean",
+            field
+        )),
+        None => Err(general_err!("Key material field '{}' is missing", field)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Key material store in the format written by parquet-mr
+    const KEY_MATERIAL_STORE: &str = r#"{
+        "footerKey": "{\"keyMaterialType\":\"PKMT1\",\"isFooterKey\":true,\"kmsInstanceID\":\"DEFAULT\",\"kmsInstanceURL\":\"DEFAULT\",\"masterKeyID\":\"kf\",\"wrappedDEK\":\"d3JhcHBlZF9mb290ZXI=\",\"doubleWrapping\":false}",
+        "columnKey0": "{\"keyMaterialType\":\"PKMT1\",\"isFooterKey\":false,\"masterKeyID\":\"kc1\",\"wrappedDEK\":\"d3JhcHBlZF9jb2x1bW4=\",\"doubleWrapping\":true,\"keyEncryptionKeyID\":\"a2VrX2lk\",\"wrappedKEK\":\"d3JhcHBlZF9rZWs=\"}"
+    }"#;
+
+    #[test]
+    fn test_parse_key_material_store() {
+        let store = KeyMaterialStore::parse(KEY_MATERIAL_STORE).unwrap();
+        let mut key_references: Vec<_> = store.key_references().collect();
+        key_references.sort();
+        assert_eq!(key_references, vec!["columnKey0", "footerKey"]);
+
+        let footer_material = store.get_key_material(FOOTER_KEY_REFERENCE).unwrap();
+        assert!(footer_material.is_footer_key());
+        assert_eq!(footer_material.kms_instance_id(), Some("DEFAULT"));
+        assert_eq!(footer_material.kms_instance_url(), Some("DEFAULT"));
+        assert_eq!(footer_material.master_key_id(), "kf");
+        assert_eq!(footer_material.wrapped_dek(), "d3JhcHBlZF9mb290ZXI=");
+        assert!(!footer_material.is_double_wrapped());
+
+        let column_material = store.get_key_material("columnKey0").unwrap();
+        assert!(!column_material.is_footer_key());
+        assert_eq!(column_material.kms_instance_id(), None);
+        assert_eq!(column_material.master_key_id(), "kc1");
+        assert!(column_material.is_double_wrapped());
+        assert_eq!(column_material.key_encryption_key_id(), Some("a2VrX2lk"));
+        assert_eq!(column_material.wrapped_kek(), Some("d3JhcHBlZF9rZWs="));
+
+        assert!(store.get_key_material("columnKey1").is_none());
+
+        let roundtrip = KeyMaterialStore::parse(&store.serialize()).unwrap();
+        assert_eq!(roundtrip, store);
+    }
+
+    #[test]
+    fn test_key_metadata_roundtrip() {
+        let key_material = KeyMaterial::new("kf".to_owned(), "wrapped".to_owned())
+            .with_footer_key("DEFAULT".to_owned(), "DEFAULT".to_owned())
+            .with_key_encryption_key("kek".to_owned(), "wrapped_kek".to_owned());
+        let internal = KeyMetadata::Internal(key_material);
+        assert_eq!(KeyMetadata::parse(&internal.serialize()).unwrap(), internal);
+
+        let external = KeyMetadata::External {
+            key_reference: "columnKey3".to_owned(),
+        };
+        assert_eq!(KeyMetadata::parse(&external.serialize()).unwrap(), external);
+    }
+
+    #[test]
+    fn test_parse_key_metadata() {
+        let key_metadata =
+            br#"{"keyMaterialType":"PKMT1","internalStorage":false,"keyReference":"footerKey"}"#;
+        assert_eq!(
+            KeyMetadata::parse(key_metadata).unwrap(),
+            KeyMetadata::External {
+                key_reference: FOOTER_KEY_REFERENCE.to_owned()
+            }
+        );
+
+        let key_metadata = br#"{"keyMaterialType":"PKMT1","internalStorage":true,"isFooterKey":false,"masterKeyID":"kc1","wrappedDEK":"abc","doubleWrapping":false}"#;
+        assert_eq!(
+            KeyMetadata::parse(key_metadata).unwrap(),
+            KeyMetadata::Internal(KeyMaterial::new("kc1".to_owned(), "abc".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_key_material() {
+        let err = KeyMaterial::parse("not json").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Parquet error: Failed to parse key material JSON")
+        );
+
+        let err = KeyMaterial::parse(r#"{"keyMaterialType":"PKMT2"}"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Unsupported key material type: PKMT2"
+        );
+
+        let err =
+            KeyMaterial::parse(r#"{"keyMaterialType":"PKMT1","isFooterKey":false}"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Key material field 'doubleWrapping' is missing"
+        );
+
+        let err = KeyMetadata::parse(br#"{"internalStorage":"yes"}"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Key material field 'internalStorage' is not a boolean"
+        );
+    }
+}
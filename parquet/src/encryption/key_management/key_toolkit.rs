@@ -0,0 +1,71 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Local wrapping of keys with AES GCM, used for double wrapping
+
+use crate::encryption::ciphers::{
+    BlockDecryptor, BlockEncryptor, RingGcmBlockDecryptor, RingGcmBlockEncryptor, SIZE_LEN,
+};
+use crate::errors::{ParquetError, Result};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+
+/// Encrypt a key with a wrapping key, returning the base64 encoded nonce, ciphertext and tag.
+/// Unlike Parquet modules, wrapped keys are not prefixed with their length.
+pub(crate) fn encrypt_key_locally(key: &[u8], wrapping_key: &[u8], aad: &[u8]) -> Result<String> {
+    let mut encryptor = RingGcmBlockEncryptor::new(wrapping_key)?;
+    let encrypted_key = encryptor.encrypt(key, aad)?;
+    Ok(BASE64_STANDARD.encode(&encrypted_key[SIZE_LEN..]))
+}
+
+/// Decrypt a key that was encrypted with [`encrypt_key_locally`]
+pub(crate) fn decrypt_key_locally(
+    encoded_encrypted_key: &str,
+    wrapping_key: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>> {
+    let encrypted_key = BASE64_STANDARD
+        .decode(encoded_encrypted_key)
+        .map_err(|e| general_err!("Could not decode wrapped key: {}", e))?;
+    let length = u32::try_from(encrypted_key.len())
+        .map_err(|e| general_err!("Wrapped key too long: {}", e))?;
+    let length_and_ciphertext = [&length.to_le_bytes(), encrypted_key.as_slice()].concat();
+    RingGcmBlockDecryptor::new(wrapping_key)?
+        .decrypt(&length_and_ciphertext, aad)
+        .map_err(|_| general_err!("Failed to unwrap key, the wrapping key may be wrong"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_key_wrapping_roundtrip() {
+        let wrapping_key = b"0123456789012345";
+        let key = b"1234567890123450";
+
+        let wrapped = encrypt_key_locally(key, wrapping_key, b"aad").unwrap();
+        let unwrapped = decrypt_key_locally(&wrapped, wrapping_key, b"aad").unwrap();
+        assert_eq!(unwrapped, key);
+
+        let err = decrypt_key_locally(&wrapped, wrapping_key, b"wrong aad").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Failed to unwrap key, the wrapping key may be wrong"
+        );
+    }
+}
@@ -0,0 +1,33 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Interface to a Key Management Service (KMS)
+
+use crate::errors::Result;
+
+/// A client for a Key Management Service (KMS) that holds master keys.
+///
+/// Master keys never leave the KMS. Instead, the KMS is used to wrap (encrypt)
+/// and unwrap (decrypt) other keys using a master key identified by its ID.
+pub trait KmsClient: Send + Sync {
+    /// Wrap a key with the master key identified by `master_key_identifier`,
+    /// returning the wrapped key as a string, for example base64 encoded ciphertext.
+    fn wrap_key(&self, key_bytes: &[u8], master_key_identifier: &str) -> Result<String>;
+
+    /// Unwrap a key that was wrapped with the master key identified by `master_key_identifier`
+    fn unwrap_key(&self, wrapped_key: &str, master_key_identifier: &str) -> Result<Vec<u8>>;
+}
@@ -0,0 +1,37 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Key management for Parquet Modular Encryption, compatible with the key management
+//! tools of parquet-mr (parquet-java).
+//!
+//! Data encryption keys are wrapped by master keys held in a Key Management Service (KMS),
+//! accessed through a [`KmsClient`](kms::KmsClient). The wrapped keys, together with the
+//! information required to unwrap them, are stored as JSON key material.
+//! Key material is either stored inside the Parquet file as key metadata ("internal storage"),
+//! or in a separate key material store, with the key metadata holding a reference to it
+//! ("external storage").
+//!
+//! A [`CryptoFactory`](crypto_factory::CryptoFactory) creates file encryption properties with
+//! newly generated data keys wrapped by master keys, and file decryption properties that
+//! unwrap the data keys when reading.
+
+pub mod crypto_factory;
+pub mod file_key_unwrapper;
+mod file_key_wrapper;
+pub mod key_material;
+mod key_toolkit;
+pub mod kms;
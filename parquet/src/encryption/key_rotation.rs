@@ -0,0 +1,188 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Rewrite an encrypted Parquet file under new encryption properties, for example to
+//! rotate the footer and column keys.
+//!
+//! The file is read with the old decryption properties and written again with the new
+//! encryption properties. The data is decoded and encoded again, so statistics and page
+//! indexes are recomputed from the same values. Row group boundaries and key-value
+//! metadata are kept. [`reencrypt`] also keeps per column compression codecs and sorting
+//! columns, but uses default values for other writer settings, while
+//! [`reencrypt_with_properties`] takes the other writer settings from the caller.
+//!
+//! # Example
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use arrow_array::{ArrayRef, Int32Array, RecordBatch};
+//! # use parquet::encryption::in_memory::{decrypt_record_batches, encrypt_record_batches};
+//! use parquet::encryption::decrypt::FileDecryptionProperties;
+//! use parquet::encryption::encrypt::FileEncryptionProperties;
+//! use parquet::encryption::key_rotation::reencrypt;
+//!
+//! # let batch = RecordBatch::try_from_iter(vec![
+//! #     ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+//! # ])?;
+//! # let old_encryption_properties =
+//! #     FileEncryptionProperties::builder(b"0123456789012345".into())
+//! #         .with_uniform_encryption()
+//! #         .build()?;
+//! # let encrypted = encrypt_record_batches(&[batch.clone()], old_encryption_properties)?;
+//! let old_keys = FileDecryptionProperties::builder(b"0123456789012345".into()).build()?;
+//! let new_keys = FileEncryptionProperties::builder(b"5432109876543210".into())
+//!     .with_uniform_encryption()
+//!     .build()?;
+//!
+//! let mut rotated = Vec::new();
+//! reencrypt(encrypted, &mut rotated, old_keys, new_keys)?;
+//! # let new_decryption_properties =
+//! #     FileDecryptionProperties::builder(b"5432109876543210".into()).build()?;
+//! # let batches = decrypt_record_batches(rotated.into(), new_decryption_properties)?;
+//! # assert_eq!(batches, vec![batch]);
+//! # Ok::<(), parquet::errors::ParquetError>(())
+//! ```
+
+use crate::arrow::ARROW_SCHEMA_META_KEY;
+use crate::arrow::ArrowWriter;
+use crate::arrow::arrow_reader::{ArrowReaderOptions, ParquetRecordBatchReaderBuilder};
+use crate::encryption::decrypt::FileDecryptionProperties;
+use crate::encryption::encrypt::FileEncryptionProperties;
+use crate::errors::Result;
+use crate::file::metadata::ParquetMetaData;
+use crate::file::properties::WriterProperties;
+use crate::file::reader::ChunkReader;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Read the encrypted Parquet file in `input` with `decryption_properties`, and write it
+/// to `output` encrypted with `encryption_properties`, returning the metadata of the
+/// written file.
+///
+/// The new file keeps exactly the following from the input:
+/// - the schema, including the stored Arrow schema
+/// - the row group boundaries
+/// - the key-value metadata
+/// - the compression codec of each column in the first row group, which is used for that
+///   column in every row group
+/// - the sorting columns of the first row group, which are set on every row group
+///
+/// All other writer settings, such as the writer version, data page size, encodings,
+/// dictionary, statistics and bloom filter settings, use the [`WriterProperties`]
+/// defaults. Use [`reencrypt_with_properties`] to choose them.
+pub fn reencrypt<R: ChunkReader + 'static, W: Write + Send>(
+    input: R,
+    output: W,
+    decryption_properties: Arc<FileDecryptionProperties>,
+    encryption_properties: Arc<FileEncryptionProperties>,
+) -> Result<ParquetMetaData> {
+    let builder = open_input(input, decryption_properties)?;
+    let mut props = WriterProperties::builder();
+    if let Some(row_group) = builder.metadata().row_groups().first() {
+        for column in row_group.columns() {
+            props =
+                props.set_column_compression(column.column_path().clone(), column.compression());
+        }
+        props = props.set_sorting_columns(row_group.sorting_columns().cloned());
+    }
+    rewrite(builder, output, props.build(), encryption_properties)
+}
+
+/// Read the encrypted Parquet file in `input` with `decryption_properties`, and write it
+/// to `output` with `writer_properties`, encrypted with `encryption_properties`, returning
+/// the metadata of the written file.
+///
+/// Any encryption properties set in `writer_properties` are replaced with
+/// `encryption_properties`, and its row group size limits are ignored so that the new file
+/// has the same row group boundaries as the input. The key-value metadata of the input is
+/// kept unless `writer_properties` sets its own. Every other setting, including compression
+/// codecs and sorting columns, is taken from `writer_properties`.
+pub fn reencrypt_with_properties<R: ChunkReader + 'static, W: Write + Send>(
+    input: R,
+    output: W,
+    decryption_properties: Arc<FileDecryptionProperties>,
+    encryption_properties: Arc<FileEncryptionProperties>,
+    writer_properties: WriterProperties,
+) -> Result<ParquetMetaData> {
+    let builder = open_input(input, decryption_properties)?;
+    rewrite(builder, output, writer_properties, encryption_properties)
+}
+
+fn open_input<R: ChunkReader + 'static>(
+    input: R,
+    decryption_properties: Arc<FileDecryptionProperties>,
+) -> Result<ParquetRecordBatchReaderBuilder<R>> {
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    ParquetRecordBatchReaderBuilder::try_new_with_options(input, options)
+}
+
+/// Write the data of `builder` to `output`, with the row groups and, unless `props` sets its
+/// own, the key-value metadata of the input
+fn rewrite<R: ChunkReader + 'static, W: Write + Send>(
+    builder: ParquetRecordBatchReaderBuilder<R>,
+    output: W,
+    props: WriterProperties,
+    encryption_properties: Arc<FileEncryptionProperties>,
+) -> Result<ParquetMetaData> {
+    let metadata = Arc::clone(builder.metadata());
+    let schema = Arc::clone(builder.schema());
+
+    let key_value_metadata = props.key_value_metadata().cloned().or_else(|| {
+        metadata
+            .file_metadata()
+            .key_value_metadata()
+            .map(|key_value_metadata| {
+                // The Arrow schema is stored again by the writer
+                key_value_metadata
+                    .iter()
+                    .filter(|kv| kv.key != ARROW_SCHEMA_META_KEY)
+                    .cloned()
+                    .collect()
+            })
+    });
+    let props = props
+        .into_builder()
+        .with_file_encryption_properties(encryption_properties)
+        .set_max_row_group_row_count(None)
+        .set_max_row_group_bytes(None)
+        .set_key_value_metadata(key_value_metadata)
+        .build();
+    let mut writer = ArrowWriter::try_new(output, schema, Some(props))?;
+
+    // Batches may span row groups, so are split at the row group boundaries of the input
+    let mut row_group_rows = metadata
+        .row_groups()
+        .iter()
+        .map(|row_group| row_group.num_rows() as usize)
+        .filter(|num_rows| *num_rows > 0);
+    let mut remaining = row_group_rows.next().unwrap_or_default();
+    for batch in builder.build()? {
+        let mut batch = batch?;
+        while batch.num_rows() > 0 {
+            let len = batch.num_rows().min(remaining);
+            writer.write(&batch.slice(0, len))?;
+            batch = batch.slice(len, batch.num_rows() - len);
+            remaining -= len;
+            if remaining == 0 {
+                writer.flush()?;
+                remaining = row_group_rows.next().unwrap_or_default();
+            }
+        }
+    }
+    writer.close()
+}
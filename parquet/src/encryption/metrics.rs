@@ -0,0 +1,61 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Metrics for observing the work done to encrypt and decrypt Parquet files
+
+use std::fmt::Debug;
+
+/// A sink for counters of encryption and decryption operations, reported per column.
+///
+/// Set with [`EncryptionPropertiesBuilder::with_metrics`] when writing files, or
+/// [`DecryptionPropertiesBuilder::with_metrics`] when reading them. Nothing is recorded
+/// when no sink is set. Columns are identified by their dotted path.
+///
+/// All methods do nothing by default, so only the counters of interest need to be
+/// implemented. Methods may be called concurrently from multiple threads, and should
+/// return quickly as they are called for every page.
+///
+/// ```
+/// # use parquet::encryption::metrics::EncryptionMetrics;
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// #[derive(Debug, Default)]
+/// struct PageBytes(AtomicUsize);
+///
+/// impl EncryptionMetrics for PageBytes {
+///     fn page_encrypted(&self, _column_path: &str, bytes: usize) {
+///         self.0.fetch_add(bytes, Ordering::Relaxed);
+///     }
+/// }
+/// ```
+///
+/// [`EncryptionPropertiesBuilder::with_metrics`]: crate::encryption::encrypt::EncryptionPropertiesBuilder::with_metrics
+/// [`DecryptionPropertiesBuilder::with_metrics`]: crate::encryption::decrypt::DecryptionPropertiesBuilder::with_metrics
+pub trait EncryptionMetrics: Debug + Send + Sync {
+    /// Called after a data or dictionary page of a column is encrypted,
+    /// with the length in bytes of the page data before encryption
+    fn page_encrypted(&self, _column_path: &str, _bytes: usize) {}
+
+    /// Called after a data or dictionary page of a column is decrypted,
+    /// with the length in bytes of the decrypted page data
+    fn page_decrypted(&self, _column_path: &str, _bytes: usize) {}
+
+    /// Called each time a [`KeyRetriever`] is used to retrieve a key, with the path
+    /// of the column the key is for, or `None` for the footer key
+    ///
+    /// [`KeyRetriever`]: crate::encryption::decrypt::KeyRetriever
+    fn key_retrieved(&self, _column_path: Option<&str>) {}
+}
@@ -105,8 +105,52 @@
 //! }
 //! # Ok::<(), parquet::errors::ParquetError>(())
 //! ```
+//!
+//! # Encryption granularity
+//!
+//! As required by the specification, each page header and page of an encrypted column
+//! is encrypted as a separate module, with the page ordinal included in the module's AAD.
+//! Encrypting a whole column chunk as a single module isn't supported, as this can't be
+//! described in the file metadata and other readers would be unable to read the file.
+//! To reduce the number of encryption operations and the per-page overhead, write larger
+//! pages, for example with
+//! [`WriterPropertiesBuilder::set_encrypted_data_page_min_size`].
+//!
+//! [`WriterPropertiesBuilder::set_encrypted_data_page_min_size`]: crate::file::properties::WriterPropertiesBuilder::set_encrypted_data_page_min_size
+//!
+//! # Binding row groups to a file
+//!
+//! The AAD of every encrypted module includes a file AAD, made of the optional AAD prefix
+//! and 8 random bytes generated for each file, along with the ordinals of the row group,
+//! column and page the module belongs to. A row group copied from another file, or moved
+//! to a different position within the same file, therefore fails to decrypt even when
+//! the same keys and AAD prefix are used. A per row group context isn't mixed into the
+//! AAD, as the specification doesn't allow for one and other readers couldn't read the file.
+//! Context that should be authenticated can instead be stored in the encrypted footer
+//! as key-value metadata, or included in the AAD prefix.
+//!
+//! # Parallel encryption
+//!
+//! Each column chunk is encrypted by its own encryptor, with an independent nonce sequence,
+//! so the columns of a row group can be encoded and encrypted on separate threads.
+//! Create the column writers with an [`ArrowRowGroupWriterFactory`] for the file writer,
+//! write to each [`ArrowColumnWriter`] from a worker thread, then append the closed
+//! column chunks to the row group in schema order. Pages within a column chunk are
+//! always encrypted in order on a single thread, so the page ordinals in the module AADs
+//! are the same as when writing serially.
+//!
+//! [`ArrowRowGroupWriterFactory`]: crate::arrow::arrow_writer::ArrowRowGroupWriterFactory
+//! [`ArrowColumnWriter`]: crate::arrow::arrow_writer::ArrowColumnWriter
 
+pub mod backend;
 pub(crate) mod ciphers;
 pub mod decrypt;
 pub mod encrypt;
-pub(crate) mod modules;
+#[cfg(feature = "arrow")]
+pub mod in_memory;
+pub mod key_loader;
+pub mod key_management;
+#[cfg(feature = "arrow")]
+pub mod key_rotation;
+pub mod metrics;
+pub mod modules;
@@ -15,26 +15,122 @@
 // specific language governing permissions and limitations
 // under the License.
 
+//! Construction of the additional authenticated data (AAD) for Parquet modules
+//!
+//! Each encrypted module of a Parquet file uses a module AAD, made of the file AAD
+//! followed by a suffix that identifies the module, as described in the
+//! [specification](https://github.com/apache/parquet-format/blob/master/Encryption.md#442-aad-suffix).
+
 use crate::errors::ParquetError;
 
-#[derive(PartialEq)]
-pub(crate) enum ModuleType {
+/// The maximum length in bytes of an AAD prefix.
+///
+/// The AAD prefix is part of the AAD of every encrypted module,
+/// so it is limited to avoid excessive memory use and computation.
+pub const MAX_AAD_PREFIX_LEN: usize = 4096;
+
+/// Check that an AAD prefix does not exceed [`MAX_AAD_PREFIX_LEN`]
+pub(crate) fn validate_aad_prefix(aad_prefix: Option<&[u8]>) -> crate::errors::Result<()> {
+    match aad_prefix {
+        Some(aad_prefix) if aad_prefix.len() > MAX_AAD_PREFIX_LEN => Err(general_err!(
+            "AAD prefix length {} exceeds the maximum of {} bytes",
+            aad_prefix.len(),
+            MAX_AAD_PREFIX_LEN
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// The type of an encrypted module, which is included in its AAD suffix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleType {
+    /// The file footer
     Footer = 0,
+    /// Column chunk metadata
     ColumnMetaData = 1,
+    /// Data page body
     DataPage = 2,
+    /// Dictionary page body
     DictionaryPage = 3,
+    /// Data page header
     DataPageHeader = 4,
+    /// Dictionary page header
     DictionaryPageHeader = 5,
+    /// Column index
     ColumnIndex = 6,
+    /// Offset index
     OffsetIndex = 7,
-    _BloomFilterHeader = 8,
-    _BloomFilterBitset = 9,
+    /// Bloom filter header
+    BloomFilterHeader = 8,
+    /// Bloom filter bitset
+    BloomFilterBitset = 9,
 }
 
+/// The AAD of a single module within a row group, as returned by [`row_group_module_aads`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleAad {
+    /// Ordinal of the column within the row group
+    pub column_ordinal: usize,
+    /// The type of module
+    pub module_type: ModuleType,
+    /// Ordinal of the page within the column chunk, set for data pages and their headers
+    pub page_ordinal: Option<usize>,
+    /// The module AAD
+    pub aad: Vec<u8>,
+}
+
+/// Create the AAD used to encrypt or sign the file footer.
+///
+/// This is the file AAD (the AAD prefix, if any, followed by the unique file identifier)
+/// with the footer module type appended.
 pub fn create_footer_aad(file_aad: &[u8]) -> crate::errors::Result<Vec<u8>> {
     create_module_aad(file_aad, ModuleType::Footer, 0, 0, None)
 }
 
+/// List the AADs of the modules of a row group, which can be useful for debugging
+/// encrypted files or testing interoperability with other implementations.
+///
+/// `num_data_pages` holds the number of data pages in each column chunk, so its
+/// length is the number of columns. For each column, the AADs of the column metadata,
+/// dictionary page header and body, each data page header and body, then the column
+/// and offset indexes are returned. Dictionary page modules are included even though
+/// a column chunk may not have a dictionary page.
+pub fn row_group_module_aads(
+    file_aad: &[u8],
+    row_group_idx: usize,
+    num_data_pages: &[usize],
+) -> crate::errors::Result<Vec<ModuleAad>> {
+    let mut module_aads = Vec::new();
+    for (column_ordinal, num_pages) in num_data_pages.iter().enumerate() {
+        let mut push = |module_type: ModuleType, page_ordinal: Option<usize>| {
+            let aad = create_module_aad(
+                file_aad,
+                module_type,
+                row_group_idx,
+                column_ordinal,
+                page_ordinal,
+            )?;
+            module_aads.push(ModuleAad {
+                column_ordinal,
+                module_type,
+                page_ordinal,
+                aad,
+            });
+            Ok::<_, ParquetError>(())
+        };
+        push(ModuleType::ColumnMetaData, None)?;
+        push(ModuleType::DictionaryPageHeader, None)?;
+        push(ModuleType::DictionaryPage, None)?;
+        for page_ordinal in 0..*num_pages {
+            push(ModuleType::DataPageHeader, Some(page_ordinal))?;
+            push(ModuleType::DataPage, Some(page_ordinal))?;
+        }
+        push(ModuleType::ColumnIndex, None)?;
+        push(ModuleType::OffsetIndex, None)?;
+    }
+    Ok(module_aads)
+}
+
 pub(crate) fn create_module_aad(
     file_aad: &[u8],
     module_type: ModuleType,
@@ -96,3 +192,60 @@ pub(crate) fn create_module_aad(
     aad.extend_from_slice((page_ordinal as i16).to_le_bytes().as_ref());
     Ok(aad)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_footer_aad() {
+        let file_aad = b"file_aad";
+        let aad = create_footer_aad(file_aad).unwrap();
+        assert_eq!(aad, b"file_aad\x00");
+    }
+
+    #[test]
+    fn test_row_group_module_aads() {
+        let module_aads = row_group_module_aads(b"aad", 2, &[2, 0]).unwrap();
+        let actual: Vec<_> = module_aads
+            .iter()
+            .map(|m| {
+                (
+                    m.column_ordinal,
+                    m.module_type,
+                    m.page_ordinal,
+                    m.aad.as_slice(),
+                )
+            })
+            .collect();
+
+        use ModuleType::*;
+        let expected: Vec<(usize, ModuleType, Option<usize>, &[u8])> = vec![
+            (0, ColumnMetaData, None, b"aad\x01\x02\x00\x00\x00"),
+            (0, DictionaryPageHeader, None, b"aad\x05\x02\x00\x00\x00"),
+            (0, DictionaryPage, None, b"aad\x03\x02\x00\x00\x00"),
+            (
+                0,
+                DataPageHeader,
+                Some(0),
+                b"aad\x04\x02\x00\x00\x00\x00\x00",
+            ),
+            (0, DataPage, Some(0), b"aad\x02\x02\x00\x00\x00\x00\x00"),
+            (
+                0,
+                DataPageHeader,
+                Some(1),
+                b"aad\x04\x02\x00\x00\x00\x01\x00",
+            ),
+            (0, DataPage, Some(1), b"aad\x02\x02\x00\x00\x00\x01\x00"),
+            (0, ColumnIndex, None, b"aad\x06\x02\x00\x00\x00"),
+            (0, OffsetIndex, None, b"aad\x07\x02\x00\x00\x00"),
+            (1, ColumnMetaData, None, b"aad\x01\x02\x00\x01\x00"),
+            (1, DictionaryPageHeader, None, b"aad\x05\x02\x00\x01\x00"),
+            (1, DictionaryPage, None, b"aad\x03\x02\x00\x01\x00"),
+            (1, ColumnIndex, None, b"aad\x06\x02\x00\x01\x00"),
+            (1, OffsetIndex, None, b"aad\x07\x02\x00\x01\x00"),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
@@ -56,6 +56,23 @@ pub enum ParquetError {
     /// Returned when a function needs more data to complete properly.
     /// The `Range<u64>` indicates the range of bytes that are needed.
     NeedMoreDataRange(std::ops::Range<u64>),
+    #[cfg(feature = "encryption")]
+    /// Decryption failed to authenticate an encrypted module.
+    /// Returned when the key is wrong or the encrypted data has been tampered with.
+    DecryptionKeyError(String),
+    #[cfg(feature = "encryption")]
+    /// An encrypted module is malformed, for example it is truncated or its
+    /// length prefix is inconsistent with its length.
+    CiphertextFormatError(String),
+    #[cfg(feature = "encryption")]
+    /// Encrypting a module failed, for example because the encryptor's nonces are exhausted.
+    /// Failures writing encrypted data to a sink are reported as [`ParquetError::External`]
+    /// errors with an [`io::Error`] source instead.
+    EncryptionError(String),
+    #[cfg(feature = "encryption")]
+    /// A key was rejected by the cipher backend, for example because its length is not supported.
+    /// The message identifies the key, and the backend's error is the source.
+    InvalidKeyError(String, Box<ParquetError>),
 }
 
 impl std::fmt::Display for ParquetError {
@@ -76,6 +93,22 @@ impl std::fmt::Display for ParquetError {
             ParquetError::NeedMoreDataRange(range) => {
                 write!(fmt, "NeedMoreDataRange: {}..{}", range.start, range.end)
             }
+            #[cfg(feature = "encryption")]
+            ParquetError::DecryptionKeyError(message) => {
+                write!(fmt, "Decryption key error: {message}")
+            }
+            #[cfg(feature = "encryption")]
+            ParquetError::CiphertextFormatError(message) => {
+                write!(fmt, "Ciphertext format error: {message}")
+            }
+            #[cfg(feature = "encryption")]
+            ParquetError::EncryptionError(message) => {
+                write!(fmt, "Encryption error: {message}")
+            }
+            #[cfg(feature = "encryption")]
+            ParquetError::InvalidKeyError(message, source) => {
+                write!(fmt, "Invalid key error: {message}. {source}")
+            }
         }
     }
 }
@@ -84,11 +117,41 @@ impl Error for ParquetError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             ParquetError::External(e) => Some(e.as_ref()),
+            #[cfg(feature = "encryption")]
+            ParquetError::InvalidKeyError(_, source) => Some(source.as_ref()),
             _ => None,
         }
     }
 }
 
+#[cfg(feature = "encryption")]
+impl ParquetError {
+    /// Prepends `context` to the message of an error, keeping its variant so that
+    /// callers can still tell a wrong key apart from malformed data.
+    /// Errors without a message are returned unchanged.
+    pub(crate) fn with_context(self, context: impl std::fmt::Display) -> Self {
+        match self {
+            ParquetError::General(message) => {
+                ParquetError::General(format!("{context}: {message}"))
+            }
+            ParquetError::EOF(message) => ParquetError::EOF(format!("{context}: {message}")),
+            ParquetError::DecryptionKeyError(message) => {
+                ParquetError::DecryptionKeyError(format!("{context}: {message}"))
+            }
+            ParquetError::CiphertextFormatError(message) => {
+                ParquetError::CiphertextFormatError(format!("{context}: {message}"))
+            }
+            ParquetError::EncryptionError(message) => {
+                ParquetError::EncryptionError(format!("{context}: {message}"))
+            }
+            ParquetError::InvalidKeyError(message, source) => {
+                ParquetError::InvalidKeyError(format!("{context}: {message}"), source)
+            }
+            e => e,
+        }
+    }
+}
+
 impl From<TryFromIntError> for ParquetError {
     fn from(e: TryFromIntError) -> ParquetError {
         ParquetError::General(format!("Integer overflow: {e}"))
@@ -140,6 +203,8 @@ impl From<object_store::Error> for ParquetError {
     }
 }
 
+/// Fallback conversion for ring errors. ring errors don't describe what failed,
+/// so where possible they are converted with a label for the failed operation instead.
 #[cfg(feature = "encryption")]
 impl From<ring::error::Unspecified> for ParquetError {
     fn from(e: ring::error::Unspecified) -> ParquetError {
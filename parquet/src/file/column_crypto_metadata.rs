@@ -41,6 +41,16 @@ pub struct EncryptionWithColumnKey {
 }
 );
 
+impl EncryptionWithColumnKey {
+    /// The key metadata interpreted as a UTF-8 key identifier.
+    /// Returns `None` if no metadata is set or it is not valid UTF-8.
+    pub fn key_id(&self) -> Option<&str> {
+        self.key_metadata
+            .as_deref()
+            .and_then(|metadata| std::str::from_utf8(metadata).ok())
+    }
+}
+
 impl HeapSize for EncryptionWithColumnKey {
     fn heap_size(&self) -> usize {
         self.path_in_schema.heap_size() + self.key_metadata.heap_size()
@@ -234,6 +234,82 @@ impl ParquetMetaData {
         self.file_decryptor.as_deref()
     }
 
+    /// Returns the algorithm used to encrypt the file, or `None` if the file is not encrypted.
+    ///
+    /// For files with a plaintext footer that were read without decryption properties,
+    /// the algorithm is read from the footer's file metadata.
+    #[cfg(feature = "encryption")]
+    pub fn encryption_algorithm(&self) -> Option<crate::encryption::encrypt::EncryptionAlgorithm> {
+        match self.file_decryptor.as_deref() {
+            Some(file_decryptor) => Some(file_decryptor.algorithm()),
+            None => self
+                .file_metadata
+                .encryption_algorithm
+                .as_deref()
+                .map(EncryptionAlgorithm::algorithm),
+        }
+    }
+
+    /// Returns the file AAD used to decrypt this file, which is the AAD prefix
+    /// followed by the file unique AAD, or `None` if the file was not decrypted.
+    ///
+    /// This can be used to confirm that the AAD matches the one used when writing.
+    #[cfg(feature = "encryption")]
+    pub fn file_aad(&self) -> Option<&[u8]> {
+        self.file_decryptor
+            .as_deref()
+            .map(|file_decryptor| file_decryptor.file_aad().as_slice())
+    }
+
+    /// Returns the file unique part of the file AAD, or `None` if the file was not decrypted.
+    #[cfg(feature = "encryption")]
+    pub fn aad_file_unique(&self) -> Option<&[u8]> {
+        self.file_decryptor
+            .as_deref()
+            .map(|file_decryptor| file_decryptor.aad_file_unique())
+    }
+
+    /// Returns whether the file has an encrypted footer, rather than a plaintext footer.
+    ///
+    /// Files with an encrypted footer end with the `PARE` magic bytes and can only be read
+    /// with decryption properties. This returns `false` for unencrypted files and files with
+    /// a plaintext footer, see [`Self::encryption_algorithm`] to distinguish these.
+    #[cfg(feature = "encryption")]
+    pub fn encrypt_footer(&self) -> bool {
+        self.file_decryptor
+            .as_deref()
+            .is_some_and(FileDecryptor::encrypted_footer)
+    }
+
+    /// Returns whether the file is encrypted, and if so whether any columns are
+    /// encrypted with column specific keys rather than the footer key.
+    ///
+    /// This is derived from the crypto metadata of the column chunks, so can be used
+    /// to determine which keys are required to read the file.
+    #[cfg(feature = "encryption")]
+    pub fn encryption_mode(&self) -> crate::encryption::decrypt::EncryptionMode {
+        use crate::encryption::decrypt::EncryptionMode;
+
+        if self.encryption_algorithm().is_none() {
+            return EncryptionMode::None;
+        }
+        let uses_column_keys = self
+            .row_groups
+            .iter()
+            .flat_map(|row_group| row_group.columns())
+            .any(|column| {
+                matches!(
+                    column.crypto_metadata(),
+                    Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(_))
+                )
+            });
+        if uses_column_keys {
+            EncryptionMode::PerColumn
+        } else {
+            EncryptionMode::Uniform
+        }
+    }
+
     /// Returns number of row groups in this file.
     pub fn num_row_groups(&self) -> usize {
         self.row_groups.len()
@@ -2142,6 +2218,7 @@ mod tests {
         let decryption_properties = decryption_properties_builder.build().unwrap();
         let decryptor = FileDecryptor::new(
             &decryption_properties,
+            crate::encryption::encrypt::EncryptionAlgorithm::AesGcmV1,
             footer_key_metadata.as_deref(),
             aad_file_unique,
             aad_prefix,
@@ -2153,7 +2230,7 @@ mod tests {
             .set_file_decryptor(Some(decryptor))
             .build();
 
-        let expected_size_with_decryptor = 3056;
+        let expected_size_with_decryptor = 3344;
         assert!(expected_size_with_decryptor > base_expected_size);
 
         assert_eq!(
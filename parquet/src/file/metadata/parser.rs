@@ -106,7 +106,10 @@ mod inner {
         col_index: usize,
     ) -> crate::errors::Result<ColumnIndexMetaData> {
         use crate::encryption::decrypt::CryptoContext;
+        use crate::encryption::modules::ModuleType;
         match &column.column_crypto_metadata {
+            // The column key is not available, so this column can't be read
+            Some(_) if column.encrypted_column_metadata.is_some() => Ok(ColumnIndexMetaData::NONE),
             Some(crypto_metadata) => {
                 let file_decryptor = metadata.file_decryptor.as_ref().ok_or_else(|| {
                     general_err!("Cannot decrypt column index, no file decryptor set")
@@ -114,11 +117,13 @@ mod inner {
                 let crypto_context = CryptoContext::for_column(
                     file_decryptor,
                     crypto_metadata,
+                    column.column_path(),
                     row_group_index,
                     col_index,
                 )?;
                 let column_decryptor = crypto_context.metadata_decryptor();
                 let aad = crypto_context.create_column_index_aad()?;
+                crypto_context.record_key_usage(ModuleType::ColumnIndex);
                 let plaintext = column_decryptor.decrypt(bytes, &aad)?;
                 decode_column_index(&plaintext, column.column_type())
             }
@@ -134,7 +139,13 @@ mod inner {
         col_index: usize,
     ) -> crate::errors::Result<OffsetIndexMetaData> {
         use crate::encryption::decrypt::CryptoContext;
+        use crate::encryption::modules::ModuleType;
         match &column.column_crypto_metadata {
+            // The column key is not available, so this column can't be read
+            Some(_) if column.encrypted_column_metadata.is_some() => Ok(OffsetIndexMetaData {
+                page_locations: vec![],
+                unencoded_byte_array_data_bytes: None,
+            }),
             Some(crypto_metadata) => {
                 let file_decryptor = metadata.file_decryptor.as_ref().ok_or_else(|| {
                     general_err!("Cannot decrypt offset index, no file decryptor set")
@@ -142,11 +153,13 @@ mod inner {
                 let crypto_context = CryptoContext::for_column(
                     file_decryptor,
                     crypto_metadata,
+                    column.column_path(),
                     row_group_index,
                     col_index,
                 )?;
                 let column_decryptor = crypto_context.metadata_decryptor();
                 let aad = crypto_context.create_offset_index_aad()?;
+                crypto_context.record_key_usage(ModuleType::OffsetIndex);
                 let plaintext = column_decryptor.decrypt(bytes, &aad)?;
                 decode_offset_index(&plaintext)
             }
@@ -18,13 +18,13 @@
 #[cfg(feature = "encryption")]
 use crate::encryption::decrypt::FileDecryptionProperties;
 use crate::errors::{ParquetError, Result};
-use crate::file::FOOTER_SIZE;
 use crate::file::metadata::parser::decode_metadata;
 use crate::file::metadata::thrift::parquet_schema_from_bytes;
 use crate::file::metadata::{
     FooterTail, ParquetMetaData, ParquetMetaDataOptions, ParquetMetaDataPushDecoder,
 };
 use crate::file::reader::ChunkReader;
+use crate::file::{FOOTER_SIZE, PARQUET_MAGIC_ENCR_FOOTER};
 use crate::schema::types::SchemaDescriptor;
 use bytes::Bytes;
 use std::sync::Arc;
@@ -286,7 +286,7 @@ impl ParquetMetaDataReader {
     /// let metadata = reader.finish().unwrap();
     /// ```
     pub fn try_parse_sized<R: ChunkReader>(&mut self, reader: &R, file_size: u64) -> Result<()> {
-        self.metadata = match self.parse_metadata(reader) {
+        self.metadata = match self.parse_metadata(reader, file_size) {
             Ok(metadata) => Some(metadata),
             Err(ParquetError::NeedMoreData(needed)) => {
                 // If reader is the same length as `file_size` then presumably there is no more to
@@ -532,7 +532,11 @@ impl ParquetMetaDataReader {
 
     // One-shot parse of footer.
     // Side effect: this will set `self.metadata_size`
-    fn parse_metadata<R: ChunkReader>(&mut self, chunk_reader: &R) -> Result<ParquetMetaData> {
+    fn parse_metadata<R: ChunkReader>(
+        &mut self,
+        chunk_reader: &R,
+        total_file_size: u64,
+    ) -> Result<ParquetMetaData> {
         // check file is large enough to hold footer
         let file_size = chunk_reader.len();
         if file_size < (FOOTER_SIZE as u64) {
@@ -549,6 +553,18 @@ impl ParquetMetaDataReader {
         let footer_metadata_len = FOOTER_SIZE + metadata_len;
         self.metadata_size = Some(footer_metadata_len);
 
+        // Reject an implausible encrypted footer length up front, rather than asking for
+        // more data than the file holds. The footer must fit after the leading magic.
+        if footer.is_encrypted_footer()
+            && (footer_metadata_len + PARQUET_MAGIC_ENCR_FOOTER.len()) as u64 > total_file_size
+        {
+            return Err(general_err!(
+                "Encrypted footer length {} is too large for a file of {} bytes",
+                metadata_len,
+                total_file_size
+            ));
+        }
+
         if footer_metadata_len as u64 > file_size {
             return Err(ParquetError::NeedMoreData(footer_metadata_len));
         }
@@ -851,7 +867,7 @@ mod tests {
     fn test_parse_metadata_size_smaller_than_footer() {
         let test_file = tempfile::tempfile().unwrap();
         let err = ParquetMetaDataReader::new()
-            .parse_metadata(&test_file)
+            .parse_metadata(&test_file, test_file.len())
             .unwrap_err();
         assert!(matches!(err, ParquetError::NeedMoreData(FOOTER_SIZE)));
     }
@@ -859,7 +875,7 @@ mod tests {
     #[test]
     fn test_parse_metadata_corrupt_footer() {
         let data = Bytes::from(vec![1, 2, 3, 4, 5, 6, 7, 8]);
-        let reader_result = ParquetMetaDataReader::new().parse_metadata(&data);
+        let reader_result = ParquetMetaDataReader::new().parse_metadata(&data, data.len() as u64);
         assert_eq!(
             reader_result.unwrap_err().to_string(),
             "Parquet error: Invalid Parquet file. Corrupt footer"
@@ -870,7 +886,7 @@ mod tests {
     fn test_parse_metadata_invalid_start() {
         let test_file = Bytes::from(vec![255, 0, 0, 0, b'P', b'A', b'R', b'1']);
         let err = ParquetMetaDataReader::new()
-            .parse_metadata(&test_file)
+            .parse_metadata(&test_file, test_file.len() as u64)
             .unwrap_err();
         assert!(matches!(err, ParquetError::NeedMoreData(263)));
     }
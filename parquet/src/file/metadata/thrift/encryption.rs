@@ -18,7 +18,10 @@
 //! Encryption support for Thrift serialization
 
 use crate::{
-    encryption::decrypt::{FileDecryptionProperties, FileDecryptor},
+    encryption::{
+        decrypt::{FileDecryptionProperties, FileDecryptor},
+        encrypt,
+    },
     errors::{ParquetError, Result},
     file::{
         column_crypto_metadata::ColumnCryptoMetaData,
@@ -31,6 +34,7 @@ use crate::{
         ElementType, FieldType, ReadThrift, ThriftCompactInputProtocol,
         ThriftCompactOutputProtocol, ThriftSliceInputProtocol, WriteThrift, WriteThriftField,
     },
+    schema::types::ColumnPath,
     thrift_struct, thrift_union,
 };
 use std::io::Write;
@@ -87,6 +91,16 @@ union EncryptionAlgorithm {
 }
 );
 
+impl EncryptionAlgorithm {
+    /// The algorithm used to encrypt the file
+    pub(crate) fn algorithm(&self) -> encrypt::EncryptionAlgorithm {
+        match self {
+            Self::AES_GCM_V1(_) => encrypt::EncryptionAlgorithm::AesGcmV1,
+            Self::AES_GCM_CTR_V1(_) => encrypt::EncryptionAlgorithm::AesGcmCtrV1,
+        }
+    }
+}
+
 impl HeapSize for EncryptionAlgorithm {
     fn heap_size(&self) -> usize {
         match self {
@@ -136,6 +150,10 @@ fn row_group_from_encrypted_thrift(
     {
         // Read encrypted metadata if it's present and we have a decryptor.
         if let (true, Some(decryptor)) = (c.encrypted_column_metadata.is_some(), decryptor) {
+            if decryptor.footer_only() {
+                columns.push(c);
+                continue;
+            }
             let column_decryptor = match c.crypto_metadata() {
                 None => {
                     return Err(general_err!(
@@ -144,10 +162,10 @@ fn row_group_from_encrypted_thrift(
                     ));
                 }
                 Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(crypto_metadata)) => {
-                    let column_name = crypto_metadata.path_in_schema.join(".");
+                    let key_path = ColumnPath::new(crypto_metadata.path_in_schema.clone());
                     // Try to get the decryptor - if it fails, we don't have the key
                     match decryptor.get_column_metadata_decryptor(
-                        column_name.as_str(),
+                        &key_path,
                         crypto_metadata.key_metadata.as_deref(),
                     ) {
                         Ok(dec) => dec,
@@ -174,13 +192,17 @@ fn row_group_from_encrypted_thrift(
             // Take the encrypted column metadata as it is no longer needed.
             let encrypted_column_metadata = c.encrypted_column_metadata.take();
             let buf = encrypted_column_metadata.unwrap();
+            decryptor.record_key_usage(
+                Some(&d.path().string()),
+                crate::encryption::modules::ModuleType::ColumnMetaData,
+            );
             let decrypted_cc_buf = column_decryptor
                 .decrypt(&buf, column_aad.as_ref())
-                .map_err(|_| {
-                    general_err!(
-                        "Unable to decrypt column '{}', perhaps the column key is wrong?",
+                .map_err(|e| {
+                    e.with_context(format_args!(
+                        "Unable to decrypt column '{}'",
                         d.path().string()
-                    )
+                    ))
                 })?;
 
             // parse decrypted buffer and then replace fields in 'c'
@@ -236,30 +258,21 @@ pub(crate) fn parquet_metadata_with_encryption(
             let t_file_crypto_metadata: FileCryptoMetaData =
                 FileCryptoMetaData::read_thrift(&mut prot)
                     .map_err(|e| general_err!("Could not parse crypto metadata: {}", e))?;
-            let supply_aad_prefix = match &t_file_crypto_metadata.encryption_algorithm {
-                EncryptionAlgorithm::AES_GCM_V1(algo) => algo.supply_aad_prefix,
-                _ => Some(false),
-            }
-            .unwrap_or(false);
-            if supply_aad_prefix && file_decryption_properties.aad_prefix().is_none() {
-                return Err(general_err!(
-                    "Parquet file was encrypted with an AAD prefix that is not stored in the file, \
-                        but no AAD prefix was provided in the file decryption properties"
-                ));
-            }
             let decryptor = get_file_decryptor(
                 t_file_crypto_metadata.encryption_algorithm,
                 t_file_crypto_metadata.key_metadata,
                 file_decryption_properties,
-            )?;
+            )?
+            .with_encrypted_footer(true);
             let footer_decryptor = decryptor.get_footer_decryptor();
             let aad_footer = crate::encryption::modules::create_footer_aad(decryptor.file_aad())?;
+            decryptor.record_key_usage(None, crate::encryption::modules::ModuleType::Footer);
 
             decrypted_fmd_buf = footer_decryptor?
                 .decrypt(prot.as_slice().as_ref(), aad_footer.as_ref())
-                .map_err(|_| {
-                    general_err!(
-                        "Provided footer key and AAD were unable to decrypt parquet footer"
+                .map_err(|e| {
+                    e.with_context(
+                        "Provided footer key and AAD were unable to decrypt parquet footer",
                     )
                 })?;
 
@@ -298,6 +311,8 @@ pub(crate) fn parquet_metadata_with_encryption(
             file_decryption_properties,
         )?;
         if file_decryption_properties.check_plaintext_footer_integrity() && !encrypted_footer {
+            file_decryptor_value
+                .record_key_usage(None, crate::encryption::modules::ModuleType::Footer);
             file_decryptor_value.verify_plaintext_footer_signature(buf)?;
         }
         file_decryptor = Some(file_decryptor_value);
@@ -322,27 +337,164 @@ fn get_file_decryptor(
     footer_key_metadata: Option<&[u8]>,
     file_decryption_properties: &Arc<FileDecryptionProperties>,
 ) -> Result<FileDecryptor> {
-    match encryption_algorithm {
-        EncryptionAlgorithm::AES_GCM_V1(algo) => {
-            let aad_file_unique = algo
-                .aad_file_unique
-                .ok_or_else(|| general_err!("AAD unique file identifier is not set"))?;
-            let aad_prefix = if let Some(aad_prefix) = file_decryption_properties.aad_prefix() {
-                aad_prefix.clone()
-            } else {
-                algo.aad_prefix.map(|v| v.to_vec()).unwrap_or_default()
+    let (algorithm, aad_prefix, aad_file_unique, supply_aad_prefix) = match encryption_algorithm {
+        EncryptionAlgorithm::AES_GCM_V1(algo) => (
+            encrypt::EncryptionAlgorithm::AesGcmV1,
+            algo.aad_prefix,
+            algo.aad_file_unique,
+            algo.supply_aad_prefix,
+        ),
+        EncryptionAlgorithm::AES_GCM_CTR_V1(algo) => (
+            encrypt::EncryptionAlgorithm::AesGcmCtrV1,
+            algo.aad_prefix,
+            algo.aad_file_unique,
+            algo.supply_aad_prefix,
+        ),
+    };
+    // Check this up front, as decryption would otherwise fail with a generic error
+    if supply_aad_prefix.unwrap_or(false) && file_decryption_properties.aad_prefix().is_none() {
+        return Err(general_err!(
+            "This file requires an externally-supplied AAD prefix. It was encrypted with an \
+                AAD prefix that is not stored in the file, but no AAD prefix was provided in \
+                the file decryption properties"
+        ));
+    }
+    let aad_file_unique =
+        aad_file_unique.ok_or_else(|| general_err!("AAD unique file identifier is not set"))?;
+    let aad_prefix = if let Some(aad_prefix) = file_decryption_properties.aad_prefix() {
+        aad_prefix.clone()
+    } else {
+        aad_prefix.map(|v| v.to_vec()).unwrap_or_default()
+    };
+    let aad_file_unique = aad_file_unique.to_vec();
+
+    FileDecryptor::new(
+        file_decryption_properties,
+        algorithm,
+        footer_key_metadata,
+        aad_file_unique,
+        aad_prefix,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_type::Int32Type;
+    use crate::encryption::encrypt::FileEncryptionProperties;
+    use crate::file::properties::WriterProperties;
+    use crate::file::writer::SerializedFileWriter;
+    use crate::schema::parser::parse_message_type;
+
+    fn write_encrypted_file(file_encryption_properties: Arc<FileEncryptionProperties>) -> Vec<u8> {
+        let schema = Arc::new(parse_message_type("message m { required int32 a; }").unwrap());
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(file_encryption_properties)
+            .build();
+
+        let mut buf = Vec::new();
+        let mut writer = SerializedFileWriter::new(&mut buf, schema, Arc::new(props)).unwrap();
+        let mut row_group_writer = writer.next_row_group().unwrap();
+        let mut col_writer = row_group_writer.next_column().unwrap().unwrap();
+        col_writer
+            .typed::<Int32Type>()
+            .write_batch(&[1, 2, 3], None, None)
+            .unwrap();
+        col_writer.close().unwrap();
+        row_group_writer.close().unwrap();
+        writer.close().unwrap();
+        buf
+    }
+
+    fn read_file_crypto_metadata(buf: &[u8]) -> FileCryptoMetaData<'_> {
+        // An encrypted footer starts with the file crypto metadata
+        assert_eq!(&buf[buf.len() - 4..], b"PARE");
+        let footer_len =
+            u32::from_le_bytes(buf[buf.len() - 8..buf.len() - 4].try_into().unwrap()) as usize;
+        let footer = &buf[buf.len() - 8 - footer_len..buf.len() - 8];
+        let mut prot = ThriftSliceInputProtocol::new(footer);
+        FileCryptoMetaData::read_thrift(&mut prot).unwrap()
+    }
+
+    #[test]
+    fn test_ctr_algorithm_written_to_crypto_metadata() {
+        let file_encryption_properties =
+            FileEncryptionProperties::builder(b"0123456789012345".to_vec())
+                .with_uniform_encryption()
+                .with_algorithm(encrypt::EncryptionAlgorithm::AesGcmCtrV1)
+                .build()
+                .unwrap();
+        let buf = write_encrypted_file(file_encryption_properties);
+        let crypto_metadata = read_file_crypto_metadata(&buf);
+
+        match crypto_metadata.encryption_algorithm {
+            EncryptionAlgorithm::AES_GCM_CTR_V1(algo) => {
+                assert!(algo.aad_file_unique.is_some());
+                assert!(algo.aad_prefix.is_none());
+            }
+            algo => panic!("Unexpected encryption algorithm: {algo:?}"),
+        }
+    }
+
+    #[test]
+    fn test_aad_prefix_written_to_crypto_metadata() {
+        use crate::encryption::decrypt::FileDecryptionProperties;
+        use crate::file::metadata::ParquetMetaDataReader;
+
+        let footer_key = b"0123456789012345".to_vec();
+        let aad_prefix = b"file_prefix".to_vec();
+        for store_aad_prefix in [true, false] {
+            let file_encryption_properties = FileEncryptionProperties::builder(footer_key.clone())
+                .with_uniform_encryption()
+                .with_aad_prefix(aad_prefix.clone())
+                .with_aad_prefix_storage(store_aad_prefix)
+                .build()
+                .unwrap();
+            let buf = write_encrypted_file(file_encryption_properties);
+
+            match read_file_crypto_metadata(&buf).encryption_algorithm {
+                EncryptionAlgorithm::AES_GCM_V1(algo) => {
+                    assert_eq!(algo.supply_aad_prefix, Some(!store_aad_prefix));
+                    assert_eq!(
+                        algo.aad_prefix,
+                        store_aad_prefix.then(|| aad_prefix.clone())
+                    );
+                }
+                algo => panic!("Unexpected encryption algorithm: {algo:?}"),
+            }
+
+            let buf = bytes::Bytes::from(buf);
+            let read_metadata = |decryption_properties| {
+                ParquetMetaDataReader::new()
+                    .with_decryption_properties(Some(decryption_properties))
+                    .parse_and_finish(&buf)
             };
-            let aad_file_unique = aad_file_unique.to_vec();
 
-            FileDecryptor::new(
-                file_decryption_properties,
-                footer_key_metadata,
-                aad_file_unique,
-                aad_prefix,
+            // A stored prefix is used when the reader doesn't provide one
+            let result = read_metadata(
+                FileDecryptionProperties::builder(footer_key.clone())
+                    .build()
+                    .unwrap(),
+            );
+            if store_aad_prefix {
+                assert_eq!(result.unwrap().file_metadata().num_rows(), 3);
+            } else {
+                assert_eq!(
+                    result.unwrap_err().to_string(),
+                    "Parquet error: This file requires an externally-supplied AAD prefix. It was \
+                        encrypted with an AAD prefix that is not stored in the file, but no AAD \
+                        prefix was provided in the file decryption properties"
+                );
+            }
+
+            let metadata = read_metadata(
+                FileDecryptionProperties::builder(footer_key.clone())
+                    .with_aad_prefix(aad_prefix.clone())
+                    .build()
+                    .unwrap(),
             )
+            .unwrap();
+            assert_eq!(metadata.file_metadata().num_rows(), 3);
         }
-        EncryptionAlgorithm::AES_GCM_CTR_V1(_) => Err(nyi_err!(
-            "The AES_GCM_CTR_V1 encryption algorithm is not yet supported"
-        )),
     }
 }
@@ -27,11 +27,14 @@ use crate::{
 #[cfg(feature = "encryption")]
 use crate::{
     encryption::{
-        encrypt::{FileEncryptor, encrypt_thrift_object, write_signed_plaintext_thrift_object},
-        modules::{ModuleType, create_footer_aad, create_module_aad},
+        encrypt::{self, FileEncryptor, write_signed_plaintext_thrift_object},
+        modules::{ModuleType, create_module_aad},
     },
     file::column_crypto_metadata::ColumnCryptoMetaData,
-    file::metadata::thrift::encryption::{AesGcmV1, EncryptionAlgorithm, FileCryptoMetaData},
+    file::metadata::thrift::encryption::{
+        AesGcmCtrV1, AesGcmV1, EncryptionAlgorithm, FileCryptoMetaData,
+    },
+    schema::types::ColumnPath,
 };
 use crate::{errors::Result, file::page_index::column_index::ColumnIndexMetaData};
 
@@ -47,6 +50,7 @@ use crate::{
     parquet_thrift::ThriftCompactOutputProtocol,
 };
 use std::io::Write;
+use std::ops::Range;
 use std::sync::Arc;
 
 /// Writes `crate::file::metadata` structures to a thrift encoded byte stream
@@ -197,7 +201,13 @@ impl<'a, W: Write> ThriftMetadataWriter<'a, W> {
     }
 
     /// Assembles and writes the final metadata to self.buf
-    pub fn finish(mut self) -> Result<ParquetMetaData> {
+    pub fn finish(self) -> Result<ParquetMetaData> {
+        Ok(self.finish_with_footer_range()?.0)
+    }
+
+    /// Assembles and writes the final metadata to self.buf, also returning the
+    /// byte range of the serialized file metadata within the file
+    pub fn finish_with_footer_range(mut self) -> Result<(ParquetMetaData, Range<u64>)> {
         let num_rows = self.row_groups.iter().map(|x| x.num_rows).sum();
 
         // serialize page indexes and transform to the proper form for use in ParquetMetaData
@@ -283,10 +293,11 @@ impl<'a, W: Write> ThriftMetadataWriter<'a, W> {
             .set_column_index(column_indexes)
             .set_offset_index(offset_indexes);
 
-        Ok(match unencrypted_row_groups {
+        let metadata = match unencrypted_row_groups {
             Some(rg) => builder.set_row_groups(rg).build(),
             None => builder.set_row_groups(row_groups).build(),
-        })
+        };
+        Ok((metadata, start_pos as u64..end_pos as u64))
     }
 
     pub fn new(
@@ -630,12 +641,10 @@ impl MetadataObjectWriter {
                 crypto_metadata.write_thrift(&mut protocol)?;
 
                 // Then write encrypted footer
-                let aad = create_footer_aad(file_encryptor.file_aad())?;
-                let mut encryptor = file_encryptor.get_footer_encryptor()?;
-                encrypt_thrift_object(file_metadata, &mut encryptor, &mut sink, &aad)
+                file_encryptor.encrypt_footer(file_metadata, &mut sink)
             }
             Some(file_encryptor) if file_metadata.file_metadata.encryption_algorithm.is_some() => {
-                let aad = create_footer_aad(file_encryptor.file_aad())?;
+                let aad = file_encryptor.footer_aad()?;
                 let mut encryptor = file_encryptor.get_footer_encryptor()?;
                 write_signed_plaintext_thrift_object(file_metadata, &mut encryptor, &mut sink, &aad)
             }
@@ -738,15 +747,7 @@ impl MetadataObjectWriter {
         row_group_index: usize,
         column_index: usize,
     ) -> Result<()> {
-        let column_path_vec = column_metadata.column_path().as_ref();
-
-        let joined_column_path;
-        let column_path = if column_path_vec.len() == 1 {
-            &column_path_vec[0]
-        } else {
-            joined_column_path = column_path_vec.join(".");
-            &joined_column_path
-        };
+        let column_path = column_metadata.column_path();
 
         if file_encryptor.is_column_encrypted(column_path) {
             use crate::encryption::encrypt::encrypt_thrift_object;
@@ -791,11 +792,22 @@ impl MetadataObjectWriter {
         } else {
             None
         };
-        EncryptionAlgorithm::AES_GCM_V1(AesGcmV1 {
-            aad_prefix: aad_prefix.cloned(),
-            aad_file_unique: Some(file_encryptor.aad_file_unique().clone()),
-            supply_aad_prefix,
-        })
+        let aad_prefix = aad_prefix.cloned();
+        let aad_file_unique = Some(file_encryptor.aad_file_unique().clone());
+        match file_encryptor.properties().algorithm() {
+            encrypt::EncryptionAlgorithm::AesGcmV1 => EncryptionAlgorithm::AES_GCM_V1(AesGcmV1 {
+                aad_prefix,
+                aad_file_unique,
+                supply_aad_prefix,
+            }),
+            encrypt::EncryptionAlgorithm::AesGcmCtrV1 => {
+                EncryptionAlgorithm::AES_GCM_CTR_V1(AesGcmCtrV1 {
+                    aad_prefix,
+                    aad_file_unique,
+                    supply_aad_prefix,
+                })
+            }
+        }
     }
 
     fn file_crypto_metadata(file_encryptor: &'_ FileEncryptor) -> Result<FileCryptoMetaData<'_>> {
@@ -853,7 +865,7 @@ impl MetadataObjectWriter {
                 }
             }
             Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(col_key)) => {
-                let column_path = col_key.path_in_schema.join(".");
+                let column_path = ColumnPath::new(col_key.path_in_schema.clone());
                 Some(file_encryptor.get_column_encryptor(&column_path)?)
             }
         };
@@ -258,6 +258,8 @@ pub struct WriterProperties {
     write_path_in_schema: bool,
     #[cfg(feature = "encryption")]
     pub(crate) file_encryption_properties: Option<Arc<FileEncryptionProperties>>,
+    #[cfg(feature = "encryption")]
+    encrypted_data_page_min_size: Option<usize>,
 }
 
 impl Default for WriterProperties {
@@ -581,6 +583,15 @@ impl WriterProperties {
     pub fn file_encryption_properties(&self) -> Option<&Arc<FileEncryptionProperties>> {
         self.file_encryption_properties.as_ref()
     }
+
+    /// Returns the minimum estimated size of a data page of an encrypted column,
+    /// if any, before the row count limit is allowed to end the page
+    ///
+    /// For more details see [`WriterPropertiesBuilder::set_encrypted_data_page_min_size`]
+    #[cfg(feature = "encryption")]
+    pub fn encrypted_data_page_min_size(&self) -> Option<usize> {
+        self.encrypted_data_page_min_size
+    }
 }
 
 /// Builder for  [`WriterProperties`] Parquet writer configuration.
@@ -607,6 +618,8 @@ pub struct WriterPropertiesBuilder {
     write_path_in_schema: bool,
     #[cfg(feature = "encryption")]
     file_encryption_properties: Option<Arc<FileEncryptionProperties>>,
+    #[cfg(feature = "encryption")]
+    encrypted_data_page_min_size: Option<usize>,
 }
 
 impl Default for WriterPropertiesBuilder {
@@ -632,6 +645,8 @@ impl Default for WriterPropertiesBuilder {
             write_path_in_schema: DEFAULT_WRITE_PATH_IN_SCHEMA,
             #[cfg(feature = "encryption")]
             file_encryption_properties: None,
+            #[cfg(feature = "encryption")]
+            encrypted_data_page_min_size: None,
         }
     }
 }
@@ -687,6 +702,8 @@ impl WriterPropertiesBuilder {
             write_path_in_schema: self.write_path_in_schema,
             #[cfg(feature = "encryption")]
             file_encryption_properties: self.file_encryption_properties,
+            #[cfg(feature = "encryption")]
+            encrypted_data_page_min_size: self.encrypted_data_page_min_size,
         }
     }
 
@@ -1007,6 +1024,26 @@ impl WriterPropertiesBuilder {
         self
     }
 
+    /// Sets the minimum estimated size in bytes of a data page of an encrypted
+    /// column (defaults to `None`).
+    ///
+    /// Every encrypted data page carries a fixed overhead of a page header module
+    /// and a page module (see [`EncryptionAlgorithm::overhead_per_page`]), which
+    /// dominates the file size when pages are small, for example with sparse columns.
+    /// When set, the page row count limit set by
+    /// [`set_data_page_row_count_limit`](Self::set_data_page_row_count_limit) does not
+    /// end a data page of an encrypted column until the page has reached this size,
+    /// coalescing small pages together. The data page size limit is still applied.
+    ///
+    /// Columns that are not encrypted are unaffected.
+    ///
+    /// [`EncryptionAlgorithm::overhead_per_page`]: crate::encryption::encrypt::EncryptionAlgorithm::overhead_per_page
+    #[cfg(feature = "encryption")]
+    pub fn set_encrypted_data_page_min_size(mut self, value: Option<usize>) -> Self {
+        self.encrypted_data_page_min_size = value;
+        self
+    }
+
     // ----------------------------------------------------------------------
     // Setters for any column (global)
 
@@ -1350,6 +1387,8 @@ impl From<WriterProperties> for WriterPropertiesBuilder {
             write_path_in_schema: props.write_path_in_schema,
             #[cfg(feature = "encryption")]
             file_encryption_properties: props.file_encryption_properties,
+            #[cfg(feature = "encryption")]
+            encrypted_data_page_min_size: props.encrypted_data_page_min_size,
         }
     }
 }
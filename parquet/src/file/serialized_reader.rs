@@ -23,7 +23,7 @@ use crate::bloom_filter::Sbbf;
 use crate::column::page::{Page, PageMetadata, PageReader};
 use crate::compression::{Codec, create_codec};
 #[cfg(feature = "encryption")]
-use crate::encryption::decrypt::{CryptoContext, read_and_decrypt};
+use crate::encryption::decrypt::{CryptoContext, FileDecryptor, read_and_decrypt_into};
 use crate::errors::{ParquetError, Result};
 use crate::file::metadata::thrift::PageHeader;
 use crate::file::page_index::offset_index::{OffsetIndexMetaData, PageLocation};
@@ -305,12 +305,16 @@ impl<R: 'static + ChunkReader> FileReader for SerializedFileReader<R> {
         // Row groups should be processed sequentially.
         let props = Arc::clone(&self.props);
         let f = Arc::clone(&self.chunk_reader);
-        Ok(Box::new(SerializedRowGroupReader::new(
+        let row_group_reader = SerializedRowGroupReader::new(
             f,
             row_group_metadata,
             self.metadata.offset_index().map(|x| x[i].as_slice()),
             props,
-        )?))
+        )?;
+        #[cfg(feature = "encryption")]
+        let row_group_reader =
+            row_group_reader.with_encrypted_bloom_filters(self.metadata.file_decryptor(), i)?;
+        Ok(Box::new(row_group_reader))
     }
 
     fn get_row_iter(&self, projection: Option<SchemaType>) -> Result<RowIter<'_>> {
@@ -339,7 +343,14 @@ impl<'a, R: ChunkReader> SerializedRowGroupReader<'a, R> {
             metadata
                 .columns()
                 .iter()
-                .map(|col| Sbbf::read_from_column_chunk(col, &*chunk_reader))
+                .map(|col| {
+                    // Bloom filters of encrypted columns are read separately with decryption
+                    #[cfg(feature = "encryption")]
+                    if col.crypto_metadata().is_some() {
+                        return Ok(None);
+                    }
+                    Sbbf::read_from_column_chunk(col, &*chunk_reader)
+                })
                 .collect::<Result<Vec<_>>>()?
         } else {
             std::iter::repeat_n(None, metadata.columns().len()).collect()
@@ -352,6 +363,37 @@ impl<'a, R: ChunkReader> SerializedRowGroupReader<'a, R> {
             bloom_filters,
         })
     }
+
+    /// Read and decrypt the bloom filters of encrypted columns, if bloom filters are
+    /// to be read and a file decryptor is available
+    #[cfg(feature = "encryption")]
+    pub(crate) fn with_encrypted_bloom_filters(
+        mut self,
+        file_decryptor: Option<&FileDecryptor>,
+        row_group_idx: usize,
+    ) -> Result<Self> {
+        let Some(file_decryptor) = file_decryptor else {
+            return Ok(self);
+        };
+        if !self.props.read_bloom_filter() {
+            return Ok(self);
+        }
+        for (column_idx, column) in self.metadata.columns().iter().enumerate() {
+            if let Some(crypto_context) = CryptoContext::for_column_chunk(
+                Some(file_decryptor),
+                column,
+                row_group_idx,
+                column_idx,
+            )? {
+                self.bloom_filters[column_idx] = Sbbf::read_encrypted_from_column_chunk(
+                    column,
+                    &*self.chunk_reader,
+                    &crypto_context,
+                )?;
+            }
+        }
+        Ok(self)
+    }
 }
 
 impl<R: 'static + ChunkReader> RowGroupReader for SerializedRowGroupReader<'_, R> {
@@ -559,6 +601,9 @@ struct SerializedPageReaderContext {
     /// Crypto context carrying objects required for decryption
     #[cfg(feature = "encryption")]
     crypto_context: Option<Arc<CryptoContext>>,
+    /// Buffers reused across pages when reading and decrypting page headers
+    #[cfg(feature = "encryption")]
+    page_header_buffers: (Vec<u8>, Vec<u8>),
 }
 
 /// A serialized implementation for Parquet [`PageReader`].
@@ -616,15 +661,13 @@ impl<R: ChunkReader> SerializedPageReader<R> {
         parquet_meta_data: &ParquetMetaData,
         column_chunk_metadata: &ColumnChunkMetaData,
     ) -> Result<SerializedPageReader<R>> {
-        let Some(file_decryptor) = parquet_meta_data.file_decryptor() else {
-            return Ok(self);
-        };
-        let Some(crypto_metadata) = column_chunk_metadata.crypto_metadata() else {
-            return Ok(self);
-        };
-        let crypto_context =
-            CryptoContext::for_column(file_decryptor, crypto_metadata, rg_idx, column_idx)?;
-        self.context.crypto_context = Some(Arc::new(crypto_context));
+        let crypto_context = CryptoContext::for_column_chunk(
+            parquet_meta_data.file_decryptor(),
+            column_chunk_metadata,
+            rg_idx,
+            column_idx,
+        )?;
+        self.context.crypto_context = crypto_context.map(Arc::new);
         Ok(self)
     }
 
@@ -710,7 +753,7 @@ impl<R: ChunkReader> SerializedPageReader<R> {
                     } else {
                         let mut read = self.reader.get_read(*offset)?;
                         let (header_len, header) = Self::read_page_header_len(
-                            &self.context,
+                            &mut self.context,
                             &mut read,
                             *page_index,
                             *require_dictionary,
@@ -745,7 +788,7 @@ impl<R: ChunkReader> SerializedPageReader<R> {
     }
 
     fn read_page_header_len<T: Read>(
-        context: &SerializedPageReaderContext,
+        context: &mut SerializedPageReaderContext,
         input: &mut T,
         page_index: usize,
         dictionary_page: bool,
@@ -773,7 +816,7 @@ impl<R: ChunkReader> SerializedPageReader<R> {
     }
 
     fn read_page_header_len_from_bytes(
-        context: &SerializedPageReaderContext,
+        context: &mut SerializedPageReaderContext,
         buffer: &[u8],
         page_index: usize,
         dictionary_page: bool,
@@ -788,7 +831,7 @@ impl<R: ChunkReader> SerializedPageReader<R> {
 #[cfg(not(feature = "encryption"))]
 impl SerializedPageReaderContext {
     fn read_page_header<T: Read>(
-        &self,
+        &mut self,
         input: &mut T,
         _page_index: usize,
         _dictionary_page: bool,
@@ -814,7 +857,7 @@ impl SerializedPageReaderContext {
 #[cfg(feature = "encryption")]
 impl SerializedPageReaderContext {
     fn read_page_header<T: Read>(
-        &self,
+        &mut self,
         input: &mut T,
         page_index: usize,
         dictionary_page: bool,
@@ -831,17 +874,26 @@ impl SerializedPageReaderContext {
                 }
             }
             Some(page_crypto_context) => {
-                let data_decryptor = page_crypto_context.data_decryptor();
+                let metadata_decryptor = page_crypto_context.metadata_decryptor();
                 let aad = page_crypto_context.create_page_header_aad()?;
-
-                let buf = read_and_decrypt(data_decryptor, input, aad.as_ref()).map_err(|_| {
-                    ParquetError::General(format!(
-                        "Error decrypting page header for column {}, decryption key may be wrong",
-                        page_crypto_context.column_ordinal
+                page_crypto_context.record_key_usage(page_crypto_context.page_header_module_type());
+
+                let (ciphertext, plaintext) = &mut self.page_header_buffers;
+                read_and_decrypt_into(
+                    metadata_decryptor,
+                    input,
+                    aad.as_ref(),
+                    ciphertext,
+                    plaintext,
+                )
+                .map_err(|e| {
+                    e.with_context(format_args!(
+                        "Error decrypting page header for {}",
+                        page_crypto_context.location()
                     ))
                 })?;
 
-                let mut prot = ThriftSliceInputProtocol::new(buf.as_slice());
+                let mut prot = ThriftSliceInputProtocol::new(plaintext.as_slice());
                 if self.read_stats {
                     Ok(PageHeader::read_thrift(&mut prot)?)
                 } else {
@@ -860,7 +912,14 @@ impl SerializedPageReaderContext {
         if let Some(page_crypto_context) = page_crypto_context {
             let decryptor = page_crypto_context.data_decryptor();
             let aad = page_crypto_context.create_page_aad()?;
-            let decrypted = decryptor.decrypt(buffer.as_ref(), &aad)?;
+            page_crypto_context.record_key_usage(page_crypto_context.page_module_type());
+            let decrypted = decryptor.decrypt(buffer.as_ref(), &aad).map_err(|e| {
+                e.with_context(format_args!(
+                    "Error decrypting page for {}",
+                    page_crypto_context.location()
+                ))
+            })?;
+            page_crypto_context.record_page_decrypted(decrypted.len());
             Ok(T::from(decrypted))
         } else {
             Ok(buffer)
@@ -931,7 +990,7 @@ impl<R: ChunkReader> PageReader for SerializedPageReader<R> {
                         *header
                     } else {
                         let (header_len, header) = Self::read_page_header_len(
-                            &self.context,
+                            &mut self.context,
                             &mut read,
                             *page_index,
                             *require_dictionary,
@@ -992,7 +1051,7 @@ impl<R: ChunkReader> PageReader for SerializedPageReader<R> {
                     let buffer = self.reader.get_bytes(front.offset as u64, page_len)?;
 
                     let (offset, header) = Self::read_page_header_len_from_bytes(
-                        &self.context,
+                        &mut self.context,
                         buffer.as_ref(),
                         *page_index,
                         is_dictionary_page,
@@ -1042,7 +1101,7 @@ impl<R: ChunkReader> PageReader for SerializedPageReader<R> {
                     } else {
                         let mut read = self.reader.get_read(*offset)?;
                         let (header_len, header) = Self::read_page_header_len(
-                            &self.context,
+                            &mut self.context,
                             &mut read,
                             *page_index,
                             *require_dictionary,
@@ -1112,7 +1171,7 @@ impl<R: ChunkReader> PageReader for SerializedPageReader<R> {
                 } else {
                     let mut read = self.reader.get_read(*offset)?;
                     let (header_len, header) = Self::read_page_header_len(
-                        &self.context,
+                        &mut self.context,
                         &mut read,
                         *page_index,
                         *require_dictionary,
@@ -1191,6 +1250,79 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    #[cfg(feature = "encryption")]
+    fn test_page_decryption_errors_keep_variant() {
+        use crate::encryption::decrypt::FileDecryptionProperties;
+        use crate::encryption::encrypt::FileEncryptionProperties;
+        use crate::file::metadata::ParquetMetaDataReader;
+
+        let footer_key = b"0123456789012345".to_vec();
+        let schema = Arc::new(parse_message_type("message schema { REQUIRED INT32 x; }").unwrap());
+        let props = WriterProperties::builder()
+            .set_dictionary_enabled(false)
+            .with_file_encryption_properties(
+                FileEncryptionProperties::builder(footer_key.clone())
+                    .with_uniform_encryption()
+                    .build()
+                    .unwrap(),
+            )
+            .build();
+        let mut file = Vec::new();
+        let mut writer = SerializedFileWriter::new(&mut file, schema, Arc::new(props)).unwrap();
+        let mut row_group_writer = writer.next_row_group().unwrap();
+        let mut column_writer = row_group_writer.next_column().unwrap().unwrap();
+        column_writer
+            .typed::<Int32Type>()
+            .write_batch(&[1, 2, 3], None, None)
+            .unwrap();
+        column_writer.close().unwrap();
+        row_group_writer.close().unwrap();
+        writer.close().unwrap();
+
+        let decryption_properties = FileDecryptionProperties::builder(footer_key)
+            .build()
+            .unwrap();
+        let metadata = ParquetMetaDataReader::new()
+            .with_decryption_properties(Some(decryption_properties))
+            .parse_and_finish(&Bytes::from(file.clone()))
+            .unwrap();
+        let column = metadata.row_group(0).column(0);
+        let header_offset = column.data_page_offset() as usize;
+        let header_len =
+            u32::from_le_bytes(file[header_offset..header_offset + 4].try_into().unwrap());
+        let page_offset = header_offset + 4 + header_len as usize;
+
+        let read_corrupted = |corrupt_offset: usize| {
+            let mut corrupted = file.clone();
+            corrupted[corrupt_offset] ^= 1;
+            SerializedPageReader::new(Arc::new(Bytes::from(corrupted)), column, 3, None)
+                .unwrap()
+                .add_crypto_context(0, 0, &metadata, column)
+                .unwrap()
+                .get_next_page()
+                .unwrap_err()
+        };
+
+        // Corrupt the ciphertext after the length prefix and nonce
+        match read_corrupted(header_offset + 20) {
+            ParquetError::DecryptionKeyError(message) => assert!(
+                message.starts_with(
+                    "Error decrypting page header for column 'x' (row group 0, page 0): "
+                ),
+                "{message}"
+            ),
+            e => panic!("unexpected error: {e:?}"),
+        }
+        match read_corrupted(page_offset + 20) {
+            ParquetError::DecryptionKeyError(message) => assert!(
+                message.starts_with("Error decrypting page for column 'x' (row group 0, page 0): "),
+                "{message}"
+            ),
+            e => panic!("unexpected error: {e:?}"),
+        }
+    }
+
     #[test]
     fn test_decode_page_invalid_offset() {
         let page_header = PageHeader {
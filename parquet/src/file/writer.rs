@@ -24,6 +24,7 @@ use crate::file::page_index::offset_index::OffsetIndexMetaData;
 use crate::parquet_thrift::{ThriftCompactOutputProtocol, WriteThrift};
 use std::fmt::Debug;
 use std::io::{BufWriter, IoSlice, Read};
+use std::ops::Range;
 use std::{io::Write, sync::Arc};
 
 use crate::column::page_encryption::PageEncryptor;
@@ -165,6 +166,7 @@ pub struct SerializedFileWriter<W: Write> {
     // kv_metadatas will be appended to `props` when `write_metadata`
     kv_metadatas: Vec<KeyValue>,
     finished: bool,
+    footer_range: Option<Range<u64>>,
     #[cfg(feature = "encryption")]
     file_encryptor: Option<Arc<FileEncryptor>>,
 }
@@ -203,6 +205,7 @@ impl<W: Write + Send> SerializedFileWriter<W> {
             row_group_index: 0,
             kv_metadatas: Vec::new(),
             finished: false,
+            footer_range: None,
             #[cfg(feature = "encryption")]
             file_encryptor,
         })
@@ -216,9 +219,9 @@ impl<W: Write + Send> SerializedFileWriter<W> {
         if let Some(file_encryption_properties) = properties.file_encryption_properties() {
             file_encryption_properties.validate_encrypted_column_names(schema_descriptor)?;
 
-            Ok(Some(Arc::new(FileEncryptor::new(Arc::clone(
+            Ok(Some(Arc::new(FileEncryptor::for_properties(
                 file_encryption_properties,
-            ))?)))
+            )?)))
         } else {
             Ok(None)
         }
@@ -250,6 +253,7 @@ impl<W: Write + Send> SerializedFileWriter<W> {
             .expect("SerializedFileWriter::row_group_index overflowed");
 
         let bloom_filter_position = self.properties().bloom_filter_position();
+        let bloom_filter_encryptor_context = self.get_bloom_filter_encryptor_context();
         let row_groups = &mut self.row_groups;
         let row_bloom_filters = &mut self.bloom_filters;
         let row_column_indexes = &mut self.column_indexes;
@@ -264,9 +268,12 @@ impl<W: Write + Send> SerializedFileWriter<W> {
             row_offset_indexes.push(row_group_offset_index);
             // write bloom filters out immediately after the row group if requested
             match bloom_filter_position {
-                BloomFilterPosition::AfterRowGroup => {
-                    write_bloom_filters(buf, row_bloom_filters, &mut metadata)?
-                }
+                BloomFilterPosition::AfterRowGroup => write_bloom_filters(
+                    buf,
+                    row_bloom_filters,
+                    &mut metadata,
+                    &bloom_filter_encryptor_context,
+                )?,
                 BloomFilterPosition::End => (),
             };
             row_groups.push(metadata);
@@ -330,8 +337,14 @@ impl<W: Write + Send> SerializedFileWriter<W> {
         self.finished = true;
 
         // write out any remaining bloom filters after all row groups
+        let bloom_filter_encryptor_context = self.get_bloom_filter_encryptor_context();
         for row_group in &mut self.row_groups {
-            write_bloom_filters(&mut self.buf, &mut self.bloom_filters, row_group)?;
+            write_bloom_filters(
+                &mut self.buf,
+                &mut self.bloom_filters,
+                row_group,
+                &bloom_filter_encryptor_context,
+            )?;
         }
 
         let key_value_metadata = match self.props.key_value_metadata() {
@@ -368,7 +381,9 @@ impl<W: Write + Send> SerializedFileWriter<W> {
         if !self.props.offset_index_disabled() {
             encoder = encoder.with_offset_indexes(offset_indexes);
         }
-        encoder.finish()
+        let (metadata, footer_range) = encoder.finish_with_footer_range()?;
+        self.footer_range = Some(footer_range);
+        Ok(metadata)
     }
 
     #[inline]
@@ -452,19 +467,52 @@ impl<W: Write + Send> SerializedFileWriter<W> {
         self.buf.bytes_written()
     }
 
+    /// Returns the byte range of the serialized file metadata, or `None` if the
+    /// writer has not been finished with [`Self::finish`].
+    ///
+    /// For files with an encrypted footer, this covers the file crypto metadata
+    /// followed by the encrypted file metadata. The range is followed by the 4 byte
+    /// metadata length and the 4 byte magic number at the end of the file, so the
+    /// footer bytes can be read back from the sink, for example to checksum them or
+    /// to record the footer location in a catalog.
+    pub fn footer_range(&self) -> Option<Range<u64>> {
+        self.footer_range.clone()
+    }
+
     /// Get the file encryptor used by this instance to encrypt data
     #[cfg(feature = "encryption")]
     pub(crate) fn file_encryptor(&self) -> Option<Arc<FileEncryptor>> {
         self.file_encryptor.clone()
     }
+
+    #[cfg(feature = "encryption")]
+    fn get_bloom_filter_encryptor_context(&self) -> BloomFilterEncryptorContext {
+        BloomFilterEncryptorContext {
+            file_encryptor: self.file_encryptor.clone(),
+        }
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn get_bloom_filter_encryptor_context(&self) -> BloomFilterEncryptorContext {
+        BloomFilterEncryptorContext {}
+    }
+}
+
+#[cfg(feature = "encryption")]
+struct BloomFilterEncryptorContext {
+    file_encryptor: Option<Arc<FileEncryptor>>,
 }
 
+#[cfg(not(feature = "encryption"))]
+struct BloomFilterEncryptorContext {}
+
 /// Serialize all the bloom filters of the given row group to the given buffer,
 /// and returns the updated row group metadata.
 fn write_bloom_filters<W: Write + Send>(
     buf: &mut TrackedWrite<W>,
     bloom_filters: &mut [Vec<Option<Sbbf>>],
     row_group: &mut RowGroupMetaData,
+    encryptor_context: &BloomFilterEncryptorContext,
 ) -> Result<()> {
     // iter row group
     // iter each column
@@ -484,7 +532,14 @@ fn write_bloom_filters<W: Write + Send>(
     for (column_idx, column_chunk) in row_group.columns_mut().iter_mut().enumerate() {
         if let Some(bloom_filter) = bloom_filters[row_group_idx][column_idx].take() {
             let start_offset = buf.bytes_written();
-            bloom_filter.write(&mut *buf)?;
+            write_bloom_filter(
+                buf,
+                &bloom_filter,
+                encryptor_context,
+                column_chunk,
+                row_group_idx,
+                column_idx,
+            )?;
             let end_offset = buf.bytes_written();
             // set offset and index for bloom filter
             *column_chunk = column_chunk
@@ -498,6 +553,55 @@ fn write_bloom_filters<W: Write + Send>(
     Ok(())
 }
 
+/// Write a single bloom filter, encrypting it if its column is encrypted
+#[cfg(feature = "encryption")]
+fn write_bloom_filter<W: Write + Send>(
+    buf: &mut TrackedWrite<W>,
+    bloom_filter: &Sbbf,
+    encryptor_context: &BloomFilterEncryptorContext,
+    column_chunk: &ColumnChunkMetaData,
+    row_group_idx: usize,
+    column_idx: usize,
+) -> Result<()> {
+    use crate::encryption::modules::{ModuleType, create_module_aad};
+
+    if let Some(file_encryptor) = encryptor_context.file_encryptor.as_ref() {
+        let column_path = column_chunk.column_path();
+        if file_encryptor.is_column_encrypted(column_path) {
+            let mut encryptor = file_encryptor.get_column_encryptor(column_path)?;
+            let create_aad = |module_type| {
+                create_module_aad(
+                    file_encryptor.file_aad(),
+                    module_type,
+                    row_group_idx,
+                    column_idx,
+                    None,
+                )
+            };
+            return bloom_filter.write_encrypted(
+                &mut *buf,
+                &mut encryptor,
+                &create_aad(ModuleType::BloomFilterHeader)?,
+                &create_aad(ModuleType::BloomFilterBitset)?,
+            );
+        }
+    }
+    bloom_filter.write(&mut *buf)
+}
+
+/// Write a single bloom filter
+#[cfg(not(feature = "encryption"))]
+fn write_bloom_filter<W: Write + Send>(
+    buf: &mut TrackedWrite<W>,
+    bloom_filter: &Sbbf,
+    _encryptor_context: &BloomFilterEncryptorContext,
+    _column_chunk: &ColumnChunkMetaData,
+    _row_group_idx: usize,
+    _column_idx: usize,
+) -> Result<()> {
+    bloom_filter.write(&mut *buf)
+}
+
 /// Parquet row group writer API.
 ///
 /// Provides methods to access column writers in an iterator-like fashion, order is
@@ -849,7 +953,7 @@ impl<'a, W: Write + Send> SerializedRowGroupWriter<'a, W> {
             &context.file_encryptor,
             context.row_group_index,
             context.column_index,
-            &column.path().string(),
+            column.path(),
         )?;
 
         Ok(page_writer.with_page_encryptor(page_encryptor))
@@ -1037,6 +1141,9 @@ impl<W: Write + Send> PageWriter for SerializedPageWriter<'_, W> {
     }
 
     fn close(&mut self) -> Result<()> {
+        if let Some(page_encryptor) = self.page_encryptor_mut() {
+            page_encryptor.finish();
+        }
         self.sink.flush()?;
         Ok(())
     }
@@ -1086,6 +1193,7 @@ mod tests {
     use crate::file::serialized_reader::ReadOptionsBuilder;
     use crate::file::statistics::{from_thrift_page_stats, page_stats_to_thrift};
     use crate::file::{
+        FOOTER_SIZE,
         properties::{ReaderProperties, WriterProperties, WriterVersion},
         reader::{FileReader, SerializedFileReader, SerializedPageReader},
         statistics::Statistics,
@@ -1191,6 +1299,46 @@ mod tests {
         assert_eq!(reader.get_row_iter(None).unwrap().count(), 0);
     }
 
+    #[test]
+    fn test_file_writer_footer_range() {
+        let schema = Arc::new(
+            types::Type::group_type_builder("schema")
+                .with_fields(vec![Arc::new(
+                    types::Type::primitive_type_builder("col1", Type::INT32)
+                        .with_repetition(Repetition::REQUIRED)
+                        .build()
+                        .unwrap(),
+                )])
+                .build()
+                .unwrap(),
+        );
+        let mut buffer = Vec::new();
+        let mut writer =
+            SerializedFileWriter::new(&mut buffer, schema, Default::default()).unwrap();
+        let mut row_group_writer = writer.next_row_group().unwrap();
+        let mut column_writer = row_group_writer.next_column().unwrap().unwrap();
+        column_writer
+            .typed::<Int32Type>()
+            .write_batch(&[1, 2, 3], None, None)
+            .unwrap();
+        column_writer.close().unwrap();
+        row_group_writer.close().unwrap();
+        assert_eq!(writer.footer_range(), None);
+
+        writer.finish().unwrap();
+        let footer_range = writer.footer_range().unwrap();
+        drop(writer);
+
+        let metadata_len = footer_range.end - footer_range.start;
+        let footer_end = footer_range.end as usize;
+        assert_eq!(footer_end + FOOTER_SIZE, buffer.len());
+        assert_eq!(
+            &buffer[footer_end..footer_end + 4],
+            &(metadata_len as u32).to_le_bytes()
+        );
+        assert_eq!(&buffer[footer_end + 4..], &PARQUET_MAGIC);
+    }
+
     #[test]
     fn test_file_writer_column_orders_populated() {
         let file = tempfile::tempfile().unwrap();
@@ -22,3 +22,7 @@ pub mod file_util;
 
 #[cfg(test)]
 pub mod rand_gen;
+
+/// Fails to compile unless `T` is [`Send`] and [`Sync`]
+#[cfg(test)]
+pub fn assert_send_sync<T: Send + Sync>() {}
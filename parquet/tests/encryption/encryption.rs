@@ -22,29 +22,45 @@ use crate::encryption_util::{
     AES_128_COLUMN_KEYS, AES_128_COLUMN_NAME_KEYS, AES_128_COLUMN_NAMES, AES_128_FOOTER_KEY,
     AES_128_FOOTER_KEY_NAME, AES_128_KEY_NAME_KEY, AES_128_KEY_NAMES, AES_256_COLUMN_KEYS,
     AES_256_COLUMN_NAME_KEYS, AES_256_COLUMN_NAMES, AES_256_FOOTER_KEY, AES_256_FOOTER_KEY_NAME,
-    AES_256_KEY_NAME_KEY, AES_256_KEY_NAMES, BAD_AES_128_FOOTER_KEY, BAD_AES_256_FOOTER_KEY,
-    TestKeyRetriever, read_and_roundtrip_to_encrypted_file, verify_column_indexes,
-    verify_encryption_test_file_read,
+    AES_256_KEY_NAME_KEY, AES_256_KEY_NAMES, AUTHENTICATION_FAILED, BAD_AES_128_FOOTER_KEY,
+    BAD_AES_256_FOOTER_KEY, ExpectedEncryptedFile, SequenceRandomSource, TestKeyRetriever,
+    int32_batch, read_and_roundtrip_to_encrypted_file, verify_column_indexes,
+    verify_encrypted_file_read, verify_encryption_test_file_read, write_batch,
+    write_batch_with_properties,
 };
 use arrow::array::*;
 use arrow::error::Result as ArrowResult;
 use arrow_array::{Int32Array, RecordBatch};
 use arrow_schema::{DataType as ArrowDataType, DataType, Field, Schema};
-use parquet::arrow::ArrowWriter;
 use parquet::arrow::arrow_reader::{
-    ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder, RowSelection,
-    RowSelector,
+    ArrowPredicateFn, ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
+    RowFilter, RowSelection, RowSelector, read_encrypted_parquet, read_encrypted_parquet_with,
 };
-use parquet::data_type::{ByteArray, ByteArrayType};
-use parquet::encryption::decrypt::FileDecryptionProperties;
-use parquet::encryption::encrypt::FileEncryptionProperties;
+use parquet::arrow::arrow_writer::write_encrypted_parquet;
+use parquet::arrow::{ArrowWriter, ProjectionMask};
+use parquet::data_type::{ByteArray, ByteArrayType, Int32Type};
+use parquet::encryption::backend::{AeadBackend, BlockDecryptor, BlockEncryptor};
+use parquet::encryption::decrypt::{
+    EncryptionMode, FileDecryptionProperties, KeyUsageEvent, VersionedKeyRetriever,
+};
+use parquet::encryption::encrypt::{
+    EncryptionAlgorithm, EncryptionKey, FileEncryptionProperties, KeyId,
+};
+use parquet::encryption::in_memory::{decrypt_record_batches, encrypt_record_batches};
+use parquet::encryption::metrics::EncryptionMetrics;
+use parquet::encryption::modules::ModuleType;
 use parquet::errors::ParquetError;
+use parquet::file::column_crypto_metadata::ColumnCryptoMetaData;
 use parquet::file::metadata::{ColumnChunkMetaData, PageIndexPolicy, ParquetMetaData};
+use parquet::file::page_index::column_index::ColumnIndexMetaData;
 use parquet::file::properties::WriterProperties;
 use parquet::file::writer::SerializedFileWriter;
 use parquet::schema::parser::parse_message_type;
+use parquet::schema::types::ColumnPath;
+use std::collections::HashMap;
 use std::fs::File;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[test]
 fn test_non_uniform_encryption_plaintext_footer() {
@@ -149,7 +165,9 @@ fn test_non_uniform_encryption_disabled_aad_storage() {
         assert!(result.is_err());
         std::assert_eq!(
             result.unwrap_err().to_string(),
-            "Parquet error: Provided footer key and AAD were unable to decrypt parquet footer"
+            format!(
+                "Decryption key error: Provided footer key and AAD were unable to decrypt parquet footer: {AUTHENTICATION_FAILED}"
+            )
         );
 
         // Not providing any AAD prefix should fail as it isn't stored in the file
@@ -166,8 +184,9 @@ fn test_non_uniform_encryption_disabled_aad_storage() {
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err().to_string(),
-            "Parquet error: Parquet file was encrypted with an AAD prefix that is not stored in the file, \
-        but no AAD prefix was provided in the file decryption properties"
+            "Parquet error: This file requires an externally-supplied AAD prefix. It was encrypted with an \
+            AAD prefix that is not stored in the file, but no AAD prefix was provided in \
+            the file decryption properties"
         );
     }
 
@@ -252,25 +271,65 @@ fn test_aes_ctr_encryption() {
             builder = builder.with_column_key(column_name, key.to_vec());
         }
         let decryption_properties = builder.build().unwrap();
-
-        let options =
-            ArrowReaderOptions::new().with_file_decryption_properties(decryption_properties);
-        let metadata = ArrowReaderMetadata::load(&file, options);
-
-        match metadata {
-            Err(ParquetError::NYI(s)) => {
-                assert!(s.contains("AES_GCM_CTR_V1"));
-            }
-            _ => {
-                panic!("Expected ParquetError::NYI");
-            }
-        };
+        verify_encryption_test_file_read(file, decryption_properties);
     }
 
     aes_ctr_encryption(AES_128_FOOTER_KEY, AES_128_COLUMN_NAME_KEYS);
     aes_ctr_encryption(AES_256_FOOTER_KEY, AES_256_COLUMN_NAME_KEYS);
 }
 
+#[test]
+fn test_interop_fixtures() {
+    // Encrypted files written by other Parquet implementations. New fixtures can be
+    // verified by adding their file name, keys and expected structure here.
+    let fixtures = [
+        ("uniform_encryption.parquet.encrypted", false),
+        ("encrypt_columns_and_footer.parquet.encrypted", true),
+        ("encrypt_columns_plaintext_footer.parquet.encrypted", true),
+        ("encrypt_columns_and_footer_ctr.parquet.encrypted", true),
+    ];
+    let key_sets = [
+        (
+            AES_128_FOOTER_KEY.as_slice(),
+            AES_128_COLUMN_NAME_KEYS.as_slice(),
+        ),
+        (
+            AES_256_FOOTER_KEY.as_slice(),
+            AES_256_COLUMN_NAME_KEYS.as_slice(),
+        ),
+    ];
+
+    for (footer_key, column_keys) in key_sets {
+        for (file_name, uses_column_keys) in fixtures {
+            let path = encryption_util::encrypted_data_path(footer_key, file_name);
+            let file = File::open(path).unwrap();
+
+            let mut builder = FileDecryptionProperties::builder(footer_key.to_vec());
+            let expected = if uses_column_keys {
+                for (column_name, key) in column_keys {
+                    builder = builder.with_column_key(column_name, key.to_vec());
+                }
+                ExpectedEncryptedFile::parquet_testing(
+                    column_keys
+                        .iter()
+                        .map(|(column_name, _)| *column_name)
+                        .collect(),
+                )
+            } else {
+                let expected = ExpectedEncryptedFile::parquet_testing(vec![]);
+                let all_columns = expected.columns.iter().map(|(path, _)| *path).collect();
+                ExpectedEncryptedFile {
+                    encrypted_columns: all_columns,
+                    ..expected
+                }
+            };
+            let decryption_properties = builder.build().unwrap();
+
+            verify_encrypted_file_read(file, decryption_properties, &expected);
+        }
+    }
+}
+
 #[test]
 fn test_non_uniform_encryption_plaintext_footer_with_key_retriever() {
     fn non_uniform_encryption_plaintext_footer_with_key_retriever(
@@ -579,8 +638,9 @@ fn uniform_encryption_roundtrip(
     let file = tempfile::tempfile()?;
 
     let footer_key = AES_128_FOOTER_KEY;
-    let file_encryption_properties =
-        FileEncryptionProperties::builder(footer_key.to_vec()).build()?;
+    let file_encryption_properties = FileEncryptionProperties::builder(footer_key.to_vec())
+        .with_uniform_encryption()
+        .build()?;
 
     let props = WriterProperties::builder()
         // Ensure multiple row groups
@@ -684,8 +744,9 @@ fn uniform_encryption_page_skipping(page_index: bool) -> parquet::errors::Result
     let file = tempfile::tempfile()?;
 
     let footer_key = AES_128_FOOTER_KEY;
-    let file_encryption_properties =
-        FileEncryptionProperties::builder(footer_key.to_vec()).build()?;
+    let file_encryption_properties = FileEncryptionProperties::builder(footer_key.to_vec())
+        .with_uniform_encryption()
+        .build()?;
 
     let props = WriterProperties::builder()
         // Ensure multiple row groups
@@ -840,6 +901,7 @@ fn test_write_uniform_encryption_plaintext_footer() {
         .unwrap();
 
     let file_encryption_properties = FileEncryptionProperties::builder(footer_key)
+        .with_uniform_encryption()
         .with_plaintext_footer(true)
         .build()
         .unwrap();
@@ -934,6 +996,7 @@ pub fn test_column_statistics_with_plaintext_footer_and_uniform_encryption() {
 
     // Write with uniform encryption and a plaintext footer.
     let encryption_properties = FileEncryptionProperties::builder(footer_key.clone())
+        .with_uniform_encryption()
         .with_plaintext_footer(true)
         .build()
         .unwrap();
@@ -989,6 +1052,7 @@ pub fn test_column_statistics_with_encrypted_footer_and_uniform_encryption() {
 
     // Encrypt with an encrypted footer and uniform encryption
     let encryption_properties = FileEncryptionProperties::builder(footer_key.clone())
+        .with_uniform_encryption()
         .with_plaintext_footer(false)
         .build()
         .unwrap();
@@ -1121,6 +1185,7 @@ fn test_write_uniform_encryption() {
             .unwrap();
 
         let file_encryption_properties = FileEncryptionProperties::builder(footer_key.to_vec())
+            .with_uniform_encryption()
             .build()
             .unwrap();
 
@@ -1221,6 +1286,7 @@ fn test_write_encrypted_column() {
     let builder = WriterProperties::builder();
     let footer_key: &[u8] = AES_128_FOOTER_KEY;
     let file_encryption_properties = FileEncryptionProperties::builder(footer_key.to_vec())
+        .with_uniform_encryption()
         .build()
         .unwrap();
 
@@ -1292,6 +1358,106 @@ fn test_write_encrypted_column() {
     assert_eq!(row_count, file_metadata.num_rows() as usize);
 }
 
+#[test]
+fn test_write_encrypted_columns_with_column_keys() {
+    let message_type = "
+            message test_schema {
+                REQUIRED INT32 a;
+                OPTIONAL BYTE_ARRAY b (UTF8);
+                REQUIRED INT32 c;
+            }
+        ";
+    let schema = Arc::new(parse_message_type(message_type).unwrap());
+    let file: File = tempfile::tempfile().unwrap();
+
+    // Column c has no key so is left unencrypted
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("a", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("b", AES_128_COLUMN_KEYS[1].into())
+        .with_plaintext_footer(true)
+        .build()
+        .unwrap();
+    let props = Arc::new(
+        WriterProperties::builder()
+            .with_file_encryption_properties(file_encryption_properties)
+            .set_data_page_row_count_limit(4)
+            .set_write_batch_size(4)
+            .build(),
+    );
+
+    let num_row_groups = 2;
+    let a_values: Vec<i32> = (0..10).collect();
+    let b_values = vec![ByteArray::from("parquet"); 7];
+    let b_def_levels = [1, 1, 0, 1, 1, 0, 1, 1, 0, 1];
+    let c_values: Vec<i32> = (100..110).collect();
+
+    let mut writer = SerializedFileWriter::new(&file, schema, props).unwrap();
+    for _ in 0..num_row_groups {
+        let mut row_group_writer = writer.next_row_group().unwrap();
+
+        let mut col_writer = row_group_writer.next_column().unwrap().unwrap();
+        col_writer
+            .typed::<Int32Type>()
+            .write_batch(&a_values, None, None)
+            .unwrap();
+        col_writer.close().unwrap();
+
+        let mut col_writer = row_group_writer.next_column().unwrap().unwrap();
+        col_writer
+            .typed::<ByteArrayType>()
+            .write_batch(&b_values, Some(&b_def_levels), None)
+            .unwrap();
+        col_writer.close().unwrap();
+
+        let mut col_writer = row_group_writer.next_column().unwrap().unwrap();
+        col_writer
+            .typed::<Int32Type>()
+            .write_batch(&c_values, None, None)
+            .unwrap();
+        col_writer.close().unwrap();
+
+        row_group_writer.close().unwrap();
+    }
+    writer.close().unwrap();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("a", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("b", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let (batches, metadata) =
+        encryption_util::read_encrypted_file(&file, decryption_properties).unwrap();
+
+    assert_eq!(metadata.metadata().num_row_groups(), num_row_groups);
+    for row_group in metadata.metadata().row_groups() {
+        let columns = row_group.columns();
+        assert!(columns[0].crypto_metadata().is_some());
+        assert!(columns[1].crypto_metadata().is_some());
+        assert!(columns[2].crypto_metadata().is_none());
+    }
+
+    let batch = arrow::compute::concat_batches(&batches[0].schema(), &batches).unwrap();
+    let repeat = |values: &[i32]| values.repeat(num_row_groups);
+    let expected_b: StringArray = b_def_levels
+        .repeat(num_row_groups)
+        .iter()
+        .map(|level| (*level > 0).then_some("parquet"))
+        .collect();
+    assert_eq!(
+        batch
+            .column(0)
+            .as_primitive::<arrow::datatypes::Int32Type>(),
+        &Int32Array::from(repeat(&a_values))
+    );
+    assert_eq!(batch.column(1).as_string::<i32>(), &expected_b);
+    assert_eq!(
+        batch
+            .column(2)
+            .as_primitive::<arrow::datatypes::Int32Type>(),
+        &Int32Array::from(repeat(&c_values))
+    );
+}
+
 #[test]
 fn test_write_encrypted_struct_field() {
     let int_32: Int32Array = [Some(1), Some(6)].iter().collect();
@@ -1421,6 +1587,62 @@ pub fn test_retrieve_row_group_statistics_after_encrypted_write() {
     );
 }
 
+#[test]
+fn test_read_projection_without_unprojected_column_keys() {
+    read_projection_without_unprojected_column_keys(PageIndexPolicy::Skip);
+}
+
+#[test]
+fn test_read_projection_without_unprojected_column_keys_with_page_index() {
+    read_projection_without_unprojected_column_keys(PageIndexPolicy::Required);
+}
+
+fn read_projection_without_unprojected_column_keys(page_index_policy: PageIndexPolicy) {
+    let batch = int32_batch(&["id", "x", "y"], 50);
+
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let file = write_batch(&batch, Some(file_encryption_properties));
+
+    // Only the key for column "x" is known to the reader
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let options = ArrowReaderOptions::new()
+        .with_file_decryption_properties(decryption_properties)
+        .with_page_index_policy(page_index_policy);
+
+    let builder =
+        ParquetRecordBatchReaderBuilder::try_new_with_options(file.clone(), options.clone())
+            .unwrap();
+    let mask = ProjectionMask::leaves(builder.parquet_schema(), [0, 1]);
+    let batches = builder
+        .with_projection(mask)
+        .build()
+        .unwrap()
+        .collect::<ArrowResult<Vec<_>>>()
+        .unwrap();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0], batch.project(&[0, 1]).unwrap());
+
+    // Projecting the column without a key still fails
+    let builder = ParquetRecordBatchReaderBuilder::try_new_with_options(file, options).unwrap();
+    let mask = ProjectionMask::leaves(builder.parquet_schema(), [2]);
+    let result = builder.with_projection(mask).build();
+    let err = match result {
+        Ok(mut reader) => reader.next().unwrap().unwrap_err().to_string(),
+        Err(e) => e.to_string(),
+    };
+    assert!(
+        err.contains("No column decryption key set for encrypted column 'y'"),
+        "unexpected error: {err}"
+    );
+}
+
 #[test]
 fn test_decrypt_page_index_uniform() {
     fn decrypt_page_index_uniform(footer_key: &[u8]) {
@@ -1507,3 +1729,4100 @@ fn test_decryption_properties_uses_key_retriever() {
 
     assert!(!uniform_properties.uses_key_retriever());
 }
+
+#[test]
+fn test_retrieve_footer_key_by_key_metadata() {
+    let batch = int32_batch(&["x"], 10);
+
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .with_footer_key_metadata(AES_128_FOOTER_KEY_NAME.into())
+        .build()
+        .unwrap();
+    let file = write_batch(&batch, Some(file_encryption_properties));
+
+    // The reader doesn't know the footer key up front, it is resolved by the
+    // key metadata stored in the file.
+    let key_retriever = TestKeyRetriever::new().with_key(
+        AES_128_FOOTER_KEY_NAME.to_owned(),
+        AES_128_FOOTER_KEY.to_vec(),
+    );
+    let decryption_properties =
+        FileDecryptionProperties::with_key_retriever(Arc::new(key_retriever))
+            .build()
+            .unwrap();
+    let (batches, _) = encryption_util::read_encrypted_file(&file, decryption_properties).unwrap();
+    assert_eq!(batches, vec![batch]);
+
+    // A retriever without the footer key can't read the file
+    let decryption_properties =
+        FileDecryptionProperties::with_key_retriever(Arc::new(TestKeyRetriever::new()))
+            .build()
+            .unwrap();
+    let err = encryption_util::read_encrypted_file(&file, decryption_properties).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("Parquet error: Could not retrieve key for metadata \"{AES_128_FOOTER_KEY_NAME}\"")
+    );
+}
+
+#[test]
+fn test_write_encrypted_nested_and_list_columns() {
+    let struct_array = StructArray::from(vec![
+        (
+            Arc::new(Field::new("a", DataType::Int32, false)),
+            Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef,
+        ),
+        (
+            Arc::new(Field::new("b", DataType::Utf8, false)),
+            Arc::new(StringArray::from(vec!["x", "y", "z"])) as ArrayRef,
+        ),
+    ]);
+    let list_array = ListArray::from_iter_primitive::<arrow::datatypes::Int32Type, _, _>(vec![
+        Some(vec![Some(1), Some(2)]),
+        None,
+        Some(vec![Some(3), None]),
+    ]);
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("s", struct_array.data_type().clone(), false),
+        Field::new("l", list_array.data_type().clone(), true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(struct_array), Arc::new(list_array)],
+    )
+    .unwrap();
+
+    // Keys are specified for leaf columns using the full Parquet column path,
+    // which for lists includes the repeated group and element names
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("s.a", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("l.list.item", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+
+    let file = write_batch(&batch, Some(file_encryption_properties));
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("s.a", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("l.list.item", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let (batches, metadata) =
+        encryption_util::read_encrypted_file(&file, decryption_properties).unwrap();
+    assert_eq!(batches, vec![batch]);
+
+    let path_in_schema = |column: &ColumnChunkMetaData| match column.crypto_metadata() {
+        Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(column_key_encryption)) => {
+            Some(column_key_encryption.path_in_schema.clone())
+        }
+        _ => None,
+    };
+    let columns = metadata.metadata().row_group(0).columns();
+    assert_eq!(columns.len(), 3);
+    assert_eq!(
+        path_in_schema(&columns[0]),
+        Some(vec!["s".into(), "a".into()])
+    );
+    assert_eq!(path_in_schema(&columns[1]), None);
+    assert_eq!(
+        path_in_schema(&columns[2]),
+        Some(vec!["l".into(), "list".into(), "item".into()])
+    );
+}
+
+#[test]
+fn test_write_encrypted_column_paths_validated() {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new(
+            "s",
+            DataType::Struct(vec![Field::new("y", DataType::Int32, false)].into()),
+            false,
+        ),
+    ]));
+
+    let try_write = |column_paths: &[&str]| {
+        let mut builder = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into());
+        for (path, key) in column_paths.iter().zip(AES_128_COLUMN_KEYS) {
+            builder = builder.with_column_key(path, key.into());
+        }
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(builder.build().unwrap())
+            .build();
+        ArrowWriter::try_new(Vec::new(), schema.clone(), Some(props)).map(|_| ())
+    };
+
+    try_write(&["x", "s.y"]).unwrap();
+
+    let err = try_write(&["x", "s.z"]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Parquet error: The following columns with encryption keys specified were not found in the schema: s.z"
+    );
+
+    // Only leaf columns can be encrypted
+    let err = try_write(&["xx", "s"]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Parquet error: The following columns with encryption keys specified were not found in the schema: s, xx"
+    );
+}
+
+#[test]
+fn test_read_with_invalid_key_length() {
+    let batch = int32_batch(&["x"], 10);
+
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let file = write_batch(&batch, Some(file_encryption_properties));
+
+    // Keys of an invalid length produce an error rather than a panic
+    let decryption_properties = FileDecryptionProperties::builder(b"bad_key".into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let result = encryption_util::read_encrypted_file(&file, decryption_properties);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Invalid key error: Invalid footer key. Parquet error: Error creating RingGcmBlockDecryptor with unsupported key length: 7"
+    );
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", b"bad_column_key".into())
+        .build()
+        .unwrap();
+    let result = encryption_util::read_encrypted_file(&file, decryption_properties);
+    assert!(result.unwrap_err().to_string().contains(
+        "Parquet error: Error creating RingGcmBlockDecryptor with unsupported key length: 14"
+    ));
+}
+
+#[test]
+fn test_aes_ctr_encryption_roundtrip() {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new("y", DataType::Utf8, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from((0..100).collect::<Vec<_>>())),
+            Arc::new(StringArray::from_iter(
+                (0..100).map(|i| (i % 7 != 0).then(|| format!("value {}", i % 5))),
+            )),
+        ],
+    )
+    .unwrap();
+
+    let uniform = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .with_algorithm(EncryptionAlgorithm::AesGcmCtrV1)
+        .build()
+        .unwrap();
+    let column_keys = FileEncryptionProperties::builder(AES_256_FOOTER_KEY.into())
+        .with_column_key("x", AES_256_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_256_COLUMN_KEYS[1].into())
+        .with_plaintext_footer(true)
+        .with_algorithm(EncryptionAlgorithm::AesGcmCtrV1)
+        .build()
+        .unwrap();
+    assert_eq!(uniform.algorithm(), EncryptionAlgorithm::AesGcmCtrV1);
+
+    for file_encryption_properties in [uniform, column_keys] {
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(file_encryption_properties.clone())
+            .set_data_page_row_count_limit(20)
+            .set_write_batch_size(20)
+            .build();
+        let file = write_batch_with_properties(&batch, props);
+
+        let mut builder =
+            FileDecryptionProperties::builder(file_encryption_properties.footer_key().to_vec());
+        for (column_name, _) in file_encryption_properties.column_keys() {
+            let key = match column_name {
+                "x" => AES_256_COLUMN_KEYS[0],
+                _ => AES_256_COLUMN_KEYS[1],
+            };
+            builder = builder.with_column_key(column_name, key.into());
+        }
+        let decryption_properties = builder.build().unwrap();
+        let (batches, _) =
+            encryption_util::read_encrypted_file(&file, decryption_properties).unwrap();
+        assert_eq!(batches, vec![batch.clone()]);
+    }
+}
+
+/// An insecure cipher backend that XORs data with the key, using the same
+/// module layout as AES-GCM with zeroed nonces and tags.
+#[derive(Debug, Default)]
+struct XorBackend {
+    encryptors_created: AtomicUsize,
+    decryptors_created: AtomicUsize,
+}
+
+#[derive(Debug)]
+struct XorCipher {
+    key: Vec<u8>,
+}
+
+impl XorCipher {
+    const NONCE_LEN: usize = 12;
+    const TAG_LEN: usize = 16;
+
+    fn apply(&self, data: &mut [u8]) {
+        for (i, byte) in data.iter_mut().enumerate() {
+            *byte ^= self.key[i % self.key.len()];
+        }
+    }
+}
+
+impl BlockEncryptor for XorCipher {
+    fn encrypt(&mut self, plaintext: &[u8], _aad: &[u8]) -> parquet::errors::Result<Vec<u8>> {
+        let length = (Self::NONCE_LEN + plaintext.len() + Self::TAG_LEN) as u32;
+        let mut ciphertext = length.to_le_bytes().to_vec();
+        ciphertext.extend([0; Self::NONCE_LEN]);
+        let start = ciphertext.len();
+        ciphertext.extend(plaintext);
+        self.apply(&mut ciphertext[start..]);
+        ciphertext.extend([0; Self::TAG_LEN]);
+        Ok(ciphertext)
+    }
+}
+
+impl BlockDecryptor for XorCipher {
+    fn decrypt_into(
+        &self,
+        length_and_ciphertext: &[u8],
+        _aad: &[u8],
+        plaintext: &mut Vec<u8>,
+    ) -> parquet::errors::Result<()> {
+        plaintext.clear();
+        plaintext.extend_from_slice(
+            &length_and_ciphertext
+                [4 + Self::NONCE_LEN..length_and_ciphertext.len() - Self::TAG_LEN],
+        );
+        self.apply(plaintext);
+        Ok(())
+    }
+
+    fn compute_plaintext_tag(
+        &self,
+        _aad: &[u8],
+        _plaintext: &[u8],
+    ) -> parquet::errors::Result<Vec<u8>> {
+        Ok(vec![0; Self::TAG_LEN])
+    }
+}
+
+impl AeadBackend for XorBackend {
+    fn new_encryptor(&self, key: &[u8]) -> parquet::errors::Result<Box<dyn BlockEncryptor>> {
+        self.encryptors_created.fetch_add(1, Ordering::Relaxed);
+        Ok(Box::new(XorCipher { key: key.to_vec() }))
+    }
+
+    fn new_decryptor(&self, key: &[u8]) -> parquet::errors::Result<Arc<dyn BlockDecryptor>> {
+        self.decryptors_created.fetch_add(1, Ordering::Relaxed);
+        Ok(Arc::new(XorCipher { key: key.to_vec() }))
+    }
+}
+
+#[test]
+fn test_custom_aead_backend() {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new("y", DataType::Utf8, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from((0..100).collect::<Vec<_>>())),
+            Arc::new(StringArray::from_iter(
+                (0..100).map(|i| (i % 7 != 0).then(|| format!("value {}", i % 5))),
+            )),
+        ],
+    )
+    .unwrap();
+
+    let backend = Arc::new(XorBackend::default());
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_aead_backend(backend.clone())
+        .build()
+        .unwrap();
+
+    let file = write_batch(&batch, Some(file_encryption_properties));
+    assert!(backend.encryptors_created.load(Ordering::Relaxed) > 0);
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_aead_backend(backend.clone())
+        .build()
+        .unwrap();
+    let (batches, _) = encryption_util::read_encrypted_file(&file, decryption_properties).unwrap();
+    assert_eq!(batches, vec![batch]);
+    assert!(backend.decryptors_created.load(Ordering::Relaxed) > 0);
+
+    // The file can't be decrypted by the default AES-GCM backend
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    assert!(encryption_util::read_encrypted_file(&file, decryption_properties).is_err());
+}
+
+#[test]
+fn test_decryptors_reused_across_row_groups_and_pages() {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new("y", DataType::Int32, false),
+        Field::new("z", DataType::Int32, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from((0..1000).collect::<Vec<_>>())),
+            Arc::new(Int32Array::from(
+                (0..1000).map(|i| i * 2).collect::<Vec<_>>(),
+            )),
+            Arc::new(Int32Array::from(
+                (0..1000).map(|i| i * 3).collect::<Vec<_>>(),
+            )),
+        ],
+    )
+    .unwrap();
+
+    let backend = Arc::new(XorBackend::default());
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[0].into())
+        .with_aead_backend(backend.clone())
+        .build()
+        .unwrap();
+
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(file_encryption_properties)
+        .set_max_row_group_row_count(Some(200))
+        .set_data_page_row_count_limit(50)
+        .set_write_batch_size(50)
+        .build();
+    let file = write_batch_with_properties(&batch, props);
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[0].into())
+        .with_aead_backend(backend.clone())
+        .build()
+        .unwrap();
+    let options = ArrowReaderOptions::default()
+        .with_file_decryption_properties(decryption_properties)
+        .with_page_index_policy(PageIndexPolicy::Required);
+    let metadata = ArrowReaderMetadata::load(&file, options).unwrap();
+    assert_eq!(metadata.metadata().num_row_groups(), 5);
+    let offset_index = metadata.metadata().offset_index().unwrap();
+    assert!(offset_index[0][0].page_locations().len() > 1);
+
+    let batches = ParquetRecordBatchReaderBuilder::new_with_metadata(file, metadata)
+        .build()
+        .unwrap()
+        .collect::<ArrowResult<Vec<_>>>()
+        .unwrap();
+    assert_eq!(
+        arrow::compute::concat_batches(&schema, &batches).unwrap(),
+        batch
+    );
+
+    // One decryptor for the footer key and one for the shared column key,
+    // rather than new decryptors for every row group
+    assert_eq!(backend.decryptors_created.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn test_metadata_debug_redacts_cached_decryptor_keys() {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new("y", DataType::Int32, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from((0..100).collect::<Vec<_>>())),
+            Arc::new(Int32Array::from(
+                (0..100).map(|i| i * 2).collect::<Vec<_>>(),
+            )),
+        ],
+    )
+    .unwrap();
+
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let file = write_batch(&batch, Some(file_encryption_properties));
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let metadata = ArrowReaderMetadata::load(&file, options).unwrap();
+
+    // Reading the columns populates the decryptor cache shared with the metadata
+    let batches = ParquetRecordBatchReaderBuilder::new_with_metadata(file, metadata.clone())
+        .build()
+        .unwrap()
+        .collect::<ArrowResult<Vec<_>>>()
+        .unwrap();
+    assert_eq!(batches, vec![batch]);
+
+    let debug = format!("{:?}", metadata.metadata());
+    assert!(debug.contains("decryptor_cache"), "{debug}");
+    assert!(!debug.contains("gcm_decryptors: 0"), "{debug}");
+    for key in [
+        AES_128_FOOTER_KEY,
+        AES_128_COLUMN_KEYS[0],
+        AES_128_COLUMN_KEYS[1],
+    ] {
+        assert!(
+            !debug.contains(std::str::from_utf8(key).unwrap()),
+            "{debug}"
+        );
+        assert!(!debug.contains(&format!("{:?}", key.as_slice())), "{debug}");
+    }
+}
+
+#[test]
+fn test_reproducible_write_with_fixed_aad_file_unique() {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new("y", DataType::Utf8, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from((0..100).collect::<Vec<_>>())),
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("value {i}")),
+            )),
+        ],
+    )
+    .unwrap();
+
+    let write_file = |aad_file_unique: Option<&[u8]>| {
+        // The XOR backend uses fixed nonces, so output is deterministic
+        // given a fixed AAD file identifier
+        let mut builder = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+            .with_aead_backend(Arc::new(XorBackend::default()));
+        if let Some(aad_file_unique) = aad_file_unique {
+            builder = builder.with_aad_file_unique(aad_file_unique.to_vec());
+        }
+        write_batch(&batch, Some(builder.build().unwrap()))
+    };
+
+    let aad_file_unique = b"12345678".as_slice();
+    let file_a = write_file(Some(aad_file_unique));
+    let file_b = write_file(Some(aad_file_unique));
+    assert_eq!(file_a, file_b);
+
+    // By default a random file identifier is generated for each file
+    let file_a = write_file(None);
+    let file_b = write_file(None);
+    assert_ne!(file_a, file_b);
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_aead_backend(Arc::new(XorBackend::default()))
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let batches = ParquetRecordBatchReaderBuilder::try_new_with_options(
+        write_file(Some(aad_file_unique)),
+        options,
+    )
+    .unwrap()
+    .build()
+    .unwrap()
+    .collect::<ArrowResult<Vec<_>>>()
+    .unwrap();
+    assert_eq!(batches, vec![batch]);
+}
+
+#[test]
+fn test_file_magic() {
+    let batch = int32_batch(&["x"], 10);
+    let assert_magic = |file_bytes: &[u8], magic: &[u8; 4]| {
+        assert_eq!(&file_bytes[..4], magic);
+        assert_eq!(&file_bytes[file_bytes.len() - 4..], magic);
+    };
+
+    // Files with an encrypted footer use the PARE magic
+    let encrypted_footer = write_batch(
+        &batch,
+        Some(
+            FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+                .with_uniform_encryption()
+                .build()
+                .unwrap(),
+        ),
+    );
+    assert_magic(&encrypted_footer, b"PARE");
+
+    let err = ArrowReaderMetadata::load(&encrypted_footer, Default::default()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Parquet error: Parquet file has an encrypted footer but decryption properties were not provided"
+    );
+
+    // Files with a plaintext footer are readable by legacy readers so use the PAR1 magic
+    let plaintext_footer = write_batch(
+        &batch,
+        Some(
+            FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+                .with_uniform_encryption()
+                .with_plaintext_footer(true)
+                .build()
+                .unwrap(),
+        ),
+    );
+    assert_magic(&plaintext_footer, b"PAR1");
+
+    let unencrypted = write_batch(&batch, None);
+    assert_magic(&unencrypted, b"PAR1");
+}
+
+#[test]
+fn test_read_encryption_algorithm() {
+    let batch = int32_batch(&["x"], 10);
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .build()
+        .unwrap();
+
+    for algorithm in [
+        EncryptionAlgorithm::AesGcmV1,
+        EncryptionAlgorithm::AesGcmCtrV1,
+    ] {
+        for plaintext_footer in [false, true] {
+            let file_encryption_properties =
+                FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+                    .with_uniform_encryption()
+                    .with_plaintext_footer(plaintext_footer)
+                    .with_algorithm(algorithm)
+                    .build()
+                    .unwrap();
+            let file = write_batch(&batch, Some(file_encryption_properties));
+
+            let options = ArrowReaderOptions::default()
+                .with_file_decryption_properties(decryption_properties.clone());
+            let metadata = ArrowReaderMetadata::load(&file, options).unwrap();
+            assert_eq!(metadata.metadata().encryption_algorithm(), Some(algorithm));
+
+            if plaintext_footer {
+                // The algorithm is also available without decryption properties
+                let metadata = ArrowReaderMetadata::load(&file, Default::default()).unwrap();
+                assert_eq!(metadata.metadata().encryption_algorithm(), Some(algorithm));
+            }
+        }
+    }
+
+    let file = write_batch(&batch, None);
+    let metadata = ArrowReaderMetadata::load(&file, Default::default()).unwrap();
+    assert_eq!(metadata.metadata().encryption_algorithm(), None);
+}
+
+#[test]
+fn test_read_encrypted_offset_index() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..1000)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(Int32Array::from_iter_values((0..1000).map(|i| -i))) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let encryption_properties = [
+        FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_uniform_encryption()
+            .build()
+            .unwrap(),
+        FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+            .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+            .with_plaintext_footer(true)
+            .build()
+            .unwrap(),
+    ];
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+
+    for encryption_properties in encryption_properties {
+        let props = WriterProperties::builder()
+            .set_dictionary_enabled(false)
+            .set_data_page_row_count_limit(100)
+            .set_write_batch_size(100)
+            .with_file_encryption_properties(encryption_properties)
+            .build();
+        let mut file_bytes = Vec::new();
+        let mut writer =
+            ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let options = ArrowReaderOptions::default()
+            .with_file_decryption_properties(decryption_properties.clone())
+            .with_page_index_policy(PageIndexPolicy::Required);
+        let metadata = ArrowReaderMetadata::load(&bytes::Bytes::from(file_bytes), options).unwrap();
+        let metadata = metadata.metadata();
+        let row_group = metadata.row_group(0);
+        let offset_index = &metadata.offset_index().unwrap()[0];
+        let column_index = &metadata.column_index().unwrap()[0];
+
+        for (column_idx, column) in row_group.columns().iter().enumerate() {
+            let page_locations = offset_index[column_idx].page_locations();
+            assert_eq!(page_locations.len(), 10);
+
+            // Pages are contiguous, starting at the first data page and
+            // covering the whole column chunk
+            assert_eq!(page_locations[0].offset, column.data_page_offset());
+            for (page_idx, pair) in page_locations.windows(2).enumerate() {
+                assert_eq!(
+                    pair[0].offset + pair[0].compressed_page_size as i64,
+                    pair[1].offset
+                );
+                assert_eq!(pair[1].first_row_index, (page_idx as i64 + 1) * 100);
+            }
+            let total_size: i64 = page_locations
+                .iter()
+                .map(|location| location.compressed_page_size as i64)
+                .sum();
+            assert_eq!(total_size, column.compressed_size());
+
+            match &column_index[column_idx] {
+                ColumnIndexMetaData::INT32(index) => {
+                    assert_eq!(index.num_pages(), 10);
+                    let (first_min, first_max) = if column_idx == 0 { (0, 99) } else { (-99, 0) };
+                    assert_eq!(index.min_value(0), Some(&first_min));
+                    assert_eq!(index.max_value(0), Some(&first_max));
+                }
+                _ => panic!("Expected an INT32 column index for column {column_idx}"),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_read_encryption_mode() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let read_mode = |file_encryption_properties: Option<Arc<FileEncryptionProperties>>| {
+        let mut builder = WriterProperties::builder();
+        if let Some(file_encryption_properties) = file_encryption_properties {
+            builder = builder.with_file_encryption_properties(file_encryption_properties);
+        }
+        let mut file_bytes = Vec::new();
+        let mut writer =
+            ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(builder.build())).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+            .build()
+            .unwrap();
+        let options =
+            ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+        let metadata = ArrowReaderMetadata::load(&bytes::Bytes::from(file_bytes), options).unwrap();
+        metadata.metadata().encryption_mode()
+    };
+
+    assert_eq!(read_mode(None), EncryptionMode::None);
+
+    let uniform = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .with_unencrypted_column("y")
+        .build()
+        .unwrap();
+    assert_eq!(read_mode(Some(uniform)), EncryptionMode::Uniform);
+
+    let per_column = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    assert_eq!(read_mode(Some(per_column)), EncryptionMode::PerColumn);
+}
+
+#[test]
+fn test_encryption_map() {
+    let struct_array = StructArray::from(vec![
+        (
+            Arc::new(Field::new("a", DataType::Int32, false)),
+            Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+        ),
+        (
+            Arc::new(Field::new("b", DataType::Int32, false)),
+            Arc::new(Int32Array::from_iter_values(10..20)) as ArrayRef,
+        ),
+    ]);
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+        ),
+        ("s", Arc::new(struct_array) as ArrayRef),
+    ])
+    .unwrap();
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("s.b", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("s.b", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let metadata = ArrowReaderMetadata::load(&bytes::Bytes::from(file_bytes), options).unwrap();
+
+    let encryption_map = metadata.encryption_map();
+    assert_eq!(encryption_map.len(), 4);
+    assert!(encryption_map["x"]);
+    assert!(!encryption_map["y"]);
+    assert!(!encryption_map["s.a"]);
+    assert!(encryption_map["s.b"]);
+}
+
+#[test]
+fn test_column_path_keys_with_dot_in_name() {
+    // A top-level column named "a.b" and a column "b" nested within "a"
+    // both have the dotted path "a.b"
+    let struct_array = StructArray::from(vec![(
+        Arc::new(Field::new("b", DataType::Int32, false)),
+        Arc::new(Int32Array::from_iter_values(10..20)) as ArrayRef,
+    )]);
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "a.b",
+            Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+        ),
+        ("a", Arc::new(struct_array) as ArrayRef),
+    ])
+    .unwrap();
+    let top_level_path = ColumnPath::new(vec!["a.b".to_owned()]);
+    let nested_path = ColumnPath::new(vec!["a".to_owned(), "b".to_owned()]);
+
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_path_key(&top_level_path, AES_128_COLUMN_KEYS[0].into())
+        .with_column_path_key(&nested_path, AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    let file_bytes = bytes::Bytes::from(file_bytes);
+
+    let read = |decryption_properties: Arc<FileDecryptionProperties>| {
+        let options =
+            ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+        ParquetRecordBatchReaderBuilder::try_new_with_options(file_bytes.clone(), options)?
+            .build()?
+            .collect::<ArrowResult<Vec<_>>>()
+            .map_err(ParquetError::from)
+    };
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_path_key(&top_level_path, AES_128_COLUMN_KEYS[0].into())
+        .with_column_path_key(&nested_path, AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let batches = read(decryption_properties).unwrap();
+    assert_eq!(batches, vec![batch]);
+
+    // Each key only matches the column it was configured for
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_path_key(&top_level_path, AES_128_COLUMN_KEYS[0].into())
+        .with_column_path_key(&nested_path, AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    assert!(read(decryption_properties).is_err());
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_path_key(&top_level_path, AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let err = read(decryption_properties).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("No column decryption key set for encrypted column 'a.b'"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_module_key_derivation_roundtrip() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("{}", i % 10)),
+            )) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let write = |module_key_derivation: bool| {
+        let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+            .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+            .with_module_key_derivation(module_key_derivation)
+            .build()
+            .unwrap();
+        assert_eq!(
+            encryption_properties.module_key_derivation(),
+            module_key_derivation
+        );
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(encryption_properties)
+            .set_statistics_enabled(parquet::file::properties::EnabledStatistics::Page)
+            .build();
+        write_batch_with_properties(&batch, props)
+    };
+    let read = |file_bytes: &bytes::Bytes, module_key_derivation: bool| {
+        let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+            .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+            .with_module_key_derivation(module_key_derivation)
+            .build()
+            .unwrap();
+        let options = ArrowReaderOptions::default()
+            .with_file_decryption_properties(decryption_properties)
+            .with_page_index_policy(PageIndexPolicy::Required);
+        ParquetRecordBatchReaderBuilder::try_new_with_options(file_bytes.clone(), options)?
+            .build()?
+            .collect::<ArrowResult<Vec<_>>>()
+            .map_err(ParquetError::from)
+    };
+
+    let derived_file = write(true);
+    assert_eq!(read(&derived_file, true).unwrap(), vec![batch.clone()]);
+    // Column modules aren't encrypted with the column keys themselves
+    assert!(read(&derived_file, false).is_err());
+
+    // Derivation is disabled by default, and files are encrypted with the column keys
+    let default_file = write(false);
+    let default_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .build()
+        .unwrap();
+    assert!(!default_properties.module_key_derivation());
+    assert_eq!(read(&default_file, false).unwrap(), vec![batch]);
+    assert!(read(&default_file, true).is_err());
+}
+
+#[test]
+fn test_encryption_metrics() {
+    #[derive(Debug, Default)]
+    struct CountingMetrics {
+        pages_encrypted: std::sync::Mutex<HashMap<String, (usize, usize)>>,
+        pages_decrypted: std::sync::Mutex<HashMap<String, (usize, usize)>>,
+        keys_retrieved: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    impl EncryptionMetrics for CountingMetrics {
+        fn page_encrypted(&self, column_path: &str, bytes: usize) {
+            let mut pages = self.pages_encrypted.lock().unwrap();
+            let counts = pages.entry(column_path.to_owned()).or_default();
+            counts.0 += 1;
+            counts.1 += bytes;
+        }
+
+        fn page_decrypted(&self, column_path: &str, bytes: usize) {
+            let mut pages = self.pages_decrypted.lock().unwrap();
+            let counts = pages.entry(column_path.to_owned()).or_default();
+            counts.0 += 1;
+            counts.1 += bytes;
+        }
+
+        fn key_retrieved(&self, column_path: Option<&str>) {
+            let mut keys = self.keys_retrieved.lock().unwrap();
+            keys.push(column_path.map(str::to_owned));
+        }
+    }
+
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let write_metrics = Arc::new(CountingMetrics::default());
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_footer_key_metadata(b"kf".into())
+        .with_column_key_and_metadata("x", AES_128_COLUMN_KEYS[0].into(), b"kc1".into())
+        .with_metrics(write_metrics.clone())
+        .build()
+        .unwrap();
+    // Ten plain encoded pages of ten 4 byte values
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .set_dictionary_enabled(false)
+        .set_write_batch_size(10)
+        .set_data_page_row_count_limit(10)
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let expected_pages = HashMap::from([("x".to_owned(), (10, 400))]);
+    assert_eq!(
+        *write_metrics.pages_encrypted.lock().unwrap(),
+        expected_pages
+    );
+    assert!(write_metrics.pages_decrypted.lock().unwrap().is_empty());
+
+    let key_retriever = TestKeyRetriever::new()
+        .with_key("kf".to_owned(), AES_128_FOOTER_KEY.to_vec())
+        .with_key("kc1".to_owned(), AES_128_COLUMN_KEYS[0].to_vec());
+    let read_metrics = Arc::new(CountingMetrics::default());
+    let decryption_properties =
+        FileDecryptionProperties::with_key_retriever(Arc::new(key_retriever))
+            .with_metrics(read_metrics.clone())
+            .build()
+            .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let batches = ParquetRecordBatchReaderBuilder::try_new_with_options(
+        bytes::Bytes::from(file_bytes),
+        options,
+    )
+    .unwrap()
+    .build()
+    .unwrap()
+    .collect::<ArrowResult<Vec<_>>>()
+    .unwrap();
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 100);
+
+    assert_eq!(
+        *read_metrics.pages_decrypted.lock().unwrap(),
+        expected_pages
+    );
+    assert!(read_metrics.pages_encrypted.lock().unwrap().is_empty());
+    let keys_retrieved = read_metrics.keys_retrieved.lock().unwrap();
+    assert_eq!(keys_retrieved.iter().filter(|c| c.is_none()).count(), 1);
+    assert!(
+        keys_retrieved
+            .iter()
+            .flatten()
+            .all(|column_path| column_path == "x")
+    );
+    assert_eq!(keys_retrieved.len(), 4, "{keys_retrieved:?}");
+}
+
+#[test]
+fn test_versioned_key_id_roundtrip() {
+    struct RecordingVersionedRetriever {
+        requested: std::sync::Mutex<Vec<KeyId>>,
+    }
+
+    impl VersionedKeyRetriever for RecordingVersionedRetriever {
+        fn retrieve_key(&self, key_id: &KeyId) -> parquet::errors::Result<Vec<u8>> {
+            self.requested.lock().unwrap().push(key_id.clone());
+            match (key_id.id.as_str(), key_id.version) {
+                ("footer", 2) => Ok(AES_128_FOOTER_KEY.to_vec()),
+                ("column", 5) => Ok(AES_128_COLUMN_KEYS[0].to_vec()),
+                _ => Err(ParquetError::General(format!("Unknown key {key_id}"))),
+            }
+        }
+    }
+
+    let batch = RecordBatch::try_from_iter(vec![(
+        "x",
+        Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+    )])
+    .unwrap();
+    let footer_key_id = KeyId::new("footer", 2);
+    let column_key_id = KeyId::new("column", 5);
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_footer_key_id(&footer_key_id)
+        .with_column_key_and_metadata(
+            "x",
+            AES_128_COLUMN_KEYS[0].into(),
+            column_key_id.to_key_metadata(),
+        )
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let retriever = Arc::new(RecordingVersionedRetriever {
+        requested: Default::default(),
+    });
+    let decryption_properties =
+        FileDecryptionProperties::with_versioned_key_retriever(retriever.clone())
+            .build()
+            .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let batches = ParquetRecordBatchReaderBuilder::try_new_with_options(
+        bytes::Bytes::from(file_bytes),
+        options,
+    )
+    .unwrap()
+    .build()
+    .unwrap()
+    .collect::<ArrowResult<Vec<_>>>()
+    .unwrap();
+    assert_eq!(batches, vec![batch]);
+
+    let requested = retriever.requested.lock().unwrap();
+    assert_eq!(requested[0], footer_key_id);
+    assert!(requested.contains(&column_key_id));
+    assert!(
+        requested
+            .iter()
+            .all(|key_id| *key_id == footer_key_id || *key_id == column_key_id)
+    );
+}
+
+#[test]
+fn test_read_file_aad() {
+    let batch = RecordBatch::try_from_iter(vec![(
+        "x",
+        Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+    )])
+    .unwrap();
+
+    let read_metadata = |file_encryption_properties: Arc<FileEncryptionProperties>| {
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(file_encryption_properties)
+            .build();
+        let mut file_bytes = Vec::new();
+        let mut writer =
+            ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .build()
+            .unwrap();
+        let options =
+            ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+        let metadata = ArrowReaderMetadata::load(&bytes::Bytes::from(file_bytes), options).unwrap();
+        Arc::clone(metadata.metadata())
+    };
+
+    let metadata = read_metadata(
+        FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_uniform_encryption()
+            .with_aad_prefix(b"prefix".into())
+            .with_aad_prefix_storage(true)
+            .with_aad_file_unique(b"unique01".into())
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(metadata.aad_file_unique().unwrap(), b"unique01");
+    assert_eq!(metadata.file_aad().unwrap(), b"prefixunique01");
+
+    // A random file unique AAD is generated when not specified
+    let metadata = read_metadata(
+        FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_uniform_encryption()
+            .build()
+            .unwrap(),
+    );
+    let aad_file_unique = metadata.aad_file_unique().unwrap();
+    assert_eq!(aad_file_unique.len(), 8);
+    assert_eq!(metadata.file_aad().unwrap(), aad_file_unique);
+
+    // Files that aren't decrypted have no file AAD
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    let metadata =
+        ArrowReaderMetadata::load(&bytes::Bytes::from(file_bytes), Default::default()).unwrap();
+    assert!(metadata.metadata().file_aad().is_none());
+    assert!(metadata.metadata().aad_file_unique().is_none());
+}
+
+#[test]
+fn test_read_aad_file_unique_lengths() {
+    // Other writers may use a file unique AAD that isn't 8 bytes long,
+    // so the length stored in the file must be used to reconstruct the file AAD
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(Int32Array::from_iter_values(100..200)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    for aad_file_unique_len in [1, 4, 12, 16, 32] {
+        for encrypt_footer in [true, false] {
+            let aad_file_unique: Vec<u8> = (0..aad_file_unique_len as u8).collect();
+            let encryption_properties =
+                FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+                    .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+                    .with_plaintext_footer(!encrypt_footer)
+                    .with_aad_prefix(b"prefix".into())
+                    .with_aad_prefix_storage(true)
+                    .with_aad_file_unique(aad_file_unique.clone())
+                    .build()
+                    .unwrap();
+            let props = WriterProperties::builder()
+                .with_file_encryption_properties(encryption_properties)
+                .build();
+            let mut file_bytes = Vec::new();
+            let mut writer =
+                ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+            writer.write(&batch).unwrap();
+            writer.close().unwrap();
+
+            let decryption_properties =
+                FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+                    .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+                    .build()
+                    .unwrap();
+            let options = ArrowReaderOptions::default()
+                .with_file_decryption_properties(decryption_properties)
+                .with_page_index_policy(PageIndexPolicy::Required);
+            let builder = ParquetRecordBatchReaderBuilder::try_new_with_options(
+                bytes::Bytes::from(file_bytes),
+                options,
+            )
+            .unwrap();
+            let metadata = builder.metadata();
+            assert_eq!(metadata.aad_file_unique().unwrap(), aad_file_unique);
+            assert_eq!(
+                metadata.file_aad().unwrap(),
+                [b"prefix".as_slice(), &aad_file_unique].concat()
+            );
+            assert!(metadata.offset_index().is_some());
+
+            let batches = builder
+                .build()
+                .unwrap()
+                .collect::<ArrowResult<Vec<_>>>()
+                .unwrap();
+            assert_eq!(batches, vec![batch.clone()]);
+        }
+    }
+}
+
+#[test]
+fn test_footer_key_with_metadata() {
+    let batch = int32_batch(&["x"], 10);
+
+    let footer_key = EncryptionKey::new_with_metadata(AES_128_FOOTER_KEY.into(), b"kf".into());
+    let file_encryption_properties = FileEncryptionProperties::builder(Vec::new())
+        .with_uniform_encryption()
+        .with_footer_key(footer_key)
+        .build()
+        .unwrap();
+
+    let file = write_batch(&batch, Some(file_encryption_properties));
+
+    // The footer key can only be found by the retriever using the metadata written to the file
+    let key_retriever = TestKeyRetriever::new().with_key("kf".into(), AES_128_FOOTER_KEY.into());
+    let decryption_properties =
+        FileDecryptionProperties::with_key_retriever(Arc::new(key_retriever))
+            .build()
+            .unwrap();
+    let (batches, _) = encryption_util::read_encrypted_file(&file, decryption_properties).unwrap();
+    assert_eq!(batches, vec![batch]);
+}
+
+#[test]
+fn test_key_id_roundtrip() {
+    let batch = int32_batch(&["x", "y"], 10);
+
+    let footer_key = EncryptionKey::new(AES_128_FOOTER_KEY.into()).with_key_id("kf");
+    let column_key = EncryptionKey::new(AES_128_COLUMN_KEYS[0].into()).with_key_id("kc1");
+    let file_encryption_properties = FileEncryptionProperties::builder(Vec::new())
+        .with_footer_key(footer_key)
+        .with_column_key_and_metadata(
+            "x",
+            column_key.key().to_vec(),
+            column_key.key_metadata().unwrap().to_vec(),
+        )
+        .build()
+        .unwrap();
+
+    let file = write_batch(&batch, Some(file_encryption_properties));
+
+    let key_retriever = TestKeyRetriever::new()
+        .with_key("kf".into(), AES_128_FOOTER_KEY.into())
+        .with_key("kc1".into(), AES_128_COLUMN_KEYS[0].into());
+    let decryption_properties =
+        FileDecryptionProperties::with_key_retriever(Arc::new(key_retriever))
+            .build()
+            .unwrap();
+    let (batches, metadata) =
+        encryption_util::read_encrypted_file(&file, decryption_properties).unwrap();
+    assert_eq!(batches, vec![batch]);
+
+    let columns = metadata.metadata().row_group(0).columns();
+    match columns[0].crypto_metadata() {
+        Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(column_key_encryption)) => {
+            assert_eq!(column_key_encryption.key_id(), Some("kc1"));
+        }
+        other => panic!("Unexpected crypto metadata for column x: {other:?}"),
+    }
+    assert!(columns[1].crypto_metadata().is_none());
+}
+
+#[test]
+fn test_read_column_key_metadata() {
+    use parquet::encryption::decrypt::KeyRetriever;
+
+    #[derive(Debug, Default)]
+    struct RecordingKeyRetriever {
+        requested: std::sync::Mutex<Vec<Vec<u8>>>,
+    }
+
+    impl KeyRetriever for RecordingKeyRetriever {
+        fn retrieve_key(&self, key_metadata: &[u8]) -> parquet::errors::Result<Vec<u8>> {
+            self.requested.lock().unwrap().push(key_metadata.to_vec());
+            match key_metadata {
+                b"kf" => Ok(AES_128_FOOTER_KEY.to_vec()),
+                b"kc1" => Ok(AES_128_COLUMN_KEYS[0].to_vec()),
+                b"kc2" => Ok(AES_128_COLUMN_KEYS[1].to_vec()),
+                _ => Err(ParquetError::General("Unknown key".to_string())),
+            }
+        }
+    }
+
+    let struct_fields = vec![Field::new("a", DataType::Int32, false)];
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..50)) as ArrayRef,
+        ),
+        (
+            "s",
+            Arc::new(StructArray::new(
+                struct_fields.into(),
+                vec![Arc::new(Int32Array::from_iter_values(50..100)) as ArrayRef],
+                None,
+            )) as ArrayRef,
+        ),
+        (
+            "z",
+            Arc::new(Int32Array::from_iter_values(100..150)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_footer_key_metadata(b"kf".into())
+        .with_column_key_and_metadata("x", AES_128_COLUMN_KEYS[0].into(), b"kc1".into())
+        .with_column_key_and_metadata("s.a", AES_128_COLUMN_KEYS[1].into(), b"kc2".into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let key_retriever = Arc::new(RecordingKeyRetriever::default());
+    let decryption_properties = FileDecryptionProperties::with_key_retriever(key_retriever.clone())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let builder = ParquetRecordBatchReaderBuilder::try_new_with_options(
+        bytes::Bytes::from(file_bytes),
+        options,
+    )
+    .unwrap();
+
+    let columns = builder.metadata().row_group(0).columns();
+    let column_key = |column: &ColumnChunkMetaData| match column.crypto_metadata() {
+        Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(column_key_encryption)) => Some((
+            column_key_encryption.path_in_schema.clone(),
+            column_key_encryption.key_metadata.clone(),
+        )),
+        _ => None,
+    };
+    assert_eq!(
+        column_key(&columns[0]),
+        Some((vec!["x".into()], Some(b"kc1".to_vec())))
+    );
+    assert_eq!(
+        column_key(&columns[1]),
+        Some((vec!["s".into(), "a".into()], Some(b"kc2".to_vec())))
+    );
+    assert!(columns[2].crypto_metadata().is_none());
+
+    let batches = builder
+        .build()
+        .unwrap()
+        .collect::<ArrowResult<Vec<_>>>()
+        .unwrap();
+    assert_eq!(batches, vec![batch]);
+
+    // Each column key was looked up using the key metadata read from the file
+    let requested = key_retriever.requested.lock().unwrap();
+    for key_metadata in [&b"kf"[..], b"kc1", b"kc2"] {
+        assert!(
+            requested.iter().any(|r| r == key_metadata),
+            "key metadata {key_metadata:?} was not requested: {requested:?}"
+        );
+    }
+}
+
+#[test]
+fn test_roundtrip_with_shared_keys() {
+    // Keys held in a shared store can back many files without being copied
+    let footer_key: Arc<[u8]> = Arc::from(AES_128_FOOTER_KEY.as_slice());
+    let column_key: Arc<[u8]> = Arc::from(AES_128_COLUMN_KEYS[0].as_slice());
+
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(Int32Array::from_iter_values(100..200)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    for _ in 0..2 {
+        let encryption_properties = FileEncryptionProperties::builder(Vec::new())
+            .with_footer_key(EncryptionKey::from_arc(Arc::clone(&footer_key)).with_key_id("kf"))
+            .with_column_encryption_key(
+                "x",
+                EncryptionKey::from_arc(Arc::clone(&column_key)).with_key_id("kc1"),
+            )
+            .build()
+            .unwrap();
+        let file = write_batch(&batch, Some(encryption_properties));
+
+        let key_retriever = TestKeyRetriever::new()
+            .with_key("kf".into(), footer_key.to_vec())
+            .with_key("kc1".into(), column_key.to_vec());
+        let decryption_properties =
+            FileDecryptionProperties::with_key_retriever(Arc::new(key_retriever))
+                .build()
+                .unwrap();
+        let (batches, metadata) =
+            encryption_util::read_encrypted_file(&file, decryption_properties).unwrap();
+        assert_eq!(batches, vec![batch.clone()]);
+        let columns = metadata.metadata().row_group(0).columns();
+        assert!(columns[0].crypto_metadata().is_some());
+        assert!(columns[1].crypto_metadata().is_none());
+    }
+}
+
+#[test]
+fn test_encrypted_footer_range() {
+    let batch = RecordBatch::try_from_iter(vec![(
+        "x",
+        Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+    )])
+    .unwrap();
+
+    for encrypt_footer in [true, false] {
+        let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_uniform_encryption()
+            .with_plaintext_footer(!encrypt_footer)
+            .build()
+            .unwrap();
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(encryption_properties.clone())
+            .build();
+        let mut file_bytes = Vec::new();
+        let mut writer =
+            ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        assert_eq!(writer.footer_range(), None);
+        writer.finish().unwrap();
+        let footer_range = writer.footer_range().unwrap();
+        drop(writer);
+
+        // The footer is followed by its length and the magic number
+        let footer_start = footer_range.start as usize;
+        let footer_end = footer_range.end as usize;
+        assert_eq!(footer_end + 8, file_bytes.len());
+        let footer_len =
+            u32::from_le_bytes(file_bytes[footer_end..footer_end + 4].try_into().unwrap());
+        assert_eq!(footer_len as usize, footer_end - footer_start);
+        let magic: &[u8] = if encrypt_footer { b"PARE" } else { b"PAR1" };
+        assert_eq!(&file_bytes[footer_end + 4..], magic);
+
+        let file_bytes = bytes::Bytes::from(file_bytes);
+        let decryption_properties = encryption_properties.to_decryption_properties().unwrap();
+        let options =
+            ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+        let metadata = ArrowReaderMetadata::load(&file_bytes, options).unwrap();
+        assert_eq!(metadata.metadata().encrypt_footer(), encrypt_footer);
+        // Column chunk data precedes the footer
+        let column = metadata.metadata().row_group(0).column(0);
+        let (column_start, column_len) = column.byte_range();
+        assert!(column_start + column_len <= footer_range.start);
+    }
+}
+
+#[test]
+fn test_write_without_column_key_metadata() {
+    let batch = int32_batch(&["x", "y"], 100);
+    let column_key_id = b"kms-column-key-x";
+
+    let write_file = |store_key_metadata: bool| {
+        // Use a plaintext footer so that the column crypto metadata isn't encrypted
+        let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_plaintext_footer(true)
+            .with_column_key_and_metadata(
+                "x",
+                AES_128_COLUMN_KEYS[0].into(),
+                column_key_id.to_vec(),
+            )
+            .with_store_key_metadata(store_key_metadata)
+            .build()
+            .unwrap();
+        write_batch(&batch, Some(encryption_properties))
+    };
+    let contains_key_id = |file_bytes: &[u8]| {
+        file_bytes
+            .windows(column_key_id.len())
+            .any(|window| window == column_key_id)
+    };
+
+    let file_bytes = write_file(true);
+    assert!(contains_key_id(&file_bytes));
+
+    let file_bytes = write_file(false);
+    assert!(!contains_key_id(&file_bytes));
+
+    // Readers must know the column key without the key metadata
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let builder =
+        ParquetRecordBatchReaderBuilder::try_new_with_options(file_bytes, options).unwrap();
+    match builder.metadata().row_group(0).column(0).crypto_metadata() {
+        Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(column_key_encryption)) => {
+            assert_eq!(column_key_encryption.path_in_schema, vec!["x".to_owned()]);
+            assert_eq!(column_key_encryption.key_metadata, None);
+        }
+        other => panic!("Unexpected crypto metadata for column x: {other:?}"),
+    }
+    let batches = builder
+        .build()
+        .unwrap()
+        .collect::<ArrowResult<Vec<_>>>()
+        .unwrap();
+    assert_eq!(batches, vec![batch]);
+}
+
+#[test]
+fn test_write_empty_encrypted_file() {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new("y", DataType::Utf8, true),
+    ]));
+    let empty_batch = RecordBatch::new_empty(schema.clone());
+
+    let uniform_encryption = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .build()
+        .unwrap();
+    let column_keys = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_plaintext_footer(true)
+        .build()
+        .unwrap();
+
+    for encryption_properties in [uniform_encryption, column_keys] {
+        let read_file = |file_bytes: Vec<u8>| {
+            let decryption_properties = encryption_properties.to_decryption_properties().unwrap();
+            let options = ArrowReaderOptions::default()
+                .with_file_decryption_properties(decryption_properties)
+                .with_page_index_policy(PageIndexPolicy::Optional);
+            let builder = ParquetRecordBatchReaderBuilder::try_new_with_options(
+                bytes::Bytes::from(file_bytes),
+                options,
+            )
+            .unwrap();
+            assert_eq!(builder.schema().fields(), schema.fields());
+            assert_eq!(builder.metadata().file_metadata().num_rows(), 0);
+            let metadata = Arc::clone(builder.metadata());
+            let batches = builder
+                .build()
+                .unwrap()
+                .collect::<ArrowResult<Vec<_>>>()
+                .unwrap();
+            let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+            assert_eq!(num_rows, 0);
+            metadata
+        };
+
+        let expected_magic: &[u8] = if encryption_properties.encrypt_footer() {
+            b"PARE"
+        } else {
+            b"PAR1"
+        };
+
+        // No row groups are written for an empty stream of batches
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(encryption_properties.clone())
+            .build();
+        let mut file_bytes = Vec::new();
+        let mut writer =
+            ArrowWriter::try_new(&mut file_bytes, schema.clone(), Some(props)).unwrap();
+        writer.write(&empty_batch).unwrap();
+        writer.close().unwrap();
+        assert_eq!(&file_bytes[..4], expected_magic);
+        assert_eq!(&file_bytes[file_bytes.len() - 4..], expected_magic);
+        let metadata = read_file(file_bytes);
+        assert_eq!(metadata.num_row_groups(), 0);
+        assert_eq!(
+            metadata.encrypt_footer(),
+            encryption_properties.encrypt_footer()
+        );
+
+        // A row group without any pages still has encrypted column chunk metadata
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(encryption_properties.clone())
+            .build();
+        let parquet_schema = parse_message_type(
+            "message schema { REQUIRED INT32 x; OPTIONAL BYTE_ARRAY y (UTF8); }",
+        )
+        .unwrap();
+        let mut file_bytes = Vec::new();
+        let mut writer =
+            SerializedFileWriter::new(&mut file_bytes, Arc::new(parquet_schema), Arc::new(props))
+                .unwrap();
+        let mut row_group_writer = writer.next_row_group().unwrap();
+        while let Some(column_writer) = row_group_writer.next_column().unwrap() {
+            column_writer.close().unwrap();
+        }
+        row_group_writer.close().unwrap();
+        writer.close().unwrap();
+        assert_eq!(&file_bytes[..4], expected_magic);
+        assert_eq!(&file_bytes[file_bytes.len() - 4..], expected_magic);
+        let metadata = read_file(file_bytes);
+        assert_eq!(metadata.num_row_groups(), 1);
+        let row_group = metadata.row_group(0);
+        assert_eq!(row_group.num_rows(), 0);
+        for column in row_group.columns() {
+            assert_eq!(column.num_values(), 0);
+        }
+        // Column x is encrypted in both configurations, column y only with uniform encryption
+        assert!(row_group.column(0).crypto_metadata().is_some());
+        assert_eq!(
+            row_group.column(1).crypto_metadata().is_some(),
+            encryption_properties.encrypt_footer()
+        );
+    }
+}
+
+#[test]
+fn test_read_across_key_rotation() {
+    let batch = int32_batch(&["x", "y"], 10);
+
+    // Files in the same dataset written before and after rotating the footer and column keys
+    let write_file = |footer_key: &[u8],
+                      footer_key_id: &str,
+                      column_key: &[u8],
+                      column_key_id: &str| {
+        let file_encryption_properties = FileEncryptionProperties::builder(Vec::new())
+            .with_footer_key(EncryptionKey::new(footer_key.into()).with_key_id(footer_key_id))
+            .with_column_key_and_metadata("x", column_key.into(), column_key_id.as_bytes().into())
+            .build()
+            .unwrap();
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(file_encryption_properties)
+            .set_max_row_group_row_count(Some(5))
+            .build();
+        write_batch_with_properties(&batch, props)
+    };
+    let files = [
+        write_file(AES_128_FOOTER_KEY, "kf_v1", AES_128_COLUMN_KEYS[0], "kc_v1"),
+        write_file(AES_256_FOOTER_KEY, "kf_v2", AES_256_COLUMN_KEYS[0], "kc_v2"),
+    ];
+
+    let key_retriever = Arc::new(
+        TestKeyRetriever::new()
+            .with_key("kf_v1".into(), AES_128_FOOTER_KEY.into())
+            .with_key("kf_v2".into(), AES_256_FOOTER_KEY.into())
+            .with_key("kc_v1".into(), AES_128_COLUMN_KEYS[0].into())
+            .with_key("kc_v2".into(), AES_256_COLUMN_KEYS[0].into()),
+    );
+    let decryption_properties = FileDecryptionProperties::with_key_retriever(key_retriever)
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+
+    for file in files {
+        let metadata = ArrowReaderMetadata::load(&file, options.clone()).unwrap();
+        assert_eq!(metadata.metadata().num_row_groups(), 2);
+        let batches = ParquetRecordBatchReaderBuilder::new_with_metadata(file, metadata)
+            .build()
+            .unwrap()
+            .collect::<ArrowResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            arrow::compute::concat_batches(&batch.schema(), &batches).unwrap(),
+            batch
+        );
+    }
+}
+
+#[test]
+fn test_plaintext_footer_signature_with_rotated_signing_key() {
+    use parquet::encryption::decrypt::KeyRetriever;
+
+    struct RecordingKeyRetriever {
+        keys: HashMap<Vec<u8>, Vec<u8>>,
+        requested: std::sync::Mutex<Vec<Vec<u8>>>,
+    }
+
+    impl KeyRetriever for RecordingKeyRetriever {
+        fn retrieve_key(&self, key_metadata: &[u8]) -> parquet::errors::Result<Vec<u8>> {
+            self.requested.lock().unwrap().push(key_metadata.to_vec());
+            self.keys
+                .get(key_metadata)
+                .cloned()
+                .ok_or_else(|| ParquetError::General("Unknown key".to_string()))
+        }
+    }
+
+    let batch = int32_batch(&["x", "y"], 10);
+
+    // Plaintext footers signed before and after rotating the footer key
+    let write_file = |footer_key: &[u8], footer_key_id: &KeyId| {
+        let file_encryption_properties = FileEncryptionProperties::builder(footer_key.into())
+            .with_footer_key_id(footer_key_id)
+            .with_column_key_and_metadata("x", AES_128_COLUMN_KEYS[0].into(), b"kc1".into())
+            .with_plaintext_footer(true)
+            .build()
+            .unwrap();
+        write_batch(&batch, Some(file_encryption_properties))
+    };
+    let key_v1 = KeyId::new("kf", 1);
+    let key_v2 = KeyId::new("kf", 2);
+    let files = [
+        (key_v1.clone(), write_file(AES_128_FOOTER_KEY, &key_v1)),
+        (key_v2.clone(), write_file(AES_256_FOOTER_KEY, &key_v2)),
+    ];
+
+    let read_metadata = |file: &bytes::Bytes, signing_keys: Vec<(&KeyId, &[u8])>| {
+        let mut keys: HashMap<_, _> = signing_keys
+            .into_iter()
+            .map(|(key_id, key)| (key_id.to_key_metadata(), key.to_vec()))
+            .collect();
+        keys.insert(b"kc1".to_vec(), AES_128_COLUMN_KEYS[0].to_vec());
+        let key_retriever = Arc::new(RecordingKeyRetriever {
+            keys,
+            requested: Default::default(),
+        });
+        let decryption_properties =
+            FileDecryptionProperties::with_key_retriever(key_retriever.clone())
+                .build()
+                .unwrap();
+        let options =
+            ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+        let result = ArrowReaderMetadata::load(file, options);
+        let requested = key_retriever.requested.lock().unwrap().clone();
+        (result, requested)
+    };
+
+    let signing_keys = vec![
+        (&key_v1, AES_128_FOOTER_KEY.as_slice()),
+        (&key_v2, AES_256_FOOTER_KEY.as_slice()),
+    ];
+    for (key_id, file) in &files {
+        // The signing key is resolved from the footer signing key metadata and verifies the footer
+        let (result, requested) = read_metadata(file, signing_keys.clone());
+        let metadata = result.unwrap();
+        assert_eq!(requested[0], key_id.to_key_metadata());
+        assert!(!metadata.metadata().encrypt_footer());
+        assert_eq!(metadata.metadata().num_row_groups(), 1);
+    }
+
+    // Resolving the key ID of the rotated key to the old key fails verification
+    let (result, requested) =
+        read_metadata(&files[1].1, vec![(&key_v2, AES_128_FOOTER_KEY.as_slice())]);
+    assert_eq!(requested, vec![key_v2.to_key_metadata()]);
+    let err = result.unwrap_err().to_string();
+    assert!(
+        err.starts_with("Parquet error: Footer signature verification failed"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_in_memory_roundtrip() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "id",
+            Arc::new(Int64Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "name",
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("name {i}")),
+            )) as ArrayRef,
+        ),
+        (
+            "score",
+            Arc::new(Float64Array::from_iter(
+                (0..100).map(|i| (i % 3 != 0).then_some(i as f64 / 2.0)),
+            )) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+    let batches = vec![batch.slice(0, 50), batch.slice(50, 50)];
+
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("name", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("score", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let encrypted = encrypt_record_batches(&batches, encryption_properties).unwrap();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("name", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("score", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let read_batches = decrypt_record_batches(encrypted.clone(), decryption_properties).unwrap();
+    assert_eq!(
+        arrow::compute::concat_batches(&batch.schema(), &read_batches).unwrap(),
+        batch
+    );
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("name", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let err = decrypt_record_batches(encrypted, decryption_properties).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("No column decryption key set for encrypted column 'score'"),
+        "{err}"
+    );
+
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .build()
+        .unwrap();
+    let err = encrypt_record_batches(&[], encryption_properties).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Parquet error: At least one record batch is required to write an encrypted file"
+    );
+}
+
+#[test]
+fn test_plaintext_footer_metadata_without_keys() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(Int32Array::from_iter_values(10..20)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_plaintext_footer(true)
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    let file_bytes = bytes::Bytes::from(file_bytes);
+
+    let footer_key_only = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .build()
+        .unwrap();
+    for (decryption_properties, expected_error) in [
+        (
+            None,
+            "Column 'x' is encrypted but file decryption properties were not provided",
+        ),
+        (
+            Some(footer_key_only),
+            "No column decryption key set for encrypted column 'x'",
+        ),
+    ] {
+        let mut options =
+            ArrowReaderOptions::default().with_page_index_policy(PageIndexPolicy::Optional);
+        if let Some(decryption_properties) = decryption_properties {
+            options = options.with_file_decryption_properties(decryption_properties);
+        }
+        let metadata = ArrowReaderMetadata::load(&file_bytes, options).unwrap();
+
+        // The schema and statistics of the plaintext column are available without column keys
+        assert_eq!(metadata.schema().fields(), batch.schema().fields());
+        let row_group = metadata.metadata().row_group(0);
+        assert_eq!(row_group.num_rows(), 10);
+        let y_stats = row_group.column(1).statistics().unwrap();
+        assert_eq!(y_stats.min_bytes_opt().unwrap(), 10i32.to_le_bytes());
+        assert_eq!(y_stats.max_bytes_opt().unwrap(), 19i32.to_le_bytes());
+
+        let mask = ProjectionMask::leaves(metadata.parquet_schema(), [1]);
+        let batches = ParquetRecordBatchReaderBuilder::new_with_metadata(
+            file_bytes.clone(),
+            metadata.clone(),
+        )
+        .with_projection(mask)
+        .build()
+        .unwrap()
+        .collect::<ArrowResult<Vec<_>>>()
+        .unwrap();
+        assert_eq!(batches, vec![batch.project(&[1]).unwrap()]);
+
+        // Reading the encrypted column requires its key
+        let mask = ProjectionMask::leaves(metadata.parquet_schema(), [0]);
+        let result =
+            ParquetRecordBatchReaderBuilder::new_with_metadata(file_bytes.clone(), metadata)
+                .with_projection(mask)
+                .build()
+                .and_then(|reader| Ok(reader.collect::<ArrowResult<Vec<_>>>()?));
+        let err = result.unwrap_err();
+        assert!(
+            err.to_string().contains(expected_error),
+            "unexpected error: {err}"
+        );
+    }
+}
+
+#[test]
+fn test_read_without_decryption_properties() {
+    let batch = int32_batch(&["x", "y"], 10);
+
+    // Encrypted footer
+    let file = write_batch(
+        &batch,
+        Some(
+            FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+                .with_uniform_encryption()
+                .build()
+                .unwrap(),
+        ),
+    );
+    let err = ArrowReaderMetadata::load(&file, Default::default()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Parquet error: Parquet file has an encrypted footer but decryption properties were not provided"
+    );
+
+    // Plaintext footer with an encrypted column
+    let file = write_batch(
+        &batch,
+        Some(
+            FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+                .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+                .with_plaintext_footer(true)
+                .build()
+                .unwrap(),
+        ),
+    );
+    let metadata = ArrowReaderMetadata::load(&file, Default::default()).unwrap();
+
+    // The unencrypted column can still be read
+    let mask = ProjectionMask::leaves(metadata.metadata().file_metadata().schema_descr(), [1]);
+    let batches =
+        ParquetRecordBatchReaderBuilder::new_with_metadata(file.clone(), metadata.clone())
+            .with_projection(mask)
+            .build()
+            .unwrap()
+            .collect::<ArrowResult<Vec<_>>>()
+            .unwrap();
+    assert_eq!(batches, vec![batch.project(&[1]).unwrap()]);
+
+    let err = ParquetRecordBatchReaderBuilder::new_with_metadata(file, metadata)
+        .build()
+        .unwrap()
+        .collect::<ArrowResult<Vec<_>>>()
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Column 'x' is encrypted but file decryption properties were not provided"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_share_decryption_properties_between_readers() {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new("y", DataType::Int32, false),
+    ]));
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+
+    let files_and_batches: Vec<_> = (0..2)
+        .map(|i| {
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from_iter_values(i * 10..(i + 1) * 10)),
+                    Arc::new(Int32Array::from_iter_values(i * 100..i * 100 + 10)),
+                ],
+            )
+            .unwrap();
+            let file = write_batch(&batch, Some(file_encryption_properties.clone()));
+            (file, batch)
+        })
+        .collect();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let options = ArrowReaderOptions::default()
+        .with_file_decryption_properties(decryption_properties.clone());
+
+    // Open both readers before reading from either
+    let readers: Vec<_> = files_and_batches
+        .iter()
+        .map(|(file, _)| {
+            ParquetRecordBatchReaderBuilder::try_new_with_options(file.clone(), options.clone())
+                .unwrap()
+                .build()
+                .unwrap()
+        })
+        .collect();
+    assert!(Arc::strong_count(&decryption_properties) > 2);
+
+    for (reader, (_, batch)) in readers.into_iter().zip(&files_and_batches) {
+        let batches = reader.collect::<ArrowResult<Vec<_>>>().unwrap();
+        assert_eq!(batches, vec![batch.clone()]);
+    }
+}
+
+#[test]
+fn test_encrypted_footer_with_plaintext_columns() {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new("y", DataType::Utf8, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from((0..10).collect::<Vec<_>>())),
+            Arc::new(StringArray::from_iter_values(
+                (0..10).map(|i| format!("plaintext value {i}")),
+            )),
+        ],
+    )
+    .unwrap();
+
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_plaintext_columns(true)
+        .build()
+        .unwrap();
+    assert!(file_encryption_properties.plaintext_columns());
+
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(file_encryption_properties)
+        .set_dictionary_enabled(false)
+        .build();
+    let file_bytes = write_batch_with_properties(&batch, props);
+
+    // Column data is written in plaintext
+    let needle = b"plaintext value 7";
+    assert!(file_bytes.windows(needle.len()).any(|w| w == needle));
+
+    // but the footer requires the key
+    let err = ArrowReaderMetadata::load(&file_bytes, Default::default()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Parquet error: Parquet file has an encrypted footer but decryption properties were not provided"
+    );
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let metadata = ArrowReaderMetadata::load(&file_bytes, options).unwrap();
+    for column in metadata.metadata().row_group(0).columns() {
+        assert!(column.crypto_metadata().is_none());
+    }
+    let batches = ParquetRecordBatchReaderBuilder::new_with_metadata(file_bytes, metadata)
+        .build()
+        .unwrap()
+        .collect::<ArrowResult<Vec<_>>>()
+        .unwrap();
+    assert_eq!(batches, vec![batch]);
+}
+
+#[test]
+fn test_uniform_encryption_with_unencrypted_column() {
+    let batch = int32_batch(&["x", "y"], 10);
+
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .with_unencrypted_column("x")
+        .build()
+        .unwrap();
+    let file = write_batch(&batch, Some(file_encryption_properties));
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .build()
+        .unwrap();
+    let (batches, metadata) =
+        encryption_util::read_encrypted_file(&file, decryption_properties).unwrap();
+    assert_eq!(batches, vec![batch]);
+
+    let row_group = metadata.metadata().row_group(0);
+    assert!(row_group.column(0).crypto_metadata().is_none());
+    assert!(row_group.column(1).crypto_metadata().is_some());
+
+    // Unencrypted columns can't be combined with column keys
+    let result = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[0].into())
+        .with_unencrypted_column("x")
+        .build();
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Parquet error: Unencrypted columns can only be specified when no column keys are set"
+    );
+
+    // Unencrypted columns must exist in the schema
+    let schema = Arc::new(Schema::new(vec![Field::new("x", DataType::Int32, false)]));
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .with_unencrypted_column("z")
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(file_encryption_properties)
+        .build();
+    let result = ArrowWriter::try_new(Vec::new(), schema, Some(props));
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Parquet error: The following columns specified as unencrypted were not found in the schema: z"
+    );
+}
+
+#[test]
+fn test_write_encrypted_bloom_filter() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("value {i}")),
+            )) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let encryption_properties = [
+        FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_uniform_encryption()
+            .build()
+            .unwrap(),
+        FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+            .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+            .build()
+            .unwrap(),
+        FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+            .with_algorithm(EncryptionAlgorithm::AesGcmCtrV1)
+            .build()
+            .unwrap(),
+    ];
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+
+    for encryption_properties in encryption_properties {
+        let props = WriterProperties::builder()
+            .set_bloom_filter_enabled(true)
+            .with_file_encryption_properties(encryption_properties)
+            .build();
+        let mut file_bytes = Vec::new();
+        let mut writer =
+            ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let file_bytes = bytes::Bytes::from(file_bytes);
+
+        let options = ArrowReaderOptions::default()
+            .with_file_decryption_properties(decryption_properties.clone());
+        let builder =
+            ParquetRecordBatchReaderBuilder::try_new_with_options(file_bytes.clone(), options)
+                .unwrap();
+
+        let row_group = builder.metadata().row_group(0).clone();
+        for (column_idx, column) in row_group.columns().iter().enumerate() {
+            let encrypted = column.crypto_metadata().is_some();
+            let offset = column.bloom_filter_offset().unwrap() as usize;
+
+            // Encrypted bloom filters are stored as a length prefixed header module
+            // followed by the bitset module, so the header is not readable as plaintext.
+            let header_len = u32::from_le_bytes(file_bytes[offset..offset + 4].try_into().unwrap());
+            if encrypted {
+                let bitset_offset = offset + 4 + header_len as usize;
+                let bitset_len = u32::from_le_bytes(
+                    file_bytes[bitset_offset..bitset_offset + 4]
+                        .try_into()
+                        .unwrap(),
+                );
+                assert_eq!(
+                    column.bloom_filter_length(),
+                    Some(8 + header_len as i32 + bitset_len as i32)
+                );
+            }
+
+            let sbbf = builder
+                .get_row_group_column_bloom_filter(0, column_idx)
+                .unwrap()
+                .unwrap();
+            match column_idx {
+                0 => {
+                    assert!(sbbf.check(&42_i32));
+                    assert!(!sbbf.check(&1000_i32));
+                }
+                _ => {
+                    assert!(sbbf.check(&"value 42"));
+                    assert!(!sbbf.check(&"value 1000"));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_encrypted_bloom_filter_without_length_rejects_bad_length_prefixes() {
+    let batch = int32_batch(&["x"], 100);
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .set_bloom_filter_enabled(true)
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+    let file_bytes = write_batch_with_properties(&batch, props);
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let reader_metadata = ArrowReaderMetadata::load(&file_bytes, options.clone()).unwrap();
+
+    // Drop the bloom filter length from the metadata, so that the reader has to
+    // rely on the unauthenticated length prefixes of the encrypted modules
+    let mut metadata_builder = reader_metadata.metadata().as_ref().clone().into_builder();
+    let mut row_group = metadata_builder.take_row_groups().remove(0).into_builder();
+    let mut columns = row_group.take_columns();
+    let offset = columns[0].bloom_filter_offset().unwrap() as usize;
+    columns[0] = columns[0]
+        .clone()
+        .into_builder()
+        .set_bloom_filter_length(None)
+        .build()
+        .unwrap();
+    let row_group = row_group.set_column_metadata(columns).build().unwrap();
+    let metadata = Arc::new(metadata_builder.add_row_group(row_group).build());
+    let reader_metadata = ArrowReaderMetadata::try_new(metadata, options).unwrap();
+
+    let read_bloom_filter = |file_bytes: bytes::Bytes| {
+        ParquetRecordBatchReaderBuilder::new_with_metadata(file_bytes, reader_metadata.clone())
+            .get_row_group_column_bloom_filter(0, 0)
+    };
+
+    let sbbf = read_bloom_filter(file_bytes.clone()).unwrap().unwrap();
+    assert!(sbbf.check(&42_i32));
+
+    let header_length = u32::from_le_bytes(file_bytes[offset..offset + 4].try_into().unwrap());
+    let bitset_offset = offset + 4 + header_length as usize;
+    let with_length_prefix = |at: usize, length: u32| {
+        let mut corrupted = file_bytes.to_vec();
+        corrupted[at..at + 4].copy_from_slice(&length.to_le_bytes());
+        bytes::Bytes::from(corrupted)
+    };
+
+    let err = read_bloom_filter(with_length_prefix(offset, u32::MAX)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Parquet error: Encrypted bloom filter module length 4294967295 exceeds the maximum of 134217756"
+    );
+
+    let err = read_bloom_filter(with_length_prefix(bitset_offset, u32::MAX)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Parquet error: Encrypted bloom filter module length 4294967295 exceeds the maximum of 134217756"
+    );
+
+    let err = read_bloom_filter(with_length_prefix(bitset_offset, 1 << 20)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Parquet error: Encrypted bloom filter of {} bytes at offset {offset} exceeds the file length {}",
+            (bitset_offset - offset) + 4 + (1 << 20),
+            file_bytes.len()
+        )
+    );
+}
+
+#[test]
+fn test_validate_decryption_properties() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("value {}", i % 10)),
+            )) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let write_file = |plaintext_footer: bool| {
+        let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+            .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+            .with_plaintext_footer(plaintext_footer)
+            .build()
+            .unwrap();
+        write_batch(&batch, Some(encryption_properties))
+    };
+
+    let decryption_properties = |y_key: Option<&[u8]>| {
+        let mut builder = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_column_key("x", AES_128_COLUMN_KEYS[0].into());
+        if let Some(y_key) = y_key {
+            builder = builder.with_column_key("y", y_key.into());
+        }
+        builder.build().unwrap()
+    };
+
+    for plaintext_footer in [false, true] {
+        let file = write_file(plaintext_footer);
+
+        decryption_properties(Some(AES_128_COLUMN_KEYS[1]))
+            .validate_against(&file)
+            .unwrap();
+
+        let err = decryption_properties(None)
+            .validate_against(&file)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: No column decryption key set for encrypted column 'y'"
+        );
+
+        let err = decryption_properties(Some(AES_128_COLUMN_KEYS[0]))
+            .validate_against(&file)
+            .unwrap_err();
+        assert!(
+            matches!(err, ParquetError::DecryptionKeyError(_)),
+            "unexpected error: {err:?}"
+        );
+        assert_eq!(
+            err.to_string(),
+            format!("Decryption key error: Unable to decrypt column 'y': {AUTHENTICATION_FAILED}")
+        );
+    }
+
+    let err = FileDecryptionProperties::builder(BAD_AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap()
+        .validate_against(&write_file(false))
+        .unwrap_err();
+    assert!(
+        matches!(err, ParquetError::DecryptionKeyError(_)),
+        "unexpected error: {err:?}"
+    );
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Decryption key error: Provided footer key and AAD were unable to decrypt parquet footer: {AUTHENTICATION_FAILED}"
+        )
+    );
+
+    // Validation reads are not reported as key usage
+    let events = Arc::new(AtomicUsize::new(0));
+    let callback_events = Arc::clone(&events);
+    FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .with_key_usage_callback(Arc::new(move |_| {
+            callback_events.fetch_add(1, Ordering::Relaxed);
+        }))
+        .build()
+        .unwrap()
+        .validate_against(&write_file(false))
+        .unwrap();
+    assert_eq!(events.load(Ordering::Relaxed), 0);
+
+    // Corrupt the first page header of column y, whose metadata can still be decrypted
+    let file = write_file(false);
+    let options = ArrowReaderOptions::default()
+        .with_file_decryption_properties(decryption_properties(Some(AES_128_COLUMN_KEYS[1])));
+    let metadata = ArrowReaderMetadata::load(&file, options).unwrap();
+    let column = metadata.metadata().row_group(0).column(1);
+    let header_offset = column
+        .dictionary_page_offset()
+        .unwrap_or(column.data_page_offset()) as usize;
+    let header_len =
+        u32::from_le_bytes(file[header_offset..header_offset + 4].try_into().unwrap()) as usize;
+
+    let mut tampered = file.to_vec();
+    tampered[header_offset + 4 + header_len - 1] ^= 1;
+    let err = decryption_properties(Some(AES_128_COLUMN_KEYS[1]))
+        .validate_against(&bytes::Bytes::from(tampered))
+        .unwrap_err();
+    assert!(matches!(err, ParquetError::DecryptionKeyError(_)), "{err}");
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Decryption key error: Unable to decrypt page header of column 'y': {AUTHENTICATION_FAILED}"
+        )
+    );
+
+    let mut truncated = file.to_vec();
+    truncated[header_offset..header_offset + 4].copy_from_slice(&(file.len() as u32).to_le_bytes());
+    let err = decryption_properties(Some(AES_128_COLUMN_KEYS[1]))
+        .validate_against(&bytes::Bytes::from(truncated))
+        .unwrap_err();
+    assert!(
+        matches!(err, ParquetError::CiphertextFormatError(_)),
+        "unexpected error: {err:?}"
+    );
+    assert!(
+        err.to_string().starts_with(
+            "Ciphertext format error: Unable to decrypt page header of column 'y': \
+            Encrypted module is truncated"
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_wrong_key_errors_are_decryption_key_errors() {
+    let file = write_batch(
+        &int32_batch(&["x", "y"], 10),
+        Some(
+            FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+                .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+                .build()
+                .unwrap(),
+        ),
+    );
+    let load = |footer_key: &[u8], column_key: &[u8]| {
+        let decryption_properties = FileDecryptionProperties::builder(footer_key.into())
+            .with_column_key("x", column_key.into())
+            .build()
+            .unwrap();
+        let options =
+            ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+        ArrowReaderMetadata::load(&file, options).unwrap_err()
+    };
+
+    match load(BAD_AES_128_FOOTER_KEY, AES_128_COLUMN_KEYS[0]) {
+        ParquetError::DecryptionKeyError(message) => assert_eq!(
+            message,
+            format!(
+                "Provided footer key and AAD were unable to decrypt parquet footer: {AUTHENTICATION_FAILED}"
+            )
+        ),
+        e => panic!("unexpected error: {e:?}"),
+    }
+
+    match load(AES_128_FOOTER_KEY, AES_128_COLUMN_KEYS[1]) {
+        ParquetError::DecryptionKeyError(message) => assert_eq!(
+            message,
+            format!("Unable to decrypt column 'x': {AUTHENTICATION_FAILED}")
+        ),
+        e => panic!("unexpected error: {e:?}"),
+    }
+}
+
+#[test]
+fn test_decryption_error_identifies_column_and_page() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(Int32Array::from_iter_values(100..200)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .set_dictionary_enabled(false)
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .build()
+        .unwrap();
+    let metadata = ArrowReaderMetadata::load(
+        &bytes::Bytes::from(file_bytes.clone()),
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties),
+    )
+    .unwrap();
+    let header_offset = metadata
+        .metadata()
+        .row_group(0)
+        .column(1)
+        .data_page_offset() as usize;
+    let header_len = u32::from_le_bytes(
+        file_bytes[header_offset..header_offset + 4]
+            .try_into()
+            .unwrap(),
+    ) as usize;
+    let page_offset = header_offset + 4 + header_len;
+
+    let read_corrupted = |corrupt_offset: usize| {
+        let mut corrupted = file_bytes.clone();
+        corrupted[corrupt_offset] ^= 0xff;
+        let reader = ParquetRecordBatchReaderBuilder::new_with_metadata(
+            bytes::Bytes::from(corrupted),
+            metadata.clone(),
+        )
+        .build()
+        .unwrap();
+        reader
+            .collect::<ArrowResult<Vec<_>>>()
+            .unwrap_err()
+            .to_string()
+    };
+
+    // Corrupt the ciphertext after the length prefix and nonce
+    let err = read_corrupted(header_offset + 20);
+    assert!(
+        err.contains(&format!(
+            "Decryption key error: Error decrypting page header for column 'y' (row group 0, page 0): {AUTHENTICATION_FAILED}"
+        )),
+        "{err}"
+    );
+
+    let err = read_corrupted(page_offset + 20);
+    assert!(
+        err.contains(&format!(
+            "Decryption key error: Error decrypting page for column 'y' (row group 0, page 0): {AUTHENTICATION_FAILED}"
+        )),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_write_encrypted_parquet() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("value {i}")),
+            )) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+    let batches = vec![batch.slice(0, 40), batch.slice(40, 60)];
+
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("encrypted.parquet");
+    write_encrypted_parquet(&path, batch.schema(), &batches, encryption_properties).unwrap();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let reader =
+        ParquetRecordBatchReaderBuilder::try_new_with_options(File::open(&path).unwrap(), options)
+            .unwrap()
+            .build()
+            .unwrap();
+    let read_batches = reader.collect::<ArrowResult<Vec<_>>>().unwrap();
+    assert_eq!(
+        arrow::compute::concat_batches(&batch.schema(), &read_batches).unwrap(),
+        batch
+    );
+
+    let err =
+        ArrowReaderMetadata::load(&File::open(&path).unwrap(), Default::default()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Parquet error: Parquet file has an encrypted footer but decryption properties were not provided"
+    );
+}
+
+#[test]
+fn test_read_encrypted_parquet() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("value {i}")),
+            )) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("encrypted.parquet");
+    write_encrypted_parquet(
+        &path,
+        batch.schema(),
+        std::slice::from_ref(&batch),
+        encryption_properties,
+    )
+    .unwrap();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let (schema, batches) =
+        read_encrypted_parquet(&path, Arc::clone(&decryption_properties)).unwrap();
+    assert_eq!(schema, batch.schema());
+    assert_eq!(batches, vec![batch.clone()]);
+
+    let (schema, batches) =
+        read_encrypted_parquet_with(&path, Arc::clone(&decryption_properties), |builder| {
+            let mask = ProjectionMask::columns(builder.parquet_schema(), ["y"]);
+            builder.with_projection(mask).with_batch_size(30)
+        })
+        .unwrap();
+    let projected = batch.project(&[1]).unwrap();
+    assert_eq!(schema, projected.schema());
+    assert_eq!(
+        batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+        vec![30, 30, 30, 10]
+    );
+    assert_eq!(
+        arrow::compute::concat_batches(&schema, &batches).unwrap(),
+        projected
+    );
+
+    // The key for column "y" is needed, even when it is the only column read
+    let footer_only = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .build()
+        .unwrap();
+    assert!(read_encrypted_parquet(&path, footer_only).is_err());
+}
+
+#[test]
+fn test_read_encrypted_file_from_bytes() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("value {}", i % 7)),
+            )) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    // Small dictionary encoded pages with a page index, so the footer, page headers,
+    // dictionary pages, data pages and page indexes are all decrypted
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .set_statistics_enabled(parquet::file::properties::EnabledStatistics::Page)
+        .set_data_page_row_count_limit(10)
+        .set_write_batch_size(10)
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    let file_bytes = bytes::Bytes::from(file_bytes);
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let options = ArrowReaderOptions::default()
+        .with_file_decryption_properties(decryption_properties)
+        .with_page_index_policy(PageIndexPolicy::Required);
+    let builder =
+        ParquetRecordBatchReaderBuilder::try_new_with_options(file_bytes.clone(), options.clone())
+            .unwrap();
+    let metadata = builder.metadata();
+    assert!(metadata.column_index().is_some());
+    assert!(metadata.offset_index().is_some());
+    let read_batches = builder
+        .build()
+        .unwrap()
+        .collect::<ArrowResult<Vec<_>>>()
+        .unwrap();
+    assert_eq!(
+        arrow::compute::concat_batches(&batch.schema(), &read_batches).unwrap(),
+        batch
+    );
+
+    // Skipping pages reads from arbitrary offsets within the buffer
+    let selection = RowSelection::from(vec![RowSelector::skip(55), RowSelector::select(20)]);
+    let read_batches = ParquetRecordBatchReaderBuilder::try_new_with_options(file_bytes, options)
+        .unwrap()
+        .with_row_selection(selection)
+        .build()
+        .unwrap()
+        .collect::<ArrowResult<Vec<_>>>()
+        .unwrap();
+    assert_eq!(
+        arrow::compute::concat_batches(&batch.schema(), &read_batches).unwrap(),
+        batch.slice(55, 20)
+    );
+}
+
+#[test]
+fn test_swapped_row_group_fails_decryption() {
+    let batch = RecordBatch::try_from_iter(vec![(
+        "x",
+        Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+    )])
+    .unwrap();
+    // Two files with the same keys, AAD prefix and data,
+    // each with two row groups of the same size
+    let write_file = || {
+        let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_uniform_encryption()
+            .with_aad_prefix(b"table".into())
+            .build()
+            .unwrap();
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(encryption_properties)
+            .set_max_row_group_row_count(Some(50))
+            .build();
+        let mut file_bytes = Vec::new();
+        let mut writer =
+            ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch.slice(0, 50)).unwrap();
+        writer.write(&batch.slice(0, 50)).unwrap();
+        writer.close().unwrap();
+        file_bytes
+    };
+    let file = write_file();
+    let other_file = write_file();
+
+    let read = |file_bytes: Vec<u8>| {
+        let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_aad_prefix(b"table".into())
+            .build()
+            .unwrap();
+        let options =
+            ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+        ParquetRecordBatchReaderBuilder::try_new_with_options(
+            bytes::Bytes::from(file_bytes),
+            options,
+        )?
+        .build()?
+        .collect::<ArrowResult<Vec<_>>>()
+        .map_err(ParquetError::from)
+    };
+    let read_rows: usize = read(file.clone())
+        .unwrap()
+        .iter()
+        .map(|b| b.num_rows())
+        .sum();
+    assert_eq!(read_rows, 100);
+
+    let metadata = ArrowReaderMetadata::load(
+        &bytes::Bytes::from(file.clone()),
+        ArrowReaderOptions::default().with_file_decryption_properties(
+            FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+                .with_aad_prefix(b"table".into())
+                .build()
+                .unwrap(),
+        ),
+    )
+    .unwrap();
+    let row_group_ranges: Vec<_> = metadata
+        .metadata()
+        .row_groups()
+        .iter()
+        .map(|row_group| {
+            let (start, len) = row_group.column(0).byte_range();
+            start as usize..(start + len) as usize
+        })
+        .collect();
+    assert_eq!(row_group_ranges.len(), 2);
+    assert_eq!(row_group_ranges[0].len(), row_group_ranges[1].len());
+
+    // A row group copied from another file fails to decrypt, as its file AAD differs
+    let mut from_other_file = file.clone();
+    let range = row_group_ranges[1].clone();
+    from_other_file[range.clone()].copy_from_slice(&other_file[range]);
+    let err = read(from_other_file).unwrap_err();
+    assert!(
+        err.to_string().contains("Error decrypting page header"),
+        "unexpected error: {err}"
+    );
+
+    // Swapping row groups within a file also fails, as the row group ordinal differs
+    let mut swapped = file.clone();
+    let (first, second) = (row_group_ranges[0].clone(), row_group_ranges[1].clone());
+    swapped[first.clone()].copy_from_slice(&file[second.clone()]);
+    swapped[second].copy_from_slice(&file[first]);
+    let err = read(swapped).unwrap_err();
+    assert!(
+        err.to_string().contains("Error decrypting page header"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_roundtrip_with_derived_decryption_properties() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("value {i}")),
+            )) as ArrayRef,
+        ),
+        (
+            "z",
+            Arc::new(Int32Array::from_iter_values(100..200)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .with_aad_prefix(b"derived".into())
+        .build()
+        .unwrap();
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("encrypted.parquet");
+    write_encrypted_parquet(
+        &path,
+        batch.schema(),
+        std::slice::from_ref(&batch),
+        Arc::clone(&encryption_properties),
+    )
+    .unwrap();
+
+    // The AAD prefix isn't stored in the file, so must be copied to the decryption properties
+    let decryption_properties = encryption_properties.to_decryption_properties().unwrap();
+    let (_, batches) = read_encrypted_parquet(&path, decryption_properties).unwrap();
+    assert_eq!(batches, vec![batch]);
+}
+
+#[test]
+#[cfg(feature = "snap")]
+fn test_reencrypt_with_rotated_keys() {
+    use parquet::basic::Compression;
+    use parquet::encryption::key_rotation::reencrypt;
+    use parquet::file::metadata::KeyValue;
+
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("value {i}")),
+            )) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let old_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(Arc::clone(&old_encryption_properties))
+        .set_column_compression(ColumnPath::from("y"), Compression::SNAPPY)
+        .set_key_value_metadata(Some(vec![KeyValue::new(
+            "owner".to_owned(),
+            "team a".to_owned(),
+        )]))
+        .set_max_row_group_row_count(Some(30))
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    let old_metadata = writer.close().unwrap();
+
+    let new_footer_key = b"9876543210987654".to_vec();
+    let new_column_keys = [b"8765432109876543".to_vec(), b"7654321098765432".to_vec()];
+    let new_encryption_properties = FileEncryptionProperties::builder(new_footer_key.clone())
+        .with_column_key("x", new_column_keys[0].clone())
+        .with_column_key("y", new_column_keys[1].clone())
+        .build()
+        .unwrap();
+    let mut rotated = Vec::new();
+    let new_metadata = reencrypt(
+        bytes::Bytes::from(file_bytes),
+        &mut rotated,
+        old_encryption_properties
+            .to_decryption_properties()
+            .unwrap(),
+        Arc::clone(&new_encryption_properties),
+    )
+    .unwrap();
+    let rotated = bytes::Bytes::from(rotated);
+
+    // Row groups, compression, statistics and key-value metadata are kept
+    assert_eq!(row_group_sizes(&new_metadata), vec![30, 30, 30, 10]);
+    for (old_row_group, new_row_group) in old_metadata
+        .row_groups()
+        .iter()
+        .zip(new_metadata.row_groups())
+    {
+        for (old_column, new_column) in old_row_group.columns().iter().zip(new_row_group.columns())
+        {
+            assert_eq!(new_column.compression(), old_column.compression());
+            assert_eq!(new_column.statistics(), old_column.statistics());
+        }
+    }
+    let key_value_metadata = new_metadata.file_metadata().key_value_metadata().unwrap();
+    let owners: Vec<_> = key_value_metadata
+        .iter()
+        .filter(|kv| kv.key == "owner")
+        .map(|kv| kv.value.as_deref())
+        .collect();
+    assert_eq!(owners, vec![Some("team a")]);
+
+    // The new file can only be read with the new keys
+    let old_keys_options = ArrowReaderOptions::default().with_file_decryption_properties(
+        old_encryption_properties
+            .to_decryption_properties()
+            .unwrap(),
+    );
+    assert!(ArrowReaderMetadata::load(&rotated, old_keys_options).is_err());
+
+    let decryption_properties = FileDecryptionProperties::builder(new_footer_key)
+        .with_column_key("x", new_column_keys[0].clone())
+        .with_column_key("y", new_column_keys[1].clone())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let builder = ParquetRecordBatchReaderBuilder::try_new_with_options(rotated, options).unwrap();
+    assert_eq!(row_group_sizes(builder.metadata()), vec![30, 30, 30, 10]);
+    assert_eq!(builder.schema().fields(), batch.schema().fields());
+    let read_batches = builder
+        .build()
+        .unwrap()
+        .collect::<ArrowResult<Vec<_>>>()
+        .unwrap();
+    assert_eq!(
+        arrow::compute::concat_batches(&batch.schema(), &read_batches).unwrap(),
+        batch
+    );
+}
+
+#[test]
+fn test_reencrypt_with_properties() {
+    use parquet::encryption::key_rotation::{reencrypt, reencrypt_with_properties};
+
+    let batch = int32_batch(&["x", "y"], 100);
+    let old_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .build()
+        .unwrap();
+    let writer_properties = || {
+        WriterProperties::builder()
+            .with_file_encryption_properties(Arc::clone(&old_encryption_properties))
+            .set_bloom_filter_enabled(true)
+            .set_created_by("key rotation test".to_owned())
+            .set_max_row_group_row_count(Some(30))
+            .build()
+    };
+    let file_bytes = write_batch_with_properties(&batch, writer_properties());
+    let decryption_properties = old_encryption_properties
+        .to_decryption_properties()
+        .unwrap();
+
+    let new_footer_key = b"9876543210987654".to_vec();
+    let new_encryption_properties = FileEncryptionProperties::builder(new_footer_key.clone())
+        .with_uniform_encryption()
+        .build()
+        .unwrap();
+    let has_bloom_filters = |metadata: &ParquetMetaData| {
+        metadata
+            .row_groups()
+            .iter()
+            .flat_map(|row_group| row_group.columns())
+            .all(|column| column.bloom_filter_offset().is_some())
+    };
+
+    // Settings other than compression and sorting columns aren't kept by default
+    let metadata = reencrypt(
+        file_bytes.clone(),
+        Vec::new(),
+        Arc::clone(&decryption_properties),
+        Arc::clone(&new_encryption_properties),
+    )
+    .unwrap();
+    assert_eq!(row_group_sizes(&metadata), vec![30, 30, 30, 10]);
+    assert!(!has_bloom_filters(&metadata));
+    assert_ne!(
+        metadata.file_metadata().created_by(),
+        Some("key rotation test")
+    );
+
+    // They can be set by the caller. The caller's encryption properties and row group
+    // limits are replaced, so the row groups of the input are kept under the new keys.
+    let writer_properties = writer_properties()
+        .into_builder()
+        .set_max_row_group_row_count(Some(1000))
+        .build();
+    let mut rotated = Vec::new();
+    let metadata = reencrypt_with_properties(
+        file_bytes,
+        &mut rotated,
+        decryption_properties,
+        new_encryption_properties,
+        writer_properties,
+    )
+    .unwrap();
+    assert_eq!(row_group_sizes(&metadata), vec![30, 30, 30, 10]);
+    assert!(has_bloom_filters(&metadata));
+    assert_eq!(
+        metadata.file_metadata().created_by(),
+        Some("key rotation test")
+    );
+
+    let options = ArrowReaderOptions::default().with_file_decryption_properties(
+        FileDecryptionProperties::builder(new_footer_key)
+            .build()
+            .unwrap(),
+    );
+    let builder =
+        ParquetRecordBatchReaderBuilder::try_new_with_options(bytes::Bytes::from(rotated), options)
+            .unwrap();
+    let sbbf = builder
+        .get_row_group_column_bloom_filter(0, 0)
+        .unwrap()
+        .unwrap();
+    assert!(sbbf.check(&10_i32));
+    let read_batches = builder
+        .build()
+        .unwrap()
+        .collect::<ArrowResult<Vec<_>>>()
+        .unwrap();
+    assert_eq!(
+        arrow::compute::concat_batches(&batch.schema(), &read_batches).unwrap(),
+        batch
+    );
+}
+
+#[test]
+fn test_metadata_encrypt_footer() {
+    let batch = int32_batch(&["x"], 10);
+    let load = |file: &bytes::Bytes, decrypt: bool| {
+        let mut options = ArrowReaderOptions::default();
+        if decrypt {
+            options = options.with_file_decryption_properties(
+                FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+                    .build()
+                    .unwrap(),
+            );
+        }
+        ArrowReaderMetadata::load(file, options).unwrap()
+    };
+
+    let encrypted_footer = write_batch(
+        &batch,
+        Some(
+            FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+                .with_uniform_encryption()
+                .build()
+                .unwrap(),
+        ),
+    );
+    let metadata = load(&encrypted_footer, true);
+    assert!(metadata.metadata().encrypt_footer());
+
+    let plaintext_footer = write_batch(
+        &batch,
+        Some(
+            FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+                .with_uniform_encryption()
+                .with_plaintext_footer(true)
+                .build()
+                .unwrap(),
+        ),
+    );
+    for decrypt in [true, false] {
+        let metadata = load(&plaintext_footer, decrypt);
+        assert!(!metadata.metadata().encrypt_footer());
+        assert!(metadata.metadata().encryption_algorithm().is_some());
+    }
+
+    let unencrypted = write_batch(&batch, None);
+    let metadata = load(&unencrypted, false);
+    assert!(!metadata.metadata().encrypt_footer());
+    assert!(metadata.metadata().encryption_algorithm().is_none());
+}
+
+#[test]
+fn test_missing_aad_prefix_error() {
+    let batch = RecordBatch::try_from_iter(vec![(
+        "x",
+        Arc::new(Int32Array::from_iter_values(0..10)) as ArrayRef,
+    )])
+    .unwrap();
+    for plaintext_footer in [false, true] {
+        let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_uniform_encryption()
+            .with_plaintext_footer(plaintext_footer)
+            .with_aad_prefix(b"not stored".into())
+            .with_aad_prefix_storage(false)
+            .build()
+            .unwrap();
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(encryption_properties)
+            .build();
+        let mut file_bytes = Vec::new();
+        let mut writer =
+            ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let file_bytes = bytes::Bytes::from(file_bytes);
+
+        let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .build()
+            .unwrap();
+        let options =
+            ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+        let err = ArrowReaderMetadata::load(&file_bytes, options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: This file requires an externally-supplied AAD prefix. It was encrypted \
+            with an AAD prefix that is not stored in the file, but no AAD prefix was provided in \
+            the file decryption properties",
+            "plaintext footer: {plaintext_footer}"
+        );
+
+        let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_aad_prefix(b"not stored".into())
+            .build()
+            .unwrap();
+        let options =
+            ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+        let batches = ParquetRecordBatchReaderBuilder::try_new_with_options(file_bytes, options)
+            .unwrap()
+            .build()
+            .unwrap()
+            .collect::<ArrowResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(batches, vec![batch.clone()]);
+    }
+}
+
+#[test]
+fn test_read_mixed_encrypted_and_plaintext_columns() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "a",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "b",
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("b {}", i % 7)),
+            )) as ArrayRef,
+        ),
+        (
+            "c",
+            Arc::new(Int64Array::from_iter_values(100..200)) as ArrayRef,
+        ),
+        (
+            "d",
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("d {i}")),
+            )) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    for plaintext_footer in [false, true] {
+        let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_column_key("b", AES_128_COLUMN_KEYS[0].into())
+            .with_column_key("c", AES_128_COLUMN_KEYS[1].into())
+            .with_plaintext_footer(plaintext_footer)
+            .build()
+            .unwrap();
+        let encrypted =
+            encrypt_record_batches(std::slice::from_ref(&batch), encryption_properties).unwrap();
+
+        let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+            .with_column_key("b", AES_128_COLUMN_KEYS[0].into())
+            .with_column_key("c", AES_128_COLUMN_KEYS[1].into())
+            .build()
+            .unwrap();
+        let options =
+            ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+        let builder =
+            ParquetRecordBatchReaderBuilder::try_new_with_options(encrypted, options).unwrap();
+
+        let encrypted_columns = builder
+            .metadata()
+            .row_group(0)
+            .columns()
+            .iter()
+            .map(|column| column.crypto_metadata().is_some())
+            .collect::<Vec<_>>();
+        assert_eq!(encrypted_columns, vec![false, true, true, false]);
+
+        let read_batches = builder
+            .build()
+            .unwrap()
+            .collect::<ArrowResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(read_batches, vec![batch.clone()]);
+    }
+}
+
+#[test]
+fn test_row_filter_on_encrypted_column() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..1000)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(StringArray::from_iter_values(
+                (0..1000).map(|i| format!("value {i}")),
+            )) as ArrayRef,
+        ),
+        (
+            "z",
+            Arc::new(Int64Array::from_iter_values(1000..2000)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .set_max_row_group_row_count(Some(400))
+        .set_data_page_row_count_limit(100)
+        .set_write_batch_size(100)
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let builder = ParquetRecordBatchReaderBuilder::try_new_with_options(
+        bytes::Bytes::from(file_bytes),
+        options,
+    )
+    .unwrap();
+
+    // Filter on the encrypted x column, and then on the encrypted y column,
+    // while projecting the plaintext z column and x
+    let schema_descr = builder.metadata().file_metadata().schema_descr_ptr();
+    let x_filter = ArrowPredicateFn::new(
+        ProjectionMask::leaves(&schema_descr, [0]),
+        |batch: RecordBatch| {
+            let x = batch
+                .column(0)
+                .as_primitive::<arrow::datatypes::Int32Type>();
+            Ok(BooleanArray::from_unary(x, |x| x % 3 == 0))
+        },
+    );
+    let y_filter = ArrowPredicateFn::new(
+        ProjectionMask::leaves(&schema_descr, [1]),
+        |batch: RecordBatch| {
+            let y = batch.column(0).as_string::<i32>();
+            Ok(BooleanArray::from_iter(
+                y.iter().map(|y| y.map(|y| y.ends_with('0'))),
+            ))
+        },
+    );
+    let reader = builder
+        .with_projection(ProjectionMask::leaves(&schema_descr, [0, 2]))
+        .with_row_filter(RowFilter::new(vec![Box::new(x_filter), Box::new(y_filter)]))
+        .build()
+        .unwrap();
+    let read_batches = reader.collect::<ArrowResult<Vec<_>>>().unwrap();
+    let read_batch =
+        arrow::compute::concat_batches(&read_batches[0].schema(), &read_batches).unwrap();
+
+    let expected_x = (0..1000).filter(|x| x % 30 == 0).collect::<Vec<_>>();
+    assert_eq!(
+        read_batch
+            .column(0)
+            .as_primitive::<arrow::datatypes::Int32Type>()
+            .values(),
+        expected_x.as_slice()
+    );
+    let expected_z = expected_x
+        .iter()
+        .map(|x| *x as i64 + 1000)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        read_batch
+            .column(1)
+            .as_primitive::<arrow::datatypes::Int64Type>()
+            .values(),
+        expected_z.as_slice()
+    );
+}
+
+#[test]
+fn test_key_usage_callback() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..300)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(Int32Array::from_iter_values(300..600)) as ArrayRef,
+        ),
+        (
+            "z",
+            Arc::new(Int32Array::from_iter_values(600..900)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .set_dictionary_enabled(false)
+        .set_data_page_row_count_limit(100)
+        .set_write_batch_size(100)
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let events = Arc::new(std::sync::Mutex::new(Vec::<KeyUsageEvent>::new()));
+    let callback_events = Arc::clone(&events);
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .with_key_usage_callback(Arc::new(move |event| {
+            callback_events.lock().unwrap().push(event)
+        }))
+        .build()
+        .unwrap();
+    let options = ArrowReaderOptions::default()
+        .with_file_decryption_properties(decryption_properties)
+        .with_page_index_policy(PageIndexPolicy::Skip);
+    let reader = ParquetRecordBatchReaderBuilder::try_new_with_options(
+        bytes::Bytes::from(file_bytes),
+        options,
+    )
+    .unwrap()
+    .build()
+    .unwrap();
+    let read_batches = reader.collect::<ArrowResult<Vec<_>>>().unwrap();
+    assert_eq!(
+        arrow::compute::concat_batches(&batch.schema(), &read_batches).unwrap(),
+        batch
+    );
+
+    let event = |column_path: Option<&str>, module_type| KeyUsageEvent {
+        column_path: column_path.map(str::to_string),
+        module_type,
+    };
+    let events = events.lock().unwrap();
+    // The footer, then the metadata of each column with a column key
+    assert_eq!(
+        &events[..3],
+        &[
+            event(None, ModuleType::Footer),
+            event(Some("x"), ModuleType::ColumnMetaData),
+            event(Some("y"), ModuleType::ColumnMetaData),
+        ]
+    );
+    // Then a header and a body for each of the 3 pages of the encrypted columns,
+    // while the plaintext z column doesn't use a key
+    for column in ["x", "y"] {
+        for module_type in [ModuleType::DataPageHeader, ModuleType::DataPage] {
+            let count = events[3..]
+                .iter()
+                .filter(|e| **e == event(Some(column), module_type))
+                .count();
+            assert_eq!(count, 3, "{column} {module_type:?}");
+        }
+    }
+    assert_eq!(events.len(), 3 + 2 * 6);
+}
+
+#[test]
+fn test_encrypted_data_page_min_size() {
+    // A sparse encrypted column and a plaintext column of the same length
+    let num_rows = 10_000;
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter(
+                (0..num_rows).map(|i| (i % 100 == 0).then_some(i)),
+            )) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(Int32Array::from_iter_values(0..num_rows)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+
+    let write_file = |min_page_size: Option<usize>| {
+        let props = WriterProperties::builder()
+            .set_data_page_row_count_limit(100)
+            .set_write_batch_size(100)
+            .set_encrypted_data_page_min_size(min_page_size)
+            .with_file_encryption_properties(encryption_properties.clone())
+            .build();
+        write_batch_with_properties(&batch, props)
+    };
+
+    let read_page_counts = |file_bytes: bytes::Bytes| {
+        let options = ArrowReaderOptions::default()
+            .with_file_decryption_properties(decryption_properties.clone())
+            .with_page_index_policy(PageIndexPolicy::Required);
+        let builder =
+            ParquetRecordBatchReaderBuilder::try_new_with_options(file_bytes, options).unwrap();
+        let offset_index = builder.metadata().offset_index().unwrap()[0].clone();
+        let read_batches = builder
+            .build()
+            .unwrap()
+            .collect::<ArrowResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            arrow::compute::concat_batches(&batch.schema(), &read_batches).unwrap(),
+            batch
+        );
+        offset_index
+            .iter()
+            .map(|column| column.page_locations().len())
+            .collect::<Vec<_>>()
+    };
+
+    let uncoalesced = write_file(None);
+    let coalesced = write_file(Some(1024));
+    assert!(
+        coalesced.len() < uncoalesced.len(),
+        "expected coalesced file ({} bytes) to be smaller than uncoalesced file ({} bytes)",
+        coalesced.len(),
+        uncoalesced.len()
+    );
+
+    let uncoalesced_pages = read_page_counts(uncoalesced);
+    let coalesced_pages = read_page_counts(coalesced);
+    assert_eq!(uncoalesced_pages[0], 100);
+    assert!(coalesced_pages[0] < uncoalesced_pages[0]);
+    // The plaintext column is not affected by the setting
+    assert_eq!(coalesced_pages[1], uncoalesced_pages[1]);
+}
+
+#[test]
+fn test_read_schema_with_footer_only_decryption() {
+    use parquet::encryption::decrypt::KeyRetriever;
+
+    #[derive(Debug, Default)]
+    struct RecordingKeyRetriever {
+        requested: std::sync::Mutex<Vec<Vec<u8>>>,
+    }
+
+    impl KeyRetriever for RecordingKeyRetriever {
+        fn retrieve_key(&self, key_metadata: &[u8]) -> parquet::errors::Result<Vec<u8>> {
+            self.requested.lock().unwrap().push(key_metadata.to_vec());
+            match key_metadata {
+                b"kf" => Ok(AES_128_FOOTER_KEY.to_vec()),
+                _ => Err(ParquetError::General("Unknown key".to_string())),
+            }
+        }
+    }
+
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(Int32Array::from_iter_values(100..200)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_footer_key_metadata(b"kf".into())
+        .with_column_key_and_metadata("x", AES_128_COLUMN_KEYS[0].into(), b"kc1".into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .set_max_row_group_row_count(Some(40))
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    let file_bytes = bytes::Bytes::from(file_bytes);
+
+    let verify = |decryption_properties: Arc<FileDecryptionProperties>| {
+        let options =
+            ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+        let metadata = ArrowReaderMetadata::load(&file_bytes, options).unwrap();
+        assert_eq!(metadata.schema().fields(), batch.schema().fields());
+        let parquet_metadata = metadata.metadata();
+        assert_eq!(parquet_metadata.file_metadata().num_rows(), 100);
+        let row_counts: Vec<_> = parquet_metadata
+            .row_groups()
+            .iter()
+            .map(|rg| rg.num_rows())
+            .collect();
+        assert_eq!(row_counts, vec![40, 40, 20]);
+    };
+
+    let events = Arc::new(std::sync::Mutex::new(Vec::<KeyUsageEvent>::new()));
+    let callback_events = Arc::clone(&events);
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_footer_only_decryption()
+        .with_key_usage_callback(Arc::new(move |event| {
+            callback_events.lock().unwrap().push(event)
+        }))
+        .build()
+        .unwrap();
+    verify(decryption_properties);
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].module_type, ModuleType::Footer);
+
+    let key_retriever = Arc::new(RecordingKeyRetriever::default());
+    let decryption_properties = FileDecryptionProperties::with_key_retriever(key_retriever.clone())
+        .with_footer_only_decryption()
+        .build()
+        .unwrap();
+    verify(decryption_properties);
+    // Only the footer key is requested from the retriever
+    assert_eq!(
+        *key_retriever.requested.lock().unwrap(),
+        vec![b"kf".to_vec()]
+    );
+}
+
+#[test]
+fn test_write_encrypted_to_cursor() {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(StringArray::from_iter_values(
+                (0..100).map(|i| format!("{i}")),
+            )) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+
+    let cursor = std::io::Cursor::new(Vec::new());
+    let mut writer = ArrowWriter::try_new(cursor, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    let cursor = writer.into_inner().unwrap();
+    assert_eq!(cursor.position() as usize, cursor.get_ref().len());
+    let file_bytes = bytes::Bytes::from(cursor.into_inner());
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let reader = ParquetRecordBatchReaderBuilder::try_new_with_options(file_bytes, options)
+        .unwrap()
+        .build()
+        .unwrap();
+    let read_batches = reader.collect::<ArrowResult<Vec<_>>>().unwrap();
+    assert_eq!(read_batches, vec![batch]);
+}
+
+#[test]
+fn test_wide_schema_encrypted_footer() {
+    use parquet::encryption::backend::{MODULE_PREFIX_LEN, RandomSource, RingAeadBackend};
+    use std::sync::Mutex;
+
+    /// The lengths and capacities of the buffers passed to `encrypt_in_place`
+    #[derive(Debug, Clone, Copy)]
+    struct InPlaceEncryption {
+        len_before: usize,
+        capacity_before: usize,
+        len_after: usize,
+        capacity_after: usize,
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingBackend(Arc<Mutex<Vec<InPlaceEncryption>>>);
+
+    #[derive(Debug)]
+    struct RecordingEncryptor(Box<dyn BlockEncryptor>, Arc<Mutex<Vec<InPlaceEncryption>>>);
+
+    impl BlockEncryptor for RecordingEncryptor {
+        fn encrypt(&mut self, plaintext: &[u8], aad: &[u8]) -> parquet::errors::Result<Vec<u8>> {
+            self.0.encrypt(plaintext, aad)
+        }
+
+        fn encrypt_in_place(
+            &mut self,
+            buffer: &mut Vec<u8>,
+            aad: &[u8],
+        ) -> parquet::errors::Result<()> {
+            let (len_before, capacity_before) = (buffer.len(), buffer.capacity());
+            self.0.encrypt_in_place(buffer, aad)?;
+            self.1.lock().unwrap().push(InPlaceEncryption {
+                len_before,
+                capacity_before,
+                len_after: buffer.len(),
+                capacity_after: buffer.capacity(),
+            });
+            Ok(())
+        }
+    }
+
+    impl AeadBackend for RecordingBackend {
+        fn new_encryptor(&self, key: &[u8]) -> parquet::errors::Result<Box<dyn BlockEncryptor>> {
+            Ok(Box::new(RecordingEncryptor(
+                RingAeadBackend.new_encryptor(key)?,
+                Arc::clone(&self.0),
+            )))
+        }
+
+        fn new_decryptor(&self, key: &[u8]) -> parquet::errors::Result<Arc<dyn BlockDecryptor>> {
+            RingAeadBackend.new_decryptor(key)
+        }
+
+        fn new_encryptor_with_random(
+            &self,
+            key: &[u8],
+            random: &dyn RandomSource,
+        ) -> parquet::errors::Result<Box<dyn BlockEncryptor>> {
+            Ok(Box::new(RecordingEncryptor(
+                RingAeadBackend.new_encryptor_with_random(key, random)?,
+                Arc::clone(&self.0),
+            )))
+        }
+    }
+
+    let num_columns = 5_000;
+    let fields: Vec<_> = (0..num_columns)
+        .map(|i| Field::new(format!("c{i}"), DataType::Int32, false))
+        .collect();
+    let columns: Vec<_> = (0..num_columns)
+        .map(|i| Arc::new(Int32Array::from(vec![i, i + 1])) as ArrayRef)
+        .collect();
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap();
+
+    let backend = RecordingBackend::default();
+    let in_place_encryptions = Arc::clone(&backend.0);
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .with_aead_backend(Arc::new(backend))
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+    let file_bytes = write_batch_with_properties(&batch, props);
+
+    // The footer is the largest module encrypted in place. It is serialized after space
+    // reserved for the length and nonce, so encrypting it only appends the tag and the
+    // buffer grows by at most the tag length.
+    let footer = in_place_encryptions
+        .lock()
+        .unwrap()
+        .iter()
+        .copied()
+        .max_by_key(|encryption| encryption.len_before)
+        .unwrap();
+    assert!(footer.len_before > MODULE_PREFIX_LEN + num_columns as usize * 10);
+    assert_eq!(footer.len_after, footer.len_before + 16);
+    assert!(
+        footer.capacity_after <= footer.capacity_before.max(footer.len_before + 16),
+        "{footer:?}"
+    );
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let reader = ParquetRecordBatchReaderBuilder::try_new_with_options(file_bytes, options)
+        .unwrap()
+        .build()
+        .unwrap();
+    let read_batches = reader.collect::<ArrowResult<Vec<_>>>().unwrap();
+    assert_eq!(read_batches, vec![batch]);
+}
+
+/// Write a small file with fixed keys, AAD and nonces, then list each encrypted module
+/// of the column chunks with its AAD, nonce and length, checking that it decrypts.
+fn encrypted_module_listing() -> String {
+    use parquet::encryption::backend::RingAeadBackend;
+    use parquet::encryption::modules::{create_footer_aad, row_group_module_aads};
+
+    let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "x",
+            Arc::new(Int32Array::from_iter_values(0..6)) as ArrayRef,
+        ),
+        (
+            "y",
+            Arc::new(Int32Array::from_iter_values(6..12)) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .with_aad_prefix(b"golden".into())
+        .with_aad_prefix_storage(true)
+        .with_aad_file_unique(vec![1, 2, 3, 4, 5, 6, 7, 8])
+        .with_random_source(Arc::new(SequenceRandomSource::new(7)))
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .set_dictionary_enabled(false)
+        .set_data_page_row_count_limit(3)
+        .set_write_batch_size(3)
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let file_bytes = bytes::Bytes::from(file_bytes);
+    let metadata = ArrowReaderMetadata::load(&file_bytes, options).unwrap();
+    let metadata = metadata.metadata();
+    let file_aad = metadata.file_aad().unwrap();
+    let decryptor = RingAeadBackend.new_decryptor(AES_128_FOOTER_KEY).unwrap();
+
+    let mut listing = format!(
+        "file_aad {}\nFooter aad={}\n",
+        hex(file_aad),
+        hex(&create_footer_aad(file_aad).unwrap())
+    );
+    let mut list_module = |row_group: usize,
+                           aads: &[parquet::encryption::modules::ModuleAad],
+                           column: usize,
+                           module_type: ModuleType,
+                           page: Option<usize>,
+                           offset: usize|
+     -> usize {
+        let len = u32::from_le_bytes(file_bytes[offset..offset + 4].try_into().unwrap()) as usize;
+        let module = &file_bytes[offset..offset + 4 + len];
+        let aad = &aads
+            .iter()
+            .find(|m| {
+                m.column_ordinal == column && m.module_type == module_type && m.page_ordinal == page
+            })
+            .unwrap()
+            .aad;
+        decryptor.decrypt(module, aad).unwrap();
+        listing.push_str(&format!(
+            "rg={row_group} col={column} {module_type:?} page={page:?} len={} nonce={} aad={}\n",
+            module.len(),
+            hex(&module[4..16]),
+            hex(aad)
+        ));
+        module.len()
+    };
+
+    for (row_group_idx, row_group) in metadata.row_groups().iter().enumerate() {
+        let num_pages: Vec<_> = row_group
+            .columns()
+            .iter()
+            .map(|column| column.num_values() as usize / 3)
+            .collect();
+        let aads = row_group_module_aads(file_aad, row_group_idx, &num_pages).unwrap();
+        for (column_idx, column) in row_group.columns().iter().enumerate() {
+            let mut offset = column.data_page_offset() as usize;
+            for page in 0..num_pages[column_idx] {
+                for module_type in [ModuleType::DataPageHeader, ModuleType::DataPage] {
+                    offset += list_module(
+                        row_group_idx,
+                        &aads,
+                        column_idx,
+                        module_type,
+                        Some(page),
+                        offset,
+                    );
+                }
+            }
+            assert_eq!(
+                offset as i64,
+                column.data_page_offset() + column.compressed_size()
+            );
+            list_module(
+                row_group_idx,
+                &aads,
+                column_idx,
+                ModuleType::ColumnIndex,
+                None,
+                column.column_index_offset().unwrap() as usize,
+            );
+            list_module(
+                row_group_idx,
+                &aads,
+                column_idx,
+                ModuleType::OffsetIndex,
+                None,
+                column.offset_index_offset().unwrap() as usize,
+            );
+        }
+    }
+    listing
+}
+
+#[test]
+fn test_module_aads_golden() {
+    let listing = encrypted_module_listing();
+    // Every module is encrypted with the same key, so no nonce may repeat
+    let nonces: Vec<_> = listing
+        .split_whitespace()
+        .filter_map(|field| field.strip_prefix("nonce="))
+        .collect();
+    let unique_nonces: std::collections::HashSet<_> = nonces.iter().collect();
+    assert_eq!(
+        unique_nonces.len(),
+        nonces.len(),
+        "Nonce reused in {listing}"
+    );
+    let golden_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/encryption/golden/module_aads.txt");
+    if std::env::var_os("PARQUET_UPDATE_GOLDEN").is_some() {
+        std::fs::write(&golden_path, &listing).unwrap();
+    }
+    let expected = std::fs::read_to_string(&golden_path).unwrap();
+    assert_eq!(
+        listing, expected,
+        "Encrypted module listing doesn't match {golden_path:?}. \
+         If the change is intended, run with PARQUET_UPDATE_GOLDEN=1 to update it."
+    );
+}
+
+#[test]
+fn test_oversized_encrypted_footer_length() {
+    let batch = RecordBatch::try_from_iter(vec![(
+        "x",
+        Arc::new(Int32Array::from_iter_values(0..100)) as ArrayRef,
+    )])
+    .unwrap();
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_uniform_encryption()
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+
+    // Patch the declared footer length, just before the trailing "PARE" magic
+    let file_len = file_bytes.len();
+    let length_offset = file_len - 8;
+    for footer_len in [u32::MAX, (file_len - 8) as u32, (file_len - 11) as u32] {
+        let mut crafted = file_bytes.clone();
+        crafted[length_offset..length_offset + 4].copy_from_slice(&footer_len.to_le_bytes());
+        let err =
+            ArrowReaderMetadata::load(&bytes::Bytes::from(crafted), options.clone()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Parquet error: Encrypted footer length {footer_len} is too large for a file of {file_len} bytes"
+            )
+        );
+    }
+
+    // The largest plausible length gets as far as parsing the footer
+    let mut crafted = file_bytes.clone();
+    crafted[length_offset..length_offset + 4]
+        .copy_from_slice(&((file_len - 12) as u32).to_le_bytes());
+    let err = ArrowReaderMetadata::load(&bytes::Bytes::from(crafted), options.clone()).unwrap_err();
+    assert!(
+        !err.to_string().contains("too large"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_parallel_column_encryption_matches_serial() {
+    use parquet::arrow::ArrowSchemaConverter;
+    use parquet::arrow::arrow_writer::{
+        ArrowColumnChunk, ArrowRowGroupWriterFactory, ArrowWriterOptions, compute_leaves,
+    };
+
+    let num_columns = 8;
+    let batch = RecordBatch::try_from_iter((0..num_columns).map(|i| {
+        (
+            format!("c{i}"),
+            Arc::new(Int32Array::from_iter_values((0..1000).map(|v| v * i))) as ArrayRef,
+        )
+    }))
+    .unwrap();
+    // A deterministic random source, started afresh for each file, makes the output
+    // reproducible so that the files can be compared byte for byte
+    let writer_properties = || {
+        let encryption_properties = (0..num_columns)
+            .fold(
+                FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into()),
+                |builder, i| {
+                    builder.with_column_key(&format!("c{i}"), format!("{i:016}").into_bytes())
+                },
+            )
+            .with_random_source(Arc::new(SequenceRandomSource::new(7)))
+            .build()
+            .unwrap();
+        WriterProperties::builder()
+            .with_file_encryption_properties(encryption_properties)
+            .set_data_page_row_count_limit(100)
+            .set_write_batch_size(100)
+            .build()
+    };
+
+    // The Arrow schema isn't stored by the parallel writer, so skip it here too
+    let mut serial = Vec::new();
+    let options = ArrowWriterOptions::new()
+        .with_properties(writer_properties())
+        .with_skip_arrow_metadata(true);
+    let mut writer =
+        ArrowWriter::try_new_with_options(&mut serial, batch.schema(), options).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let parquet_schema = ArrowSchemaConverter::new()
+        .convert(&batch.schema())
+        .unwrap();
+    let mut parallel = Vec::new();
+    let mut writer = SerializedFileWriter::new(
+        &mut parallel,
+        parquet_schema.root_schema_ptr(),
+        Arc::new(writer_properties()),
+    )
+    .unwrap();
+    let factory = ArrowRowGroupWriterFactory::new(&writer, batch.schema());
+    let column_writers = factory.create_column_writers(0).unwrap();
+    let chunks: Vec<ArrowColumnChunk> = std::thread::scope(|scope| {
+        let handles: Vec<_> = column_writers
+            .into_iter()
+            .zip(batch.columns().iter().zip(batch.schema().fields()))
+            .map(|(mut column_writer, (array, field))| {
+                let field = Arc::clone(field);
+                scope.spawn(move || {
+                    for leaves in compute_leaves(&field, array).unwrap() {
+                        column_writer.write(&leaves).unwrap();
+                    }
+                    column_writer.close().unwrap()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    let mut row_group_writer = writer.next_row_group().unwrap();
+    for chunk in chunks {
+        chunk.append_to_row_group(&mut row_group_writer).unwrap();
+    }
+    row_group_writer.close().unwrap();
+    writer.close().unwrap();
+
+    assert_eq!(parallel, serial);
+
+    let decryption_properties = (0..num_columns)
+        .fold(
+            FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into()),
+            |builder, i| builder.with_column_key(&format!("c{i}"), format!("{i:016}").into_bytes()),
+        )
+        .build()
+        .unwrap();
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let reader = ParquetRecordBatchReaderBuilder::try_new_with_options(
+        bytes::Bytes::from(parallel),
+        options,
+    )
+    .unwrap()
+    .build()
+    .unwrap();
+    let read_batches = reader.collect::<ArrowResult<Vec<_>>>().unwrap();
+    assert_eq!(read_batches, vec![batch]);
+}
@@ -22,19 +22,24 @@ use crate::encryption_util::{
     AES_128_COLUMN_KEYS, AES_128_COLUMN_NAME_KEYS, AES_128_COLUMN_NAMES, AES_128_FOOTER_KEY,
     AES_128_FOOTER_KEY_NAME, AES_128_KEY_NAME_KEY, AES_256_COLUMN_KEYS, AES_256_COLUMN_NAME_KEYS,
     AES_256_COLUMN_NAMES, AES_256_FOOTER_KEY, AES_256_FOOTER_KEY_NAME, AES_256_KEY_NAME_KEY,
-    BAD_AES_128_FOOTER_KEY, BAD_AES_256_FOOTER_KEY, TestKeyRetriever, read_encrypted_file,
-    verify_column_indexes, verify_encryption_double_test_data, verify_encryption_test_data,
+    AUTHENTICATION_FAILED, BAD_AES_128_FOOTER_KEY, BAD_AES_256_FOOTER_KEY, TestKeyRetriever,
+    int32_batch, read_encrypted_file, verify_column_indexes, verify_encryption_double_test_data,
+    verify_encryption_test_data, write_batch_with_properties,
 };
-use arrow_array::RecordBatch;
-use arrow_schema::Schema;
+use arrow::compute::concat_batches;
+use arrow_array::{Float32Array, Int32Array, RecordBatch};
+use arrow_schema::{DataType, Field, Schema};
 use futures::TryStreamExt;
-use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions};
+use parquet::arrow::arrow_reader::{
+    ArrowPredicateFn, ArrowReaderMetadata, ArrowReaderOptions, RowFilter,
+};
 use parquet::arrow::arrow_writer::{
     ArrowColumnChunk, ArrowColumnWriter, ArrowLeafColumn, ArrowRowGroupWriterFactory,
     ArrowWriterOptions, compute_leaves,
 };
 use parquet::arrow::{
     ArrowSchemaConverter, ArrowWriter, AsyncArrowWriter, ParquetRecordBatchStreamBuilder,
+    ProjectionMask,
 };
 use parquet::encryption::decrypt::FileDecryptionProperties;
 use parquet::encryption::encrypt::FileEncryptionProperties;
@@ -129,7 +134,7 @@ async fn test_misspecified_encryption_keys() {
 
     // Too short footer key
     check_for_error(
-        format!("Parquet error: Invalid footer key. Error creating RingGcmBlockDecryptor with unsupported key length: {}", "bad_pwd".len()).as_str(),
+        format!("Invalid key error: Invalid footer key. Parquet error: Error creating RingGcmBlockDecryptor with unsupported key length: {}", "bad_pwd".len()).as_str(),
         b"bad_pwd",
         column_1_key,
         column_2_key,
@@ -139,7 +144,9 @@ async fn test_misspecified_encryption_keys() {
 
     // Wrong footer key
     check_for_error(
-        "Parquet error: Provided footer key and AAD were unable to decrypt parquet footer",
+        &format!(
+            "Decryption key error: Provided footer key and AAD were unable to decrypt parquet footer: {AUTHENTICATION_FAILED}"
+        ),
         BAD_AES_128_FOOTER_KEY,
         column_1_key,
         column_2_key,
@@ -173,7 +180,9 @@ async fn test_misspecified_encryption_keys() {
 
     // Wrong column key
     check_for_error(
-        "Parquet error: Unable to decrypt column 'double_field', perhaps the column key is wrong?",
+        &format!(
+            "Decryption key error: Unable to decrypt column 'double_field': {AUTHENTICATION_FAILED}"
+        ),
         footer_key,
         "1123456789012345".as_bytes(),
         column_2_key,
@@ -183,7 +192,9 @@ async fn test_misspecified_encryption_keys() {
 
     // Mixed up keys
     check_for_error(
-        "Parquet error: Unable to decrypt column 'float_field', perhaps the column key is wrong?",
+        &format!(
+            "Decryption key error: Unable to decrypt column 'float_field': {AUTHENTICATION_FAILED}"
+        ),
         footer_key,
         column_2_key,
         column_1_key,
@@ -205,7 +216,7 @@ async fn test_misspecified_encryption_keys() {
 
     // Too short footer key
     check_for_error(
-        format!("Parquet error: Invalid footer key. Error creating RingGcmBlockDecryptor with unsupported key length: {}", "bad_pwd".len()).as_str(),
+        format!("Invalid key error: Invalid footer key. Parquet error: Error creating RingGcmBlockDecryptor with unsupported key length: {}", "bad_pwd".len()).as_str(),
         b"bad_pwd",
         aes256_column_1_key,
         aes256_column_2_key,
@@ -214,7 +225,9 @@ async fn test_misspecified_encryption_keys() {
 
     // Wrong footer key
     check_for_error(
-        "Parquet error: Provided footer key and AAD were unable to decrypt parquet footer",
+        &format!(
+            "Decryption key error: Provided footer key and AAD were unable to decrypt parquet footer: {AUTHENTICATION_FAILED}"
+        ),
         BAD_AES_256_FOOTER_KEY,
         aes256_column_1_key,
         aes256_column_2_key,
@@ -248,7 +261,9 @@ async fn test_misspecified_encryption_keys() {
 
     // Wrong column key
     check_for_error(
-        "Parquet error: Unable to decrypt column 'double_field', perhaps the column key is wrong?",
+        &format!(
+            "Decryption key error: Unable to decrypt column 'double_field': {AUTHENTICATION_FAILED}"
+        ),
         aes256_footer_key,
         "22345678901234567890123456789012".as_bytes(),
         aes256_column_2_key,
@@ -258,7 +273,9 @@ async fn test_misspecified_encryption_keys() {
 
     // Mixed up keys
     check_for_error(
-        "Parquet error: Unable to decrypt column 'float_field', perhaps the column key is wrong?",
+        &format!(
+            "Decryption key error: Unable to decrypt column 'float_field': {AUTHENTICATION_FAILED}"
+        ),
         aes256_footer_key,
         aes256_column_2_key,
         aes256_column_1_key,
@@ -334,19 +351,9 @@ async fn test_aes_ctr_encryption() {
             builder = builder.with_column_key(column_name, key.to_vec());
         }
         let decryption_properties = builder.build().unwrap();
-
-        let options =
-            ArrowReaderOptions::new().with_file_decryption_properties(decryption_properties);
-        let metadata = ArrowReaderMetadata::load_async(&mut file, options).await;
-
-        match metadata {
-            Err(ParquetError::NYI(s)) => {
-                assert!(s.contains("AES_GCM_CTR_V1"));
-            }
-            _ => {
-                panic!("Expected ParquetError::NYI");
-            }
-        };
+        verify_encryption_test_file_read_async(&mut file, decryption_properties)
+            .await
+            .unwrap();
     }
 
     aes_ctr_encryption(AES_128_FOOTER_KEY, AES_128_COLUMN_NAME_KEYS).await;
@@ -420,6 +427,120 @@ async fn test_write_non_uniform_encryption() {
     .await;
 }
 
+#[tokio::test]
+async fn test_async_writer_encryption_read_sync() {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("x", DataType::Float32, false),
+        Field::new("y", DataType::Float32, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from((0..100).collect::<Vec<_>>())),
+            Arc::new(Float32Array::from_iter_values((0..100).map(|v| v as f32))),
+            Arc::new(Float32Array::from_iter_values((100..200).map(|v| v as f32))),
+        ],
+    )
+    .unwrap();
+
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(file_encryption_properties)
+        // Ensure multiple pages and row groups are encrypted
+        .set_max_row_group_row_count(Some(40))
+        .set_data_page_row_count_limit(10)
+        .set_write_batch_size(10)
+        .build();
+
+    let temp_file = tempfile::tempfile().unwrap();
+    let file = File::from_std(temp_file.try_clone().unwrap());
+    let mut writer = AsyncArrowWriter::try_new(file, schema, Some(props)).unwrap();
+    writer.write(&batch).await.unwrap();
+    writer.close().await.unwrap();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .with_column_key("y", AES_128_COLUMN_KEYS[1].into())
+        .build()
+        .unwrap();
+    let (read_batches, metadata) = read_encrypted_file(&temp_file, decryption_properties).unwrap();
+
+    assert_eq!(metadata.metadata().num_row_groups(), 3);
+    let read_batches = concat_batches(&batch.schema(), &read_batches).unwrap();
+    assert_eq!(read_batches, batch);
+
+    // Reading the encrypted columns without keys must fail
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .build()
+        .unwrap();
+    assert!(read_encrypted_file(&temp_file, decryption_properties).is_err());
+}
+
+#[tokio::test]
+async fn test_uniform_encryption_roundtrip_async() {
+    uniform_encryption_roundtrip_async(false).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_uniform_encryption_roundtrip_async_with_page_index() {
+    uniform_encryption_roundtrip_async(true).await.unwrap();
+}
+
+async fn uniform_encryption_roundtrip_async(page_index: bool) -> Result<(), ParquetError> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x0", DataType::Int32, false),
+        Field::new("x1", DataType::Int32, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from((0..150).collect::<Vec<_>>())),
+            Arc::new(Int32Array::from((100..250).collect::<Vec<_>>())),
+        ],
+    )?;
+
+    let file_encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.to_vec())
+        .with_uniform_encryption()
+        .build()?;
+    let props = WriterProperties::builder()
+        // Ensure multiple row groups
+        .set_max_row_group_row_count(Some(50))
+        // Ensure multiple pages per row group
+        .set_write_batch_size(20)
+        .set_data_page_row_count_limit(20)
+        .with_file_encryption_properties(file_encryption_properties)
+        .build();
+
+    let temp_file = tempfile::tempfile()?;
+    let mut writer = ArrowWriter::try_new(temp_file.try_clone()?, schema, Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    let decryption_properties =
+        FileDecryptionProperties::builder(AES_128_FOOTER_KEY.to_vec()).build()?;
+    let options = ArrowReaderOptions::new()
+        .with_file_decryption_properties(decryption_properties)
+        .with_page_index_policy(PageIndexPolicy::from(page_index));
+
+    let file = File::from_std(temp_file);
+    let builder = ParquetRecordBatchStreamBuilder::new_with_options(file, options).await?;
+    assert_eq!(builder.metadata().num_row_groups(), 3);
+    if page_index {
+        assert!(builder.metadata().offset_index().is_some());
+    }
+
+    let read_batches: Vec<_> = builder.build()?.try_collect().await?;
+
+    let read_batches = concat_batches(&batch.schema(), &read_batches)?;
+    assert_eq!(read_batches, batch);
+    Ok(())
+}
+
 #[cfg(feature = "object_store")]
 async fn get_encrypted_meta_store() -> (
     object_store::ObjectMeta,
@@ -464,6 +585,73 @@ async fn test_read_encrypted_file_from_object_store() {
     verify_encryption_test_data(record_batches, &metadata);
 }
 
+#[tokio::test]
+#[cfg(feature = "object_store")]
+async fn test_encryption_roundtrip_in_memory_object_store() {
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::{ObjectStore, ObjectStoreExt};
+    use parquet::arrow::async_reader::ParquetObjectReader;
+    use parquet::arrow::async_writer::ParquetObjectWriter;
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new("y", DataType::Float32, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from((0..1000).collect::<Vec<_>>())),
+            Arc::new(Float32Array::from_iter_values((0..1000).map(|i| i as f32))),
+        ],
+    )
+    .unwrap();
+
+    let file_encryption_properties = FileEncryptionProperties::builder(b"0123456789012345".into())
+        .with_column_key("x", b"1234567890123450".into())
+        .with_column_key("y", b"1234567890123451".into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(file_encryption_properties)
+        .set_max_row_group_row_count(Some(300))
+        .set_data_page_row_count_limit(100)
+        .set_write_batch_size(100)
+        .build();
+
+    let store = Arc::new(InMemory::new()) as Arc<dyn ObjectStore>;
+    let path = Path::from("encrypted.parquet");
+    let object_store_writer = ParquetObjectWriter::new(Arc::clone(&store), path.clone());
+    let mut writer = AsyncArrowWriter::try_new(object_store_writer, schema, Some(props)).unwrap();
+    writer.write(&batch).await.unwrap();
+    writer.close().await.unwrap();
+
+    let meta = store.head(&path).await.unwrap();
+    let decryption_properties = FileDecryptionProperties::builder(b"0123456789012345".into())
+        .with_column_key("x", b"1234567890123450".into())
+        .with_column_key("y", b"1234567890123451".into())
+        .build()
+        .unwrap();
+    let options = ArrowReaderOptions::new()
+        .with_file_decryption_properties(decryption_properties)
+        .with_page_index_policy(PageIndexPolicy::Required);
+
+    // A small footer size hint means the encrypted footer length is read
+    // before the rest of the footer is fetched
+    let reader = ParquetObjectReader::new(store, meta.location)
+        .with_file_size(meta.size)
+        .with_footer_size_hint(8);
+    let builder = ParquetRecordBatchStreamBuilder::new_with_options(reader, options)
+        .await
+        .unwrap();
+    assert_eq!(builder.metadata().num_row_groups(), 4);
+    assert!(builder.metadata().offset_index().is_some());
+
+    let read_batches: Vec<_> = builder.build().unwrap().try_collect().await.unwrap();
+    let read_batches = concat_batches(&batch.schema(), &read_batches).unwrap();
+    assert_eq!(read_batches, batch);
+}
+
 #[tokio::test]
 async fn test_non_uniform_encryption_plaintext_footer_with_key_retriever() {
     async fn non_uniform_encryption_plaintext_footer_with_key_retriever(
@@ -1099,3 +1287,162 @@ async fn test_multi_threaded_encrypted_writing_deprecated() {
         "Parquet error: Parquet file has an encrypted footer but decryption properties were not provided"
     );
 }
+
+#[tokio::test]
+async fn test_read_encrypted_bloom_filter_async() {
+    let batch = RecordBatch::try_from_iter(vec![(
+        "x",
+        Arc::new(Int32Array::from_iter_values(0..100)) as _,
+    )])
+    .unwrap();
+
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .set_bloom_filter_enabled(true)
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+
+    let temp_file = tempfile::tempfile().unwrap();
+    let mut writer =
+        ArrowWriter::try_new(temp_file.try_clone().unwrap(), batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let options = ArrowReaderOptions::new().with_file_decryption_properties(decryption_properties);
+    let mut builder =
+        ParquetRecordBatchStreamBuilder::new_with_options(File::from_std(temp_file), options)
+            .await
+            .unwrap();
+
+    let sbbf = builder
+        .get_row_group_column_bloom_filter(0, 0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(sbbf.check(&42_i32));
+    assert!(!sbbf.check(&1000_i32));
+}
+
+#[tokio::test]
+async fn test_encrypted_bloom_filter_without_length_rejects_bad_length_prefix_async() {
+    let batch = int32_batch(&["x"], 100);
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .set_bloom_filter_enabled(true)
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+    let file_bytes = write_batch_with_properties(&batch, props);
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let options = ArrowReaderOptions::new().with_file_decryption_properties(decryption_properties);
+    let reader_metadata = ArrowReaderMetadata::load(&file_bytes, options.clone()).unwrap();
+
+    // Drop the bloom filter length from the metadata and corrupt the
+    // unauthenticated length prefix of the header module
+    let mut metadata_builder = reader_metadata.metadata().as_ref().clone().into_builder();
+    let mut row_group = metadata_builder.take_row_groups().remove(0).into_builder();
+    let mut columns = row_group.take_columns();
+    let offset = columns[0].bloom_filter_offset().unwrap() as usize;
+    columns[0] = columns[0]
+        .clone()
+        .into_builder()
+        .set_bloom_filter_length(None)
+        .build()
+        .unwrap();
+    let row_group = row_group.set_column_metadata(columns).build().unwrap();
+    let metadata = Arc::new(metadata_builder.add_row_group(row_group).build());
+    let reader_metadata = ArrowReaderMetadata::try_new(metadata, options).unwrap();
+
+    let mut corrupted = file_bytes.to_vec();
+    corrupted[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    let mut builder = ParquetRecordBatchStreamBuilder::new_with_metadata(
+        std::io::Cursor::new(corrupted),
+        reader_metadata,
+    );
+
+    let err = builder
+        .get_row_group_column_bloom_filter(0, 0)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Parquet error: Encrypted bloom filter module length 4294967295 exceeds the maximum of 134217756"
+    );
+}
+
+#[tokio::test]
+async fn test_row_filter_on_encrypted_column_async() {
+    let batch = RecordBatch::try_from_iter(vec![
+        ("x", Arc::new(Int32Array::from_iter_values(0..1000)) as _),
+        (
+            "y",
+            Arc::new(Float32Array::from_iter_values((0..1000).map(|i| i as f32))) as _,
+        ),
+    ])
+    .unwrap();
+
+    let encryption_properties = FileEncryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .set_data_page_row_count_limit(100)
+        .set_write_batch_size(100)
+        .build();
+    let temp_file = tempfile::tempfile().unwrap();
+    let mut writer =
+        ArrowWriter::try_new(temp_file.try_clone().unwrap(), batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let decryption_properties = FileDecryptionProperties::builder(AES_128_FOOTER_KEY.into())
+        .with_column_key("x", AES_128_COLUMN_KEYS[0].into())
+        .build()
+        .unwrap();
+    let options = ArrowReaderOptions::new()
+        .with_file_decryption_properties(decryption_properties)
+        .with_page_index_policy(PageIndexPolicy::Required);
+    let builder =
+        ParquetRecordBatchStreamBuilder::new_with_options(File::from_std(temp_file), options)
+            .await
+            .unwrap();
+
+    let schema_descr = builder.metadata().file_metadata().schema_descr_ptr();
+    let x_filter = ArrowPredicateFn::new(
+        ProjectionMask::leaves(&schema_descr, [0]),
+        |batch: RecordBatch| {
+            let x = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            Ok(x.iter()
+                .map(|x| x.map(|x| (250..350).contains(&x)))
+                .collect())
+        },
+    );
+    let stream = builder
+        .with_projection(ProjectionMask::leaves(&schema_descr, [1]))
+        .with_row_filter(RowFilter::new(vec![Box::new(x_filter)]))
+        .build()
+        .unwrap();
+    let read_batches: Vec<RecordBatch> = stream.try_collect().await.unwrap();
+    let read_batch = concat_batches(&read_batches[0].schema(), &read_batches).unwrap();
+
+    let expected = Float32Array::from_iter_values((250..350).map(|i| i as f32));
+    assert_eq!(read_batch.column(0).as_ref(), &expected);
+}
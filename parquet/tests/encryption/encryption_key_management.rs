@@ -0,0 +1,177 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Tests for encryption using keys managed by a KMS
+
+use crate::encryption_util::read_encrypted_file;
+use arrow_array::{Float32Array, Int32Array, RecordBatch};
+use arrow_schema::{DataType, Field, Schema};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use parquet::arrow::ArrowWriter;
+use parquet::encryption::key_management::crypto_factory::{CryptoFactory, EncryptionConfiguration};
+use parquet::encryption::key_management::key_material::KeyMetadata;
+use parquet::encryption::key_management::kms::KmsClient;
+use parquet::errors::{ParquetError, Result};
+use parquet::file::column_crypto_metadata::ColumnCryptoMetaData;
+use parquet::file::properties::WriterProperties;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// An in-memory mock KMS that wraps keys by XORing them with master keys.
+/// This is not secure and is only used for testing.
+struct MockKmsClient {
+    master_keys: HashMap<String, Vec<u8>>,
+    unwrap_count: AtomicUsize,
+}
+
+impl MockKmsClient {
+    fn new() -> Self {
+        Self {
+            master_keys: HashMap::from([
+                ("kf".to_owned(), b"0123456789012345".to_vec()),
+                ("kc1".to_owned(), b"1234567890123450".to_vec()),
+                ("kc2".to_owned(), b"1234567890123451".to_vec()),
+            ]),
+            unwrap_count: AtomicUsize::new(0),
+        }
+    }
+
+    fn xor_with_master_key(&self, key: &[u8], master_key_identifier: &str) -> Result<Vec<u8>> {
+        let master_key = self.master_keys.get(master_key_identifier).ok_or_else(|| {
+            ParquetError::General(format!("Unknown master key '{master_key_identifier}'"))
+        })?;
+        Ok(key
+            .iter()
+            .zip(master_key.iter().cycle())
+            .map(|(a, b)| a ^ b)
+            .collect())
+    }
+}
+
+impl KmsClient for MockKmsClient {
+    fn wrap_key(&self, key_bytes: &[u8], master_key_identifier: &str) -> Result<String> {
+        let wrapped = self.xor_with_master_key(key_bytes, master_key_identifier)?;
+        Ok(BASE64_STANDARD.encode(wrapped))
+    }
+
+    fn unwrap_key(&self, wrapped_key: &str, master_key_identifier: &str) -> Result<Vec<u8>> {
+        self.unwrap_count.fetch_add(1, Ordering::Relaxed);
+        let wrapped = BASE64_STANDARD
+            .decode(wrapped_key)
+            .map_err(|e| ParquetError::General(e.to_string()))?;
+        self.xor_with_master_key(&wrapped, master_key_identifier)
+    }
+}
+
+fn test_batch() -> RecordBatch {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("x", DataType::Float32, false),
+        Field::new("y", DataType::Float32, false),
+    ]));
+    RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(Int32Array::from((0..100).collect::<Vec<_>>())),
+            Arc::new(Float32Array::from_iter_values((0..100).map(|i| i as f32))),
+            Arc::new(Float32Array::from_iter_values(
+                (0..100).map(|i| i as f32 * 2.0),
+            )),
+        ],
+    )
+    .unwrap()
+}
+
+fn kms_roundtrip(config: EncryptionConfiguration, expected_unwrap_count: Option<usize>) {
+    let kms_client = Arc::new(MockKmsClient::new());
+    let crypto_factory = CryptoFactory::new(kms_client.clone());
+    let batch = test_batch();
+
+    let file_encryption_properties = crypto_factory.file_encryption_properties(&config).unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(file_encryption_properties)
+        .build();
+    let temp_file = tempfile::tempfile().unwrap();
+    let mut writer =
+        ArrowWriter::try_new(temp_file.try_clone().unwrap(), batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let decryption_properties = crypto_factory.file_decryption_properties().unwrap();
+    let (batches, metadata) = read_encrypted_file(&temp_file, decryption_properties).unwrap();
+    assert_eq!(batches, vec![batch]);
+    if let Some(expected_unwrap_count) = expected_unwrap_count {
+        assert_eq!(
+            kms_client.unwrap_count.load(Ordering::Relaxed),
+            expected_unwrap_count
+        );
+    }
+
+    // Column key metadata holds the key material
+    let row_group = metadata.metadata().row_group(0);
+    for (column_idx, master_key_id) in [(1, "kc1"), (2, "kc2")] {
+        let key_metadata = match row_group.column(column_idx).crypto_metadata() {
+            Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(column_key)) => {
+                column_key.key_metadata.clone().unwrap()
+            }
+            _ => panic!("Expected column to be encrypted with a column key"),
+        };
+        match KeyMetadata::parse(&key_metadata).unwrap() {
+            KeyMetadata::Internal(key_material) => {
+                assert_eq!(key_material.master_key_id(), master_key_id);
+                assert_eq!(key_material.is_double_wrapped(), config.double_wrapping());
+            }
+            _ => panic!("Expected internal key material"),
+        }
+    }
+    assert!(row_group.column(0).crypto_metadata().is_none());
+}
+
+fn encryption_configuration()
+-> parquet::encryption::key_management::crypto_factory::EncryptionConfigurationBuilder {
+    EncryptionConfiguration::builder("kf".into())
+        .with_column_keys("kc1".into(), vec!["x".into()])
+        .with_column_keys("kc2".into(), vec!["y".into()])
+}
+
+#[test]
+fn test_kms_double_wrapping_roundtrip() {
+    let config = encryption_configuration().build().unwrap();
+    // One unwrap per key encryption key, as each master key's KEK is cached
+    kms_roundtrip(config, Some(3));
+}
+
+#[test]
+fn test_kms_single_wrapping_roundtrip() {
+    let config = encryption_configuration()
+        .with_double_wrapping(false)
+        .build()
+        .unwrap();
+    kms_roundtrip(config, None);
+}
+
+#[test]
+fn test_kms_plaintext_footer_256_bit_keys() {
+    let config = encryption_configuration()
+        .with_plaintext_footer(true)
+        .with_data_key_length_bits(256)
+        .build()
+        .unwrap();
+    kms_roundtrip(config, Some(3));
+}
@@ -16,21 +16,31 @@
 // under the License.
 
 use arrow_array::cast::AsArray;
-use arrow_array::{RecordBatch, types};
+use arrow_array::{Int32Array, RecordBatch, types};
+use arrow_schema::{DataType, Field, Schema};
+use bytes::Bytes;
 use parquet::arrow::ArrowWriter;
 use parquet::arrow::arrow_reader::{
     ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
 };
+use parquet::basic::Type as PhysicalType;
+use parquet::encryption::backend::RandomSource;
 use parquet::encryption::decrypt::{FileDecryptionProperties, KeyRetriever};
 use parquet::encryption::encrypt::FileEncryptionProperties;
 use parquet::errors::{ParquetError, Result};
 use parquet::file::metadata::ParquetMetaData;
 use parquet::file::properties::WriterProperties;
+use parquet::file::reader::ChunkReader;
 use ring::aead::AES_256_GCM;
 use std::collections::HashMap;
 use std::fs::File;
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::{Arc, Mutex};
 
+/// The message of a [`ParquetError::DecryptionKeyError`] when decrypting with the wrong key
+pub(crate) const AUTHENTICATION_FAILED: &str = "Cryptographic operation failed: open (verify authentication tag and decrypt). \
+    The key may be wrong or the data may have been tampered with";
+
 pub(crate) const AES_128_FOOTER_KEY: &[u8; 16] = b"0123456789012345"; // 128bit/16
 pub(crate) const BAD_AES_128_FOOTER_KEY: &[u8; 16] = b"0000000000000000";
 pub(crate) const AES_128_FOOTER_KEY_NAME: &str = "kf";
@@ -286,8 +296,25 @@ pub(crate) fn verify_column_indexes(metadata: &ParquetMetaData) {
     };
 }
 
-pub(crate) fn read_encrypted_file(
-    file: &File,
+/// A [`ChunkReader`] that can be duplicated to create a record batch reader
+pub(crate) trait TryCloneChunkReader: ChunkReader + Sized + 'static {
+    fn try_clone_reader(&self) -> std::io::Result<Self>;
+}
+
+impl TryCloneChunkReader for File {
+    fn try_clone_reader(&self) -> std::io::Result<Self> {
+        self.try_clone()
+    }
+}
+
+impl TryCloneChunkReader for Bytes {
+    fn try_clone_reader(&self) -> std::io::Result<Self> {
+        Ok(self.clone())
+    }
+}
+
+pub(crate) fn read_encrypted_file<T: TryCloneChunkReader>(
+    file: &T,
     decryption_properties: Arc<FileDecryptionProperties>,
 ) -> std::result::Result<(Vec<RecordBatch>, ArrowReaderMetadata), ParquetError> {
     let options =
@@ -295,7 +322,7 @@ pub(crate) fn read_encrypted_file(
     let metadata = ArrowReaderMetadata::load(file, options.clone())?;
 
     let builder =
-        ParquetRecordBatchReaderBuilder::try_new_with_options(file.try_clone().unwrap(), options)?;
+        ParquetRecordBatchReaderBuilder::try_new_with_options(file.try_clone_reader()?, options)?;
     let batch_reader = builder.build()?;
     let batches = batch_reader.collect::<Result<Vec<RecordBatch>, _>>()?;
     Ok((batches, metadata))
@@ -313,7 +340,7 @@ pub(crate) fn read_and_roundtrip_to_encrypted_file(
     // write example data to a temporary file
     let temp_file = tempfile::tempfile().unwrap();
     let props = WriterProperties::builder()
-        .with_file_encryption_properties(encryption_properties)
+        .with_file_encryption_properties(Arc::clone(&encryption_properties))
         .build();
 
     let mut writer = ArrowWriter::try_new(
@@ -328,10 +355,69 @@ pub(crate) fn read_and_roundtrip_to_encrypted_file(
 
     writer.close().unwrap();
 
-    // check re-written example data
+    // check re-written example data, using keys matching those it was written with
+    let decryption_properties = encryption_properties.to_decryption_properties().unwrap();
     verify_encryption_test_file_read(temp_file, decryption_properties);
 }
 
+/// Create a batch of non-nullable Int32 columns with the given names,
+/// where the column at index `i` contains `i * num_rows..(i + 1) * num_rows`
+pub(crate) fn int32_batch(column_names: &[&str], num_rows: i32) -> RecordBatch {
+    let fields = column_names
+        .iter()
+        .map(|name| Field::new(*name, DataType::Int32, false))
+        .collect::<Vec<_>>();
+    let columns = (0..column_names.len() as i32)
+        .map(|i| {
+            Arc::new(Int32Array::from_iter_values(
+                i * num_rows..(i + 1) * num_rows,
+            )) as _
+        })
+        .collect();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap()
+}
+
+/// A deterministic [`RandomSource`] for reproducible test output, which fills each
+/// request with the next byte of a sequence so that seeds are never repeated.
+/// Never use a deterministic source for real data.
+#[derive(Debug)]
+pub(crate) struct SequenceRandomSource(AtomicU8);
+
+impl SequenceRandomSource {
+    pub(crate) fn new(first: u8) -> Self {
+        Self(AtomicU8::new(first))
+    }
+}
+
+impl RandomSource for SequenceRandomSource {
+    fn fill(&self, dest: &mut [u8]) -> Result<()> {
+        dest.fill(self.0.fetch_add(1, Ordering::Relaxed));
+        Ok(())
+    }
+}
+
+/// Write a batch to an in-memory Parquet file,
+/// encrypted with the given properties if provided
+pub(crate) fn write_batch(
+    batch: &RecordBatch,
+    file_encryption_properties: Option<Arc<FileEncryptionProperties>>,
+) -> Bytes {
+    let mut builder = WriterProperties::builder();
+    if let Some(file_encryption_properties) = file_encryption_properties {
+        builder = builder.with_file_encryption_properties(file_encryption_properties);
+    }
+    write_batch_with_properties(batch, builder.build())
+}
+
+/// Write a batch to an in-memory Parquet file using the given writer properties
+pub(crate) fn write_batch_with_properties(batch: &RecordBatch, props: WriterProperties) -> Bytes {
+    let mut file_bytes = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut file_bytes, batch.schema(), Some(props)).unwrap();
+    writer.write(batch).unwrap();
+    writer.close().unwrap();
+    file_bytes.into()
+}
+
 pub(crate) fn verify_encryption_test_file_read(
     file: File,
     decryption_properties: Arc<FileDecryptionProperties>,
@@ -350,6 +436,98 @@ pub(crate) fn verify_encryption_test_file_read(
     verify_encryption_test_data(record_batches, metadata);
 }
 
+/// The expected structure of an encrypted file, used to verify files written by
+/// other Parquet implementations without relying on their exact data
+pub(crate) struct ExpectedEncryptedFile<'a> {
+    /// Leaf column paths and physical types, in schema order
+    pub columns: Vec<(&'a str, PhysicalType)>,
+    /// Paths of the leaf columns that are expected to be encrypted
+    pub encrypted_columns: Vec<&'a str>,
+    /// Total number of rows in the file
+    pub num_rows: usize,
+}
+
+impl ExpectedEncryptedFile<'_> {
+    /// The structure of the encrypted test files from the parquet-testing repository,
+    /// with the given columns encrypted
+    pub(crate) fn parquet_testing(encrypted_columns: Vec<&str>) -> ExpectedEncryptedFile<'_> {
+        ExpectedEncryptedFile {
+            columns: vec![
+                ("boolean_field", PhysicalType::BOOLEAN),
+                ("int32_field", PhysicalType::INT32),
+                ("int64_field.list.element", PhysicalType::INT64),
+                ("int96_field", PhysicalType::INT96),
+                ("float_field", PhysicalType::FLOAT),
+                ("double_field", PhysicalType::DOUBLE),
+                ("ba_field", PhysicalType::BYTE_ARRAY),
+                ("flba_field", PhysicalType::FIXED_LEN_BYTE_ARRAY),
+            ],
+            encrypted_columns,
+            num_rows: 50,
+        }
+    }
+}
+
+/// Verifies that an encrypted file can be read and matches the expected structure,
+/// returning the decrypted record batches for any further checks
+pub(crate) fn verify_encrypted_file_read(
+    file: File,
+    decryption_properties: Arc<FileDecryptionProperties>,
+    expected: &ExpectedEncryptedFile,
+) -> Vec<RecordBatch> {
+    let options =
+        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+    let builder = ParquetRecordBatchReaderBuilder::try_new_with_options(file, options).unwrap();
+    let metadata = Arc::clone(builder.metadata());
+    let schema = Arc::clone(builder.schema());
+
+    let columns = metadata
+        .file_metadata()
+        .schema_descr()
+        .columns()
+        .iter()
+        .map(|column| (column.path().string(), column.physical_type()))
+        .collect::<Vec<_>>();
+    let expected_columns = expected
+        .columns
+        .iter()
+        .map(|(path, physical_type)| (path.to_string(), *physical_type))
+        .collect::<Vec<_>>();
+    assert_eq!(columns, expected_columns);
+
+    assert_eq!(
+        metadata.file_metadata().num_rows() as usize,
+        expected.num_rows
+    );
+    let mut row_group_rows = 0;
+    for row_group in metadata.row_groups() {
+        assert_eq!(row_group.num_columns(), expected.columns.len());
+        for column in row_group.columns() {
+            let path = column.column_path().string();
+            assert_eq!(
+                column.crypto_metadata().is_some(),
+                expected.encrypted_columns.contains(&path.as_str()),
+                "unexpected encryption state for column {path}"
+            );
+        }
+        row_group_rows += row_group.num_rows() as usize;
+    }
+    assert_eq!(row_group_rows, expected.num_rows);
+
+    let record_batches = builder
+        .build()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    for batch in &record_batches {
+        assert_eq!(batch.schema(), schema);
+    }
+    let num_rows: usize = record_batches.iter().map(|batch| batch.num_rows()).sum();
+    assert_eq!(num_rows, expected.num_rows);
+
+    record_batches
+}
+
 /// A KeyRetriever to use in Parquet encryption tests,
 /// which stores a map from key names/metadata to encryption key bytes.
 pub struct TestKeyRetriever {